@@ -5,9 +5,13 @@ mod note;
 mod note_expression;
 mod param_value;
 mod transport;
+mod trigger;
+mod tuning;
 
 pub use midi::*;
 pub use note::*;
 pub use note_expression::*;
 pub use param_value::*;
 pub use transport::*;
+pub use trigger::*;
+pub use tuning::*;
@@ -0,0 +1,121 @@
+use crate::events::spaces::{EventSpaceId, TriggersEventSpace};
+use crate::events::{impl_event_pckn, Event, EventFlags, EventHeader, Match, Pckn, UnknownEvent};
+use crate::utils::{ClapId, Cookie};
+use clap_sys::ext::draft::triggers::{clap_event_trigger, CLAP_EVENT_TRIGGER};
+use std::fmt::{Debug, Formatter};
+
+/// An event firing one of the plugin's triggers.
+///
+/// Unlike parameter events, trigger events do not belong to the core event space, but to the
+/// [`TriggersEventSpace`], whose ID has to be queried from the host's event registry.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct TriggerEvent {
+    inner: clap_event_trigger,
+}
+
+// SAFETY: this matches the type ID and event space
+unsafe impl Event for TriggerEvent {
+    const TYPE_ID: u16 = CLAP_EVENT_TRIGGER;
+    type EventSpace<'a> = TriggersEventSpace<'a>;
+}
+
+impl AsRef<UnknownEvent> for TriggerEvent {
+    #[inline]
+    fn as_ref(&self) -> &UnknownEvent {
+        self.as_unknown()
+    }
+}
+
+impl TriggerEvent {
+    #[inline]
+    pub const fn new(
+        space_id: EventSpaceId<TriggersEventSpace<'static>>,
+        time: u32,
+        trigger_id: ClapId,
+        pckn: Pckn,
+        cookie: Cookie,
+    ) -> Self {
+        Self {
+            inner: clap_event_trigger {
+                header: EventHeader::<Self>::new_for_space(space_id, time, EventFlags::empty())
+                    .into_raw(),
+                trigger_id: trigger_id.get(),
+                cookie: cookie.as_raw(),
+                note_id: pckn.raw_note_id(),
+                port_index: pckn.raw_port_index(),
+                channel: pckn.raw_channel(),
+                key: pckn.raw_key(),
+            },
+        }
+    }
+
+    #[inline]
+    pub const fn trigger_id(&self) -> Option<ClapId> {
+        ClapId::from_raw(self.inner.trigger_id)
+    }
+
+    #[inline]
+    pub fn set_trigger_id(&mut self, trigger_id: ClapId) {
+        self.inner.trigger_id = trigger_id.get()
+    }
+
+    #[inline]
+    pub const fn with_trigger_id(mut self, trigger_id: ClapId) -> Self {
+        self.inner.trigger_id = trigger_id.get();
+        self
+    }
+
+    #[inline]
+    pub const fn as_raw(&self) -> &clap_event_trigger {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn as_raw_mut(&mut self) -> &mut clap_event_trigger {
+        &mut self.inner
+    }
+
+    impl_event_pckn!();
+
+    #[inline]
+    pub const fn cookie(&self) -> Cookie {
+        Cookie::from_raw(self.inner.cookie)
+    }
+
+    #[inline]
+    pub fn set_cookie(&mut self, cookie: Cookie) {
+        self.inner.cookie = cookie.as_raw()
+    }
+
+    #[inline]
+    pub const fn with_cookie(mut self, cookie: Cookie) -> Self {
+        self.inner.cookie = cookie.as_raw();
+        self
+    }
+}
+
+impl PartialEq for TriggerEvent {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.key == other.inner.key
+            && self.inner.header.time == other.inner.header.time
+            && self.inner.channel == other.inner.channel
+            && self.inner.port_index == other.inner.port_index
+            && self.inner.trigger_id == other.inner.trigger_id
+            && self.inner.note_id == other.inner.note_id
+    }
+}
+
+impl Debug for TriggerEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TriggerEvent")
+            .field("header", &self.header())
+            .field("port_index", &self.inner.port_index)
+            .field("channel", &self.inner.channel)
+            .field("key", &self.inner.key)
+            .field("trigger_id", &self.inner.trigger_id)
+            .field("note_id", &self.inner.note_id)
+            .finish()
+    }
+}
@@ -0,0 +1,134 @@
+use crate::events::spaces::{EventSpaceId, TuningEventSpace};
+use crate::events::{Event, EventFlags, EventHeader, Match, UnknownEvent};
+use crate::utils::ClapId;
+use clap_sys::ext::draft::tuning::clap_event_tuning;
+use std::fmt::{Debug, Formatter};
+
+/// An event selecting the relative tuning to apply to the notes of a given port and channel.
+///
+/// The tuning values themselves have to be retrieved from the host's tuning extension, using the
+/// [`tuning_id`](RelativeTuningEvent::tuning_id) carried by this event.
+///
+/// Unlike note expression events, tuning events do not belong to the core event space, but to
+/// the [`TuningEventSpace`], whose ID has to be queried from the host's event registry.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct RelativeTuningEvent {
+    inner: clap_event_tuning,
+}
+
+// SAFETY: this matches the type ID and event space
+unsafe impl Event for RelativeTuningEvent {
+    // The tuning event is the only event of the tuning event space, and CLAP doesn't define a
+    // type ID constant for it.
+    const TYPE_ID: u16 = 0;
+    type EventSpace<'a> = TuningEventSpace<'a>;
+}
+
+impl AsRef<UnknownEvent> for RelativeTuningEvent {
+    #[inline]
+    fn as_ref(&self) -> &UnknownEvent {
+        self.as_unknown()
+    }
+}
+
+impl RelativeTuningEvent {
+    #[inline]
+    pub const fn new(
+        space_id: EventSpaceId<TuningEventSpace<'static>>,
+        time: u32,
+        port_index: Match<u16>,
+        channel: Match<u16>,
+        tuning_id: Option<ClapId>,
+    ) -> Self {
+        Self {
+            inner: clap_event_tuning {
+                header: EventHeader::<Self>::new_for_space(space_id, time, EventFlags::empty())
+                    .into_raw(),
+                port_index: port_index.to_raw(),
+                channel: channel.to_raw(),
+                tunning_id: ClapId::optional_to_raw(tuning_id),
+            },
+        }
+    }
+
+    #[inline]
+    pub const fn port_index(&self) -> Match<u16> {
+        Match::<u16>::from_raw(self.inner.port_index)
+    }
+
+    #[inline]
+    pub fn set_port_index(&mut self, port_index: Match<u16>) {
+        self.inner.port_index = port_index.to_raw()
+    }
+
+    #[inline]
+    pub const fn with_port_index(mut self, port_index: Match<u16>) -> Self {
+        self.inner.port_index = port_index.to_raw();
+        self
+    }
+
+    #[inline]
+    pub const fn channel(&self) -> Match<u16> {
+        Match::<u16>::from_raw(self.inner.channel)
+    }
+
+    #[inline]
+    pub fn set_channel(&mut self, channel: Match<u16>) {
+        self.inner.channel = channel.to_raw()
+    }
+
+    #[inline]
+    pub const fn with_channel(mut self, channel: Match<u16>) -> Self {
+        self.inner.channel = channel.to_raw();
+        self
+    }
+
+    /// The ID of the tuning to apply, or [`None`] to apply no tuning (i.e. 12-TET).
+    #[inline]
+    pub const fn tuning_id(&self) -> Option<ClapId> {
+        ClapId::from_raw(self.inner.tunning_id)
+    }
+
+    #[inline]
+    pub fn set_tuning_id(&mut self, tuning_id: Option<ClapId>) {
+        self.inner.tunning_id = ClapId::optional_to_raw(tuning_id)
+    }
+
+    #[inline]
+    pub const fn with_tuning_id(mut self, tuning_id: Option<ClapId>) -> Self {
+        self.inner.tunning_id = ClapId::optional_to_raw(tuning_id);
+        self
+    }
+
+    #[inline]
+    pub const fn as_raw(&self) -> &clap_event_tuning {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn as_raw_mut(&mut self) -> &mut clap_event_tuning {
+        &mut self.inner
+    }
+}
+
+impl PartialEq for RelativeTuningEvent {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.header.time == other.inner.header.time
+            && self.inner.port_index == other.inner.port_index
+            && self.inner.channel == other.inner.channel
+            && self.inner.tunning_id == other.inner.tunning_id
+    }
+}
+
+impl Debug for RelativeTuningEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RelativeTuningEvent")
+            .field("header", &self.header())
+            .field("port_index", &self.inner.port_index)
+            .field("channel", &self.inner.channel)
+            .field("tuning_id", &self.inner.tunning_id)
+            .finish()
+    }
+}
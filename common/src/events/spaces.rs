@@ -1,8 +1,12 @@
 mod core;
 mod id;
+mod triggers;
+mod tuning;
 
 pub use self::core::*;
 pub use id::*;
+pub use triggers::*;
+pub use tuning::*;
 
 use crate::events::UnknownEvent;
 use std::ffi::CStr;
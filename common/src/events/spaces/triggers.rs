@@ -0,0 +1,32 @@
+use crate::events::event_types::TriggerEvent;
+use crate::events::{Event, EventSpace, UnknownEvent};
+use clap_sys::ext::draft::triggers::CLAP_EXT_TRIGGERS;
+use std::ffi::CStr;
+
+/// The event space of the triggers extension.
+///
+/// Its ID is not fixed, and has to be queried from the host's event registry using this
+/// space's [`NAME`](EventSpace::NAME).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TriggersEventSpace<'a> {
+    Trigger(&'a TriggerEvent),
+}
+
+// SAFETY: The triggers event space is named after the triggers extension's ID.
+unsafe impl<'a> EventSpace<'a> for TriggersEventSpace<'a> {
+    const NAME: &'static CStr = CLAP_EXT_TRIGGERS;
+
+    unsafe fn from_unknown(event: &'a UnknownEvent) -> Option<Self> {
+        match event.header().type_id() {
+            TriggerEvent::TYPE_ID => Some(Self::Trigger(event.as_event_unchecked())),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn as_unknown(&self) -> &'a UnknownEvent {
+        match self {
+            Self::Trigger(e) => e.as_unknown(),
+        }
+    }
+}
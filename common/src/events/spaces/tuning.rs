@@ -0,0 +1,32 @@
+use crate::events::event_types::RelativeTuningEvent;
+use crate::events::{Event, EventSpace, UnknownEvent};
+use clap_sys::ext::draft::tuning::CLAP_EXT_TUNING;
+use std::ffi::CStr;
+
+/// The event space of the tuning extension.
+///
+/// Its ID is not fixed, and has to be queried from the host's event registry using this
+/// space's [`NAME`](EventSpace::NAME).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TuningEventSpace<'a> {
+    RelativeTuning(&'a RelativeTuningEvent),
+}
+
+// SAFETY: The tuning event space is named after the tuning extension's ID.
+unsafe impl<'a> EventSpace<'a> for TuningEventSpace<'a> {
+    const NAME: &'static CStr = CLAP_EXT_TUNING;
+
+    unsafe fn from_unknown(event: &'a UnknownEvent) -> Option<Self> {
+        match event.header().type_id() {
+            RelativeTuningEvent::TYPE_ID => Some(Self::RelativeTuning(event.as_event_unchecked())),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn as_unknown(&self) -> &'a UnknownEvent {
+        match self {
+            Self::RelativeTuning(e) => e.as_unknown(),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Types and definitions of CLAP factories that are common to both plugins and hosts.
+//!
+//! See the documentation of the `factory` module in the `clack-plugin` and `clack-host` crates
+//! to learn more about factories.
+
+pub mod plugin_state_converter;
+pub mod preset_discovery;
+
+/// Builds a factory identifier from a nul-terminated byte string.
+///
+/// This is used for the factories that are not part of the version of `clap-sys` currently in
+/// use. When used in a `const`, the given string is checked at compile time.
+pub(crate) const fn factory_id(id: &'static [u8]) -> &'static core::ffi::CStr {
+    assert!(
+        !id.is_empty() && id[id.len() - 1] == 0,
+        "Factory ID must be nul-terminated"
+    );
+
+    let mut i = 0;
+    while i < id.len() - 1 {
+        assert!(id[i] != 0, "Factory ID must not contain interior nul bytes");
+        i += 1;
+    }
+
+    // SAFETY: We just checked the string is nul-terminated and has no interior nul bytes.
+    unsafe { core::ffi::CStr::from_bytes_with_nul_unchecked(id) }
+}
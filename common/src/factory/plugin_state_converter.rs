@@ -0,0 +1,100 @@
+//! Types and definitions of the plugin state converter factory, which are common to both plugins
+//! and hosts.
+//!
+//! This factory allows plugins to convert the state (and automation) of another plugin into their
+//! own, e.g. when a plugin's ID changed between major versions, or to migrate from another plugin
+//! ABI.
+
+pub use crate::factory::preset_discovery::PluginId;
+
+/// The C-FFI definitions of the plugin state converter factory.
+///
+/// This factory is not part of the version of `clap-sys` currently in use, and is therefore
+/// defined here, following the CLAP headers.
+#[allow(non_camel_case_types, missing_docs)]
+pub mod sys {
+    use clap_sys::id::clap_id;
+    use clap_sys::stream::{clap_istream, clap_ostream};
+    use clap_sys::version::clap_version;
+    use std::ffi::{c_char, c_void, CStr};
+
+    /// The universal plugin ID has the same layout as the preset discovery plugin ID, which it
+    /// replaces in later versions of the CLAP headers.
+    pub use clap_sys::factory::draft::preset_discovery::clap_plugin_id as clap_universal_plugin_id;
+
+    pub const CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID: &CStr =
+        crate::factory::factory_id(b"clap.plugin-state-converter-factory/1\0");
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_state_converter_descriptor {
+        pub clap_version: clap_version,
+        pub src_plugin_id: clap_universal_plugin_id,
+        pub dst_plugin_id: clap_universal_plugin_id,
+        pub id: *const c_char,
+        pub name: *const c_char,
+        pub vendor: *const c_char,
+        pub version: *const c_char,
+        pub description: *const c_char,
+    }
+
+    // SAFETY: this type only holds pointers to immutable data.
+    unsafe impl Send for clap_plugin_state_converter_descriptor {}
+    // SAFETY: this type only holds pointers to immutable data.
+    unsafe impl Sync for clap_plugin_state_converter_descriptor {}
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_state_converter {
+        pub desc: *const clap_plugin_state_converter_descriptor,
+        pub converter_data: *mut c_void,
+        pub destroy: Option<unsafe extern "C" fn(converter: *mut clap_plugin_state_converter)>,
+        pub convert_state: Option<
+            unsafe extern "C" fn(
+                converter: *mut clap_plugin_state_converter,
+                src: *const clap_istream,
+                dst: *const clap_ostream,
+                error_buffer: *mut c_char,
+                error_buffer_size: usize,
+            ) -> bool,
+        >,
+        pub convert_normalized_value: Option<
+            unsafe extern "C" fn(
+                converter: *mut clap_plugin_state_converter,
+                src_param_id: clap_id,
+                src_normalized_value: f64,
+                dst_param_id: *mut clap_id,
+                dst_normalized_value: *mut f64,
+            ) -> bool,
+        >,
+        pub convert_plain_value: Option<
+            unsafe extern "C" fn(
+                converter: *mut clap_plugin_state_converter,
+                src_param_id: clap_id,
+                src_plain_value: f64,
+                dst_param_id: *mut clap_id,
+                dst_plain_value: *mut f64,
+            ) -> bool,
+        >,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_state_converter_factory {
+        pub count: Option<
+            unsafe extern "C" fn(factory: *const clap_plugin_state_converter_factory) -> u32,
+        >,
+        pub get_descriptor: Option<
+            unsafe extern "C" fn(
+                factory: *const clap_plugin_state_converter_factory,
+                index: u32,
+            ) -> *const clap_plugin_state_converter_descriptor,
+        >,
+        pub create: Option<
+            unsafe extern "C" fn(
+                factory: *const clap_plugin_state_converter_factory,
+                converter_id: *const c_char,
+            ) -> *mut clap_plugin_state_converter,
+        >,
+    }
+}
@@ -0,0 +1,363 @@
+//! Common types for the Preset Discovery factory.
+//!
+//! The Preset Discovery factory allows plugins to expose preset providers, which hosts can use to
+//! index all the presets available to a plugin (e.g. to display them in a preset browser),
+//! without having to instantiate the plugin itself.
+//!
+//! These types describe the data that is exchanged between a preset provider and the host's
+//! indexer: [file types](FileType), [locations](LocationInfo), [soundpacks](Soundpack), and
+//! the various pieces of preset metadata.
+
+use crate::utils::{cstr_from_nullable_ptr, non_empty_cstr};
+use bitflags::bitflags;
+use clap_sys::factory::draft::preset_discovery::*;
+use std::ffi::{c_char, CStr};
+use std::num::NonZeroU64;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+bitflags! {
+    /// Flags describing the origin and status of a location, soundpack, or preset.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Flags: u32 {
+        /// This is for factory or sound-designer content.
+        const IS_FACTORY_CONTENT = CLAP_PRESET_DISCOVERY_IS_FACTORY_CONTENT;
+        /// This is user content.
+        const IS_USER_CONTENT = CLAP_PRESET_DISCOVERY_IS_USER_CONTENT;
+        /// This location is meant for demo presets.
+        const IS_DEMO_CONTENT = CLAP_PRESET_DISCOVERY_IS_DEMO_CONTENT;
+        /// This preset is a user's favorite.
+        const IS_FAVORITE = CLAP_PRESET_DISCOVERY_IS_FAVORITE;
+    }
+}
+
+/// A point in time, expressed as a number of seconds since the UNIX epoch.
+///
+/// CLAP reserves the zero value for unknown timestamps, which Clack represents as
+/// `Option<Timestamp>` instead.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Timestamp(NonZeroU64);
+
+impl Timestamp {
+    /// Creates a timestamp from a number of seconds since the UNIX epoch.
+    ///
+    /// This returns [`None`] if `seconds` is zero, as CLAP uses it for unknown timestamps.
+    #[inline]
+    pub const fn from_secs_since_epoch(seconds: u64) -> Option<Self> {
+        match NonZeroU64::new(seconds) {
+            Some(seconds) => Some(Self(seconds)),
+            None => None,
+        }
+    }
+
+    /// Returns the number of seconds since the UNIX epoch this timestamp represents.
+    #[inline]
+    pub const fn secs_since_epoch(&self) -> u64 {
+        self.0.get()
+    }
+
+    /// Returns this timestamp as a [`SystemTime`].
+    #[inline]
+    pub fn to_system_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.secs_since_epoch())
+    }
+
+    /// Creates a timestamp from a [`SystemTime`], truncated to the second.
+    ///
+    /// This returns [`None`] if the given time is not strictly after the UNIX epoch.
+    #[inline]
+    pub fn from_system_time(time: SystemTime) -> Option<Self> {
+        let since_epoch = time.duration_since(UNIX_EPOCH).ok()?;
+        Self::from_secs_since_epoch(since_epoch.as_secs())
+    }
+
+    /// Creates a timestamp from its raw, C-FFI compatible representation.
+    ///
+    /// This returns [`None`] if the raw timestamp is unknown.
+    #[inline]
+    pub const fn from_raw(raw: clap_timestamp) -> Option<Self> {
+        Self::from_secs_since_epoch(raw)
+    }
+
+    /// Returns the raw, C-FFI compatible representation of an optional timestamp.
+    ///
+    /// A [`None`] value is converted to `CLAP_TIMESTAMP_UNKNOWN`.
+    #[inline]
+    pub const fn optional_to_raw(timestamp: Option<Self>) -> clap_timestamp {
+        match timestamp {
+            Some(timestamp) => timestamp.secs_since_epoch(),
+            None => CLAP_TIMESTAMP_UNKNOWN,
+        }
+    }
+}
+
+/// Where presets can be found.
+///
+/// This is used both by preset providers to declare their locations, and by the Preset Load
+/// extension to identify the location of the preset to load.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Location<'a> {
+    /// The presets are stored in a file or directory, at the given path.
+    File {
+        /// The path of the file or directory.
+        path: &'a CStr,
+    },
+    /// The presets are bundled within the plugin itself (e.g. factory presets).
+    Plugin,
+}
+
+impl<'a> Location<'a> {
+    /// Creates a location from its raw, C-FFI compatible representation.
+    ///
+    /// This returns [`None`] if the location kind is unknown, or if a file location has a null
+    /// path.
+    ///
+    /// # Safety
+    ///
+    /// The given location pointer must either be null, or point to a valid, NUL-terminated C
+    /// string that remains valid for the lifetime `'a`.
+    pub unsafe fn from_raw(
+        kind: clap_preset_discovery_location_kind,
+        location: *const c_char,
+    ) -> Option<Self> {
+        match kind {
+            CLAP_PRESET_DISCOVERY_LOCATION_FILE if !location.is_null() => Some(Self::File {
+                path: CStr::from_ptr(location),
+            }),
+            CLAP_PRESET_DISCOVERY_LOCATION_PLUGIN => Some(Self::Plugin),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw, C-FFI compatible kind of this location.
+    #[inline]
+    pub const fn kind(&self) -> clap_preset_discovery_location_kind {
+        match self {
+            Self::File { .. } => CLAP_PRESET_DISCOVERY_LOCATION_FILE,
+            Self::Plugin => CLAP_PRESET_DISCOVERY_LOCATION_PLUGIN,
+        }
+    }
+
+    /// Returns the raw, C-FFI compatible path of this location.
+    ///
+    /// This is null for [`Location::Plugin`].
+    #[inline]
+    pub const fn location_ptr(&self) -> *const c_char {
+        match self {
+            Self::File { path } => path.as_ptr(),
+            Self::Plugin => core::ptr::null(),
+        }
+    }
+
+    /// Returns the raw, C-FFI compatible representation of this location, as a pair of the
+    /// location kind and the location's path.
+    ///
+    /// The path is null for [`Location::Plugin`].
+    #[inline]
+    pub const fn to_raw(&self) -> (clap_preset_discovery_location_kind, *const c_char) {
+        (self.kind(), self.location_ptr())
+    }
+}
+
+/// A preset location, as declared by a preset provider.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct LocationInfo<'a> {
+    /// Flags that apply to all the presets in this location.
+    pub flags: Flags,
+    /// The user-friendly name of this location.
+    pub name: &'a CStr,
+    /// Where the presets can be found.
+    pub location: Location<'a>,
+}
+
+impl<'a> LocationInfo<'a> {
+    /// Creates location info from its raw, C-FFI compatible representation.
+    ///
+    /// This returns [`None`] if the location has a null name, or if the location itself is
+    /// invalid (see [`Location::from_raw`]).
+    ///
+    /// # Safety
+    ///
+    /// All the non-null string pointers in the given struct must be valid, NUL-terminated C
+    /// strings that remain valid for the lifetime `'a`.
+    pub unsafe fn from_raw(raw: &clap_preset_discovery_location) -> Option<Self> {
+        Some(Self {
+            flags: Flags::from_bits_truncate(raw.flags),
+            name: cstr_from_nullable_ptr(raw.name)?,
+            location: Location::from_raw(raw.kind, raw.location)?,
+        })
+    }
+
+    /// Returns the raw, C-FFI compatible representation of this location info.
+    ///
+    /// The returned struct borrows the strings of this location info.
+    pub fn to_raw(&self) -> clap_preset_discovery_location {
+        let (kind, location) = self.location.to_raw();
+
+        clap_preset_discovery_location {
+            flags: self.flags.bits(),
+            name: self.name.as_ptr(),
+            kind,
+            location,
+        }
+    }
+}
+
+/// A preset file type, as declared by a preset provider.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FileType<'a> {
+    /// The user-friendly name of this file type.
+    pub name: &'a CStr,
+    /// An optional description of this file type.
+    pub description: Option<&'a CStr>,
+    /// The file extension of this file type, without the leading `.`.
+    ///
+    /// If this is [`None`], then any file extension matches.
+    pub file_extension: Option<&'a CStr>,
+}
+
+impl<'a> FileType<'a> {
+    /// Creates a file type from its raw, C-FFI compatible representation.
+    ///
+    /// This returns [`None`] if the file type has a null name. Empty descriptions and file
+    /// extensions are treated as missing.
+    ///
+    /// # Safety
+    ///
+    /// All the non-null string pointers in the given struct must be valid, NUL-terminated C
+    /// strings that remain valid for the lifetime `'a`.
+    pub unsafe fn from_raw(raw: &clap_preset_discovery_filetype) -> Option<Self> {
+        Some(Self {
+            name: cstr_from_nullable_ptr(raw.name)?,
+            description: non_empty_cstr(raw.description),
+            file_extension: non_empty_cstr(raw.file_extension),
+        })
+    }
+
+    /// Returns the raw, C-FFI compatible representation of this file type.
+    ///
+    /// The returned struct borrows the strings of this file type.
+    pub fn to_raw(&self) -> clap_preset_discovery_filetype {
+        clap_preset_discovery_filetype {
+            name: self.name.as_ptr(),
+            description: optional_cstr_ptr(self.description),
+            file_extension: optional_cstr_ptr(self.file_extension),
+        }
+    }
+}
+
+/// A soundpack, i.e. a collection of presets, as declared by a preset provider.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Soundpack<'a> {
+    /// Flags that apply to this soundpack.
+    pub flags: Flags,
+    /// The unique identifier of this soundpack.
+    pub id: &'a CStr,
+    /// The user-friendly name of this soundpack.
+    pub name: &'a CStr,
+    /// An optional description of this soundpack.
+    pub description: Option<&'a CStr>,
+    /// An optional URL to the homepage of this soundpack.
+    pub homepage_url: Option<&'a CStr>,
+    /// The optional name of the vendor of this soundpack.
+    pub vendor: Option<&'a CStr>,
+    /// An optional path to an image representing this soundpack, on disk.
+    pub image_path: Option<&'a CStr>,
+    /// The release date of this soundpack, if known.
+    pub release_timestamp: Option<Timestamp>,
+}
+
+impl<'a> Soundpack<'a> {
+    /// Creates a soundpack from its raw, C-FFI compatible representation.
+    ///
+    /// This returns [`None`] if the soundpack has a null ID or name. Empty optional strings are
+    /// treated as missing.
+    ///
+    /// # Safety
+    ///
+    /// All the non-null string pointers in the given struct must be valid, NUL-terminated C
+    /// strings that remain valid for the lifetime `'a`.
+    pub unsafe fn from_raw(raw: &clap_preset_discovery_soundpack) -> Option<Self> {
+        Some(Self {
+            flags: Flags::from_bits_truncate(raw.flags),
+            id: cstr_from_nullable_ptr(raw.id)?,
+            name: cstr_from_nullable_ptr(raw.name)?,
+            description: non_empty_cstr(raw.description),
+            homepage_url: non_empty_cstr(raw.homepage_url),
+            vendor: non_empty_cstr(raw.vendor),
+            image_path: non_empty_cstr(raw.image_path),
+            release_timestamp: Timestamp::from_raw(raw.release_timestamp),
+        })
+    }
+
+    /// Returns the raw, C-FFI compatible representation of this soundpack.
+    ///
+    /// The returned struct borrows the strings of this soundpack.
+    pub fn to_raw(&self) -> clap_preset_discovery_soundpack {
+        clap_preset_discovery_soundpack {
+            flags: self.flags.bits(),
+            id: self.id.as_ptr(),
+            name: self.name.as_ptr(),
+            description: optional_cstr_ptr(self.description),
+            homepage_url: optional_cstr_ptr(self.homepage_url),
+            vendor: optional_cstr_ptr(self.vendor),
+            image_path: optional_cstr_ptr(self.image_path),
+            release_timestamp: Timestamp::optional_to_raw(self.release_timestamp),
+        }
+    }
+}
+
+/// The identifier of a plugin a preset can be loaded into.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PluginId<'a> {
+    /// The plugin ABI the identifier refers to, e.g. `clap`, `vst3`, etc.
+    pub abi: &'a CStr,
+    /// The identifier of the plugin, in the given ABI.
+    pub id: &'a CStr,
+}
+
+impl<'a> PluginId<'a> {
+    /// The ABI name of CLAP plugins.
+    // SAFETY: there is a null byte in this string.
+    pub const CLAP_ABI: &'static CStr = unsafe { CStr::from_bytes_with_nul_unchecked(b"clap\0") };
+
+    /// Creates an identifier for the CLAP plugin with the given ID.
+    #[inline]
+    pub const fn clap(id: &'a CStr) -> Self {
+        Self {
+            abi: Self::CLAP_ABI,
+            id,
+        }
+    }
+
+    /// Creates a plugin identifier from its raw, C-FFI compatible representation.
+    ///
+    /// This returns [`None`] if either the ABI or ID string is null.
+    ///
+    /// # Safety
+    ///
+    /// All the non-null string pointers in the given struct must be valid, NUL-terminated C
+    /// strings that remain valid for the lifetime `'a`.
+    pub unsafe fn from_raw(raw: &clap_plugin_id) -> Option<Self> {
+        Some(Self {
+            abi: cstr_from_nullable_ptr(raw.abi)?,
+            id: cstr_from_nullable_ptr(raw.id)?,
+        })
+    }
+
+    /// Returns the raw, C-FFI compatible representation of this plugin identifier.
+    ///
+    /// The returned struct borrows the strings of this plugin identifier.
+    #[inline]
+    pub fn to_raw(&self) -> clap_plugin_id {
+        clap_plugin_id {
+            abi: self.abi.as_ptr(),
+            id: self.id.as_ptr(),
+        }
+    }
+}
+
+#[inline]
+fn optional_cstr_ptr(string: Option<&CStr>) -> *const c_char {
+    string.map_or(core::ptr::null(), CStr::as_ptr)
+}
@@ -11,15 +11,17 @@ pub(crate) fn handle_panic<F: FnOnce() -> R, R>(f: F) -> std::thread::Result<R>
     Ok(f())
 }
 
+mod color;
 mod fixed_point;
 mod id;
 mod version;
 
+pub use color::Color;
 pub use fixed_point::*;
 pub use id::ClapId;
 pub use version::ClapVersion;
 
-use std::ffi::c_void;
+use std::ffi::{c_char, c_void, CStr};
 
 /// An opaque pointer for use in e.g. parameter definitions and parameter-related events.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
@@ -54,6 +56,35 @@ unsafe impl Send for Cookie {}
 // SAFETY: Cookies themselves are just pointers, which plugins have to consider as Send + Sync
 unsafe impl Sync for Cookie {}
 
+/// Reads a C string from a pointer that may be null, in which case this returns [`None`].
+///
+/// This is used by all the Clack crates to read the optional strings of CLAP structures.
+///
+/// # Safety
+///
+/// The given pointer must either be null, or point to a valid, NUL-terminated C string that
+/// remains valid for the lifetime `'a`.
+#[inline]
+pub unsafe fn cstr_from_nullable_ptr<'a>(ptr: *const c_char) -> Option<&'a CStr> {
+    match ptr.is_null() {
+        true => None,
+        false => Some(CStr::from_ptr(ptr)),
+    }
+}
+
+/// Reads a C string from a pointer that may be null, in which case this returns [`None`].
+///
+/// Unlike [`cstr_from_nullable_ptr`], this also returns [`None`] if the string is empty, which
+/// CLAP uses for unset optional strings.
+///
+/// # Safety
+///
+/// Same as [`cstr_from_nullable_ptr`].
+#[inline]
+pub unsafe fn non_empty_cstr<'a>(ptr: *const c_char) -> Option<&'a CStr> {
+    cstr_from_nullable_ptr(ptr).filter(|s| !s.is_empty())
+}
+
 /// A safer form of [`core::slice::from_raw_parts`] that returns a properly aligned slice in case
 /// the length is 0.
 ///
@@ -0,0 +1,49 @@
+use clap_sys::color::clap_color;
+
+/// An RGBA color, as used by e.g. track information or parameter indications.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Color {
+    /// The alpha (opacity) component of the color.
+    pub alpha: u8,
+    /// The red component of the color.
+    pub red: u8,
+    /// The green component of the color.
+    pub green: u8,
+    /// The blue component of the color.
+    pub blue: u8,
+}
+
+impl Color {
+    /// Creates a new, fully opaque color from its red, green and blue components.
+    #[inline]
+    pub const fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self {
+            alpha: u8::MAX,
+            red,
+            green,
+            blue,
+        }
+    }
+
+    /// Creates a color from the raw, C-FFI compatible color type.
+    #[inline]
+    pub const fn from_raw(raw: clap_color) -> Self {
+        Self {
+            alpha: raw.alpha,
+            red: raw.red,
+            green: raw.green,
+            blue: raw.blue,
+        }
+    }
+
+    /// Returns this color as the raw, C-FFI compatible color type.
+    #[inline]
+    pub const fn to_raw(self) -> clap_color {
+        clap_color {
+            alpha: self.alpha,
+            red: self.red,
+            green: self.green,
+            blue: self.blue,
+        }
+    }
+}
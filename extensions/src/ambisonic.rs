@@ -0,0 +1,343 @@
+//! Allows the plugin to describe the ambisonic configuration of its audio ports.
+//!
+//! This extension is used together with the [Audio Ports](crate::audio_ports) extension: ports
+//! using the [`AudioPortType::AMBISONIC`](crate::audio_ports::AudioPortType::AMBISONIC) type can
+//! then be queried for their [`AmbisonicConfig`], which describes the ordering and normalization
+//! of the port's channels.
+//!
+//! Plugins notify the host that their ambisonic configurations have changed using the `changed`
+//! call. This can only happen while the plugin is deactivated.
+
+#![deny(missing_docs)]
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::ambisonic::*;
+use std::ffi::CStr;
+
+/// The Plugin-side of the Ambisonic extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginAmbisonic(RawExtension<PluginExtensionSide, clap_plugin_ambisonic>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginAmbisonic {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_AMBISONIC;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Ambisonic extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostAmbisonic(RawExtension<HostExtensionSide, clap_host_ambisonic>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostAmbisonic {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_AMBISONIC;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The ordering of the channels of an ambisonic port.
+#[repr(u32)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum AmbisonicOrdering {
+    /// The Furse-Malham (FuMa) channel ordering.
+    FuMa = CLAP_AMBISONIC_ORDERING_FUMA,
+    /// The Ambisonic Channel Number (ACN) channel ordering.
+    Acn = CLAP_AMBISONIC_ORDERING_ACN,
+}
+
+impl AmbisonicOrdering {
+    /// Reads the ordering from the raw C-FFI-compatible integer type.
+    ///
+    /// This may return [`None`] if the given integer's value doesn't match any known ordering.
+    #[inline]
+    pub const fn from_raw(raw: clap_ambisonic_ordering) -> Option<Self> {
+        match raw {
+            CLAP_AMBISONIC_ORDERING_FUMA => Some(Self::FuMa),
+            CLAP_AMBISONIC_ORDERING_ACN => Some(Self::Acn),
+            _ => None,
+        }
+    }
+
+    /// Returns the ordering as the raw C-FFI-compatible integer type.
+    #[inline]
+    pub const fn to_raw(self) -> clap_ambisonic_ordering {
+        self as _
+    }
+}
+
+/// The normalization of the channels of an ambisonic port.
+#[repr(u32)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum AmbisonicNormalization {
+    /// The maxN normalization.
+    MaxN = CLAP_AMBISONIC_NORMALIZATION_MAXN,
+    /// The Schmidt semi-normalized 3D (SN3D) normalization.
+    Sn3d = CLAP_AMBISONIC_NORMALIZATION_SN3D,
+    /// The full 3D (N3D) normalization.
+    N3d = CLAP_AMBISONIC_NORMALIZATION_N3D,
+    /// The Schmidt semi-normalized 2D (SN2D) normalization.
+    Sn2d = CLAP_AMBISONIC_NORMALIZATION_SN2D,
+    /// The full 2D (N2D) normalization.
+    N2d = CLAP_AMBISONIC_NORMALIZATION_N2D,
+}
+
+impl AmbisonicNormalization {
+    /// Reads the normalization from the raw C-FFI-compatible integer type.
+    ///
+    /// This may return [`None`] if the given integer's value doesn't match any known
+    /// normalization.
+    #[inline]
+    pub const fn from_raw(raw: clap_ambisonic_normalization) -> Option<Self> {
+        match raw {
+            CLAP_AMBISONIC_NORMALIZATION_MAXN => Some(Self::MaxN),
+            CLAP_AMBISONIC_NORMALIZATION_SN3D => Some(Self::Sn3d),
+            CLAP_AMBISONIC_NORMALIZATION_N3D => Some(Self::N3d),
+            CLAP_AMBISONIC_NORMALIZATION_SN2D => Some(Self::Sn2d),
+            CLAP_AMBISONIC_NORMALIZATION_N2D => Some(Self::N2d),
+            _ => None,
+        }
+    }
+
+    /// Returns the normalization as the raw C-FFI-compatible integer type.
+    #[inline]
+    pub const fn to_raw(self) -> clap_ambisonic_normalization {
+        self as _
+    }
+}
+
+/// The configuration of an ambisonic audio port.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct AmbisonicConfig {
+    /// The ordering of the port's channels.
+    pub ordering: AmbisonicOrdering,
+    /// The normalization of the port's channels.
+    pub normalization: AmbisonicNormalization,
+}
+
+impl AmbisonicConfig {
+    /// Reads the configuration from the raw C-FFI-compatible struct.
+    ///
+    /// This may return [`None`] if the given ordering or normalization values are unknown.
+    #[inline]
+    pub const fn from_raw(raw: &clap_ambisonic_config) -> Option<Self> {
+        let Some(ordering) = AmbisonicOrdering::from_raw(raw.ordering) else {
+            return None;
+        };
+
+        let Some(normalization) = AmbisonicNormalization::from_raw(raw.normalization) else {
+            return None;
+        };
+
+        Some(Self {
+            ordering,
+            normalization,
+        })
+    }
+
+    /// Returns the configuration as the raw C-FFI-compatible struct.
+    #[inline]
+    pub const fn to_raw(&self) -> clap_ambisonic_config {
+        clap_ambisonic_config {
+            ordering: self.ordering.to_raw(),
+            normalization: self.normalization.to_raw(),
+        }
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+    use std::mem::MaybeUninit;
+
+    impl PluginAmbisonic {
+        /// Returns `true` if the plugin supports the given ambisonic configuration.
+        pub fn is_config_supported(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            config: AmbisonicConfig,
+        ) -> bool {
+            match plugin.use_extension(&self.0).is_config_supported {
+                None => false,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(is_config_supported) => unsafe {
+                    is_config_supported(plugin.as_raw(), &config.to_raw())
+                },
+            }
+        }
+
+        /// Retrieves the [`AmbisonicConfig`] of the given audio port.
+        ///
+        /// If the plugin failed to provide a configuration, or if it provided a configuration
+        /// unknown to this version of Clack, this returns [`None`].
+        pub fn get_config(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            is_input: bool,
+            port_index: u32,
+        ) -> Option<AmbisonicConfig> {
+            let mut config = MaybeUninit::zeroed();
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let success = unsafe {
+                plugin.use_extension(&self.0).get_config?(
+                    plugin.as_raw(),
+                    is_input,
+                    port_index,
+                    config.as_mut_ptr(),
+                )
+            };
+
+            if !success {
+                return None;
+            }
+
+            // SAFETY: we only read the buffer if the plugin returned a successful state
+            AmbisonicConfig::from_raw(unsafe { config.assume_init_ref() })
+        }
+    }
+
+    /// Implementation of the Host-side of the Ambisonic extension.
+    pub trait HostAmbisonicImpl {
+        /// Informs the host that the plugin's ambisonic configurations have changed, and need to
+        /// be queried again.
+        fn changed(&mut self);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostAmbisonic
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostAmbisonicImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_ambisonic {
+                changed: Some(changed::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn changed<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostAmbisonicImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().changed();
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+
+    impl HostAmbisonic {
+        /// Informs the host that the plugin's ambisonic configurations have changed, and need to
+        /// be queried again.
+        ///
+        /// The plugin *must* be deactivated to call this method.
+        #[inline]
+        pub fn changed(&self, host: &mut HostMainThreadHandle) {
+            if let Some(changed) = host.use_extension(&self.0).changed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { changed(host.as_raw()) }
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Ambisonic extension.
+    pub trait PluginAmbisonicImpl {
+        /// Returns `true` if the plugin supports the given ambisonic configuration.
+        fn is_config_supported(&mut self, config: AmbisonicConfig) -> bool;
+
+        /// Retrieves the [`AmbisonicConfig`] of the given audio port.
+        ///
+        /// If the given port doesn't exist or isn't an ambisonic port, this returns [`None`].
+        fn get_config(&mut self, is_input: bool, port_index: u32) -> Option<AmbisonicConfig>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginAmbisonic
+    where
+        for<'a> P::MainThread<'a>: PluginAmbisonicImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_ambisonic {
+                is_config_supported: Some(is_config_supported::<P>),
+                get_config: Some(get_config::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn is_config_supported<P: Plugin>(
+        plugin: *const clap_plugin,
+        config: *const clap_ambisonic_config,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginAmbisonicImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            let config = config
+                .as_ref()
+                .ok_or(PluginWrapperError::NulPtr("clap_ambisonic_config"))?;
+
+            // Configurations unknown to us are never supported.
+            let Some(config) = AmbisonicConfig::from_raw(config) else {
+                return Ok(false);
+            };
+
+            Ok(plugin.main_thread().as_mut().is_config_supported(config))
+        })
+        .unwrap_or(false)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_config<P: Plugin>(
+        plugin: *const clap_plugin,
+        is_input: bool,
+        port_index: u32,
+        info: *mut clap_ambisonic_config,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginAmbisonicImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            if info.is_null() {
+                return Err(PluginWrapperError::NulPtr("clap_ambisonic_config"));
+            }
+
+            match plugin
+                .main_thread()
+                .as_mut()
+                .get_config(is_input, port_index)
+            {
+                None => Ok(false),
+                Some(config) => {
+                    info.write(config.to_raw());
+                    Ok(true)
+                }
+            }
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -2,6 +2,8 @@ use bitflags::bitflags;
 use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
 use clack_common::utils::ClapId;
 use clap_sys::ext::audio_ports::*;
+use clap_sys::ext::draft::ambisonic::CLAP_PORT_AMBISONIC;
+use clap_sys::ext::draft::surround::CLAP_PORT_SURROUND;
 use std::ffi::CStr;
 use std::fmt::{Debug, Formatter};
 
@@ -19,6 +21,10 @@ pub struct AudioPortType<'a>(pub &'a CStr);
 impl AudioPortType<'_> {
     pub const MONO: AudioPortType<'static> = AudioPortType(CLAP_PORT_MONO);
     pub const STEREO: AudioPortType<'static> = AudioPortType(CLAP_PORT_STEREO);
+    /// A surround port, whose channel layout is described by the Surround extension.
+    pub const SURROUND: AudioPortType<'static> = AudioPortType(CLAP_PORT_SURROUND);
+    /// An ambisonic port, whose channel layout is described by the Ambisonic extension.
+    pub const AMBISONIC: AudioPortType<'static> = AudioPortType(CLAP_PORT_AMBISONIC);
 
     #[inline]
     pub const fn from_channel_count(channel_count: u32) -> Option<Self> {
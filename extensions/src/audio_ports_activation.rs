@@ -0,0 +1,310 @@
+//! Allows the host to activate and deactivate individual audio ports of a plugin.
+//!
+//! Deactivating a port is a way for the host to tell the plugin that a given port is not connected
+//! to anything (e.g. an unused sidechain or auxiliary input), and that the plugin can skip any
+//! processing related to it.
+//!
+//! Audio ports can only be activated or deactivated while the plugin is inactive, unless
+//! the plugin reports it [can activate them while processing](PluginAudioPortsActivationImpl::can_activate_while_processing).
+//!
+//! The host can still provide buffers for a deactivated port: the plugin is expected to treat them
+//! as if they were filled with silence, and to not write to deactivated output ports.
+
+#![deny(missing_docs)]
+
+use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::audio_ports_activation::*;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+
+/// The Plugin-side of the Audio Ports Activation extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginAudioPortsActivation(
+    RawExtension<PluginExtensionSide, clap_plugin_audio_ports_activation>,
+);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginAudioPortsActivation {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_AUDIO_PORTS_ACTIVATION;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// A hint about the sample size the host will use for an audio port once it is activated.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum SampleSize {
+    /// The host did not specify which sample size it is going to use.
+    #[default]
+    Unspecified,
+    /// The host is going to use 32-bit floating point samples.
+    Float32,
+    /// The host is going to use 64-bit floating point samples.
+    Float64,
+}
+
+impl SampleSize {
+    /// Returns the sample size as the raw C-FFI-compatible integer type, i.e. its size in bits.
+    #[inline]
+    pub const fn to_raw(&self) -> u32 {
+        match self {
+            SampleSize::Unspecified => 0,
+            SampleSize::Float32 => 32,
+            SampleSize::Float64 => 64,
+        }
+    }
+
+    /// Reads the sample size from the raw C-FFI-compatible integer type.
+    ///
+    /// This may return [`None`] if the given integer's value doesn't match any known sample size.
+    #[inline]
+    pub const fn from_raw(raw: u32) -> Option<Self> {
+        match raw {
+            0 => Some(SampleSize::Unspecified),
+            32 => Some(SampleSize::Float32),
+            64 => Some(SampleSize::Float64),
+            _ => None,
+        }
+    }
+}
+
+/// An error that occurs when the plugin either declined or failed to change the activation state
+/// of an audio port.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct AudioPortActivationError;
+
+impl Display for AudioPortActivationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Failed to change audio port activation state.")
+    }
+}
+
+impl Error for AudioPortActivationError {}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+
+    impl PluginAudioPortsActivation {
+        /// Returns `true` if the plugin supports activating and deactivating audio ports while
+        /// it is processing, from the audio thread.
+        #[inline]
+        pub fn can_activate_while_processing(&self, plugin: &mut PluginMainThreadHandle) -> bool {
+            match plugin.use_extension(&self.0).can_activate_while_processing {
+                None => false,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(can_activate_while_processing) => unsafe {
+                    can_activate_while_processing(plugin.as_raw())
+                },
+            }
+        }
+
+        /// Activates or deactivates the given audio port.
+        ///
+        /// This must only be called while the plugin is inactive. To change the activation state
+        /// of ports while the plugin is processing, use
+        /// [`set_active_while_processing`](Self::set_active_while_processing) instead.
+        ///
+        /// # Errors
+        ///
+        /// This may return an [`AudioPortActivationError`] if the given port doesn't exist, or if
+        /// the plugin either declined or failed to change its activation state.
+        pub fn set_active(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            is_input: bool,
+            port_index: u32,
+            is_active: bool,
+            sample_size: SampleSize,
+        ) -> Result<(), AudioPortActivationError> {
+            // SAFETY: This type ensures the function pointer is valid.
+            let success = unsafe {
+                plugin
+                    .use_extension(&self.0)
+                    .set_active
+                    .ok_or(AudioPortActivationError)?(
+                    plugin.as_raw(),
+                    is_input,
+                    port_index,
+                    is_active,
+                    sample_size.to_raw(),
+                )
+            };
+
+            match success {
+                true => Ok(()),
+                false => Err(AudioPortActivationError),
+            }
+        }
+
+        /// Activates or deactivates the given audio port, while the plugin is processing.
+        ///
+        /// This is only allowed if the plugin
+        /// [can activate ports while processing](Self::can_activate_while_processing).
+        ///
+        /// # Errors
+        ///
+        /// This may return an [`AudioPortActivationError`] if the given port doesn't exist, or if
+        /// the plugin either declined or failed to change its activation state.
+        pub fn set_active_while_processing(
+            &self,
+            plugin: &mut PluginAudioProcessorHandle,
+            is_input: bool,
+            port_index: u32,
+            is_active: bool,
+            sample_size: SampleSize,
+        ) -> Result<(), AudioPortActivationError> {
+            // SAFETY: This type ensures the function pointer is valid.
+            let success = unsafe {
+                plugin
+                    .use_extension(&self.0)
+                    .set_active
+                    .ok_or(AudioPortActivationError)?(
+                    plugin.as_raw(),
+                    is_input,
+                    port_index,
+                    is_active,
+                    sample_size.to_raw(),
+                )
+            };
+
+            match success {
+                true => Ok(()),
+                false => Err(AudioPortActivationError),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+
+    /// Implementation of the Plugin-side of the Audio Ports Activation extension.
+    ///
+    /// This is called on the main thread, while the plugin is inactive.
+    pub trait PluginAudioPortsActivationImpl {
+        /// Returns `true` if the plugin supports activating and deactivating audio ports while
+        /// it is processing.
+        ///
+        /// If this returns `true`, the host may call
+        /// [`set_active`](PluginAudioPortsActivationAudioProcessorImpl::set_active) on the audio
+        /// thread while the plugin is active.
+        fn can_activate_while_processing(&mut self) -> bool;
+
+        /// Activates or deactivates the given audio port.
+        ///
+        /// The `sample_size` is a hint about the sample size the host will use for that port
+        /// once it is active.
+        ///
+        /// # Errors
+        ///
+        /// This may return an error if the given port doesn't exist, or if the plugin either
+        /// declined or failed to change its activation state.
+        fn set_active(
+            &mut self,
+            is_input: bool,
+            port_index: u32,
+            is_active: bool,
+            sample_size: SampleSize,
+        ) -> Result<(), PluginError>;
+    }
+
+    /// Implementation of the audio-thread part of the Plugin-side of the Audio Ports Activation
+    /// extension.
+    ///
+    /// This is called on the audio thread, while the plugin is active. This only happens if the
+    /// plugin [can activate ports while processing](PluginAudioPortsActivationImpl::can_activate_while_processing).
+    pub trait PluginAudioPortsActivationAudioProcessorImpl {
+        /// Activates or deactivates the given audio port, while the plugin is processing.
+        ///
+        /// The `sample_size` is a hint about the sample size the host will use for that port
+        /// once it is active.
+        ///
+        /// # Errors
+        ///
+        /// This may return an error if the given port doesn't exist, or if the plugin either
+        /// declined or failed to change its activation state.
+        fn set_active(
+            &mut self,
+            is_input: bool,
+            port_index: u32,
+            is_active: bool,
+            sample_size: SampleSize,
+        ) -> Result<(), PluginError>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginAudioPortsActivation
+    where
+        for<'a> P::MainThread<'a>: PluginAudioPortsActivationImpl,
+        for<'a> P::AudioProcessor<'a>: PluginAudioPortsActivationAudioProcessorImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_audio_ports_activation {
+                can_activate_while_processing: Some(can_activate_while_processing::<P>),
+                set_active: Some(set_active::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn can_activate_while_processing<P: Plugin>(
+        plugin: *const clap_plugin,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginAudioPortsActivationImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            Ok(plugin
+                .main_thread()
+                .as_mut()
+                .can_activate_while_processing())
+        })
+        .unwrap_or(false)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn set_active<P: Plugin>(
+        plugin: *const clap_plugin,
+        is_input: bool,
+        port_index: u32,
+        is_active: bool,
+        sample_size: u32,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginAudioPortsActivationImpl,
+        for<'a> P::AudioProcessor<'a>: PluginAudioPortsActivationAudioProcessorImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            let sample_size = SampleSize::from_raw(sample_size)
+                .ok_or(PluginWrapperError::InvalidParameter("Invalid sample_size"))?;
+
+            let result = if let Ok(mut audio) = plugin.audio_processor() {
+                audio
+                    .as_mut()
+                    .set_active(is_input, port_index, is_active, sample_size)
+            } else {
+                plugin.main_thread().as_mut().set_active(
+                    is_input,
+                    port_index,
+                    is_active,
+                    sample_size,
+                )
+            };
+
+            Ok(result.is_ok())
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
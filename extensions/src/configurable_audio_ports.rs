@@ -0,0 +1,161 @@
+#![deny(missing_docs)]
+
+//! A way for the host to request arbitrary audio port layouts from the plugin.
+//!
+//! Unlike the [Audio Ports Configurations](crate::audio_ports_config) extension, which only lets
+//! the host pick from a fixed list of configurations offered by the plugin, this extension allows
+//! the host to propose a channel count and port type for each port it wants to configure, along
+//! with optional port-type-specific details (e.g. a surround channel map or an ambisonic
+//! configuration).
+//!
+//! The host can first ask whether the plugin [can apply](PluginConfigurableAudioPortsImpl::can_apply_configuration)
+//! a given set of [`AudioPortConfigurationRequest`]s, and then actually
+//! [apply](PluginConfigurableAudioPortsImpl::apply_configuration) them.
+//!
+//! Configurations can only be applied while the plugin is deactivated.
+
+use crate::ambisonic::AmbisonicConfig;
+use crate::audio_ports::AudioPortType;
+use crate::surround::SurroundChannelMap;
+use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::configurable_audio_ports::*;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+
+/// The Plugin-side of the Configurable Audio Ports extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginConfigurableAudioPorts(
+    RawExtension<PluginExtensionSide, clap_plugin_configurable_audio_ports>,
+);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginConfigurableAudioPorts {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_CONFIGURABLE_AUDIO_PORTS;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// Extra, port-type-specific information that can be attached to an
+/// [`AudioPortConfigurationRequest`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum AudioPortRequestDetails<'a> {
+    /// No additional information is given about the requested port.
+    #[default]
+    None,
+    /// The channel map of a surround port.
+    ///
+    /// It must contain exactly one speaker position per channel of the port.
+    Surround(SurroundChannelMap<'a>),
+    /// The configuration of an ambisonic port.
+    Ambisonic(AmbisonicConfig),
+}
+
+/// A request from the host to configure a single audio port of the plugin.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct AudioPortConfigurationRequest<'a> {
+    /// Whether the port to configure is an input port or an output port.
+    pub is_input: bool,
+    /// The index of the port to configure.
+    pub port_index: u32,
+    /// The number of channels requested for the port.
+    pub channel_count: u32,
+    /// The type requested for the port, if any.
+    pub port_type: Option<AudioPortType<'a>>,
+    /// Additional details about the requested port layout.
+    pub details: AudioPortRequestDetails<'a>,
+}
+
+#[cfg(feature = "clack-plugin")]
+impl<'a> AudioPortConfigurationRequest<'a> {
+    /// # Safety
+    ///
+    /// The user must ensure the port type and details pointers are either null or valid for reads
+    /// for the lifetime `'a`. If the port type is surround, the details pointer must point to
+    /// `channel_count` bytes. If the port type is ambisonic, it must point to a valid
+    /// `clap_ambisonic_config`.
+    ///
+    /// Ambisonic configurations unknown to this version of Clack are reported as
+    /// [`AudioPortRequestDetails::None`].
+    unsafe fn from_raw(raw: &'a clap_audio_port_configuration_request) -> Self {
+        let port_type = if raw.port_type.is_null() {
+            None
+        } else {
+            Some(AudioPortType(CStr::from_ptr(raw.port_type))).filter(|t| !t.0.is_empty())
+        };
+
+        Self {
+            is_input: raw.is_input,
+            port_index: raw.port_index,
+            channel_count: raw.channel_count,
+            port_type,
+            details: AudioPortRequestDetails::from_raw(
+                port_type,
+                raw.channel_count,
+                raw.port_details,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+impl<'a> AudioPortRequestDetails<'a> {
+    /// # Safety
+    ///
+    /// The user must ensure the details pointer is either null or valid for reads for the
+    /// lifetime `'a`. If the port type is surround, it must point to `channel_count` bytes. If the
+    /// port type is ambisonic, it must point to a valid `clap_ambisonic_config`.
+    ///
+    /// Ambisonic configurations unknown to this version of Clack are reported as
+    /// [`AudioPortRequestDetails::None`].
+    pub(crate) unsafe fn from_raw(
+        port_type: Option<AudioPortType>,
+        channel_count: u32,
+        port_details: *const core::ffi::c_void,
+    ) -> Self {
+        use crate::utils::slice_from_external_parts;
+        use clap_sys::ext::draft::ambisonic::clap_ambisonic_config;
+
+        match port_type {
+            _ if port_details.is_null() => Self::None,
+            Some(t) if t == AudioPortType::SURROUND => {
+                Self::Surround(SurroundChannelMap::from_raw(slice_from_external_parts(
+                    port_details.cast(),
+                    channel_count as usize,
+                )))
+            }
+            Some(t) if t == AudioPortType::AMBISONIC => {
+                AmbisonicConfig::from_raw(&*port_details.cast::<clap_ambisonic_config>())
+                    .map(Self::Ambisonic)
+                    .unwrap_or_default()
+            }
+            _ => Self::None,
+        }
+    }
+}
+
+/// An error that can occur as a plugin applies a new audio port configuration.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct AudioPortConfigApplyError;
+
+impl Display for AudioPortConfigApplyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Failed to apply plugin audio ports configuration.")
+    }
+}
+
+impl Error for AudioPortConfigApplyError {}
+
+#[cfg(feature = "clack-host")]
+mod host;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin;
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
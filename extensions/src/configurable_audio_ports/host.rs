@@ -0,0 +1,136 @@
+use super::*;
+use clack_host::extensions::prelude::*;
+use clap_sys::ext::draft::ambisonic::clap_ambisonic_config;
+
+/// The raw C-FFI-compatible form of a list of [`AudioPortConfigurationRequest`]s.
+///
+/// The raw requests may point into the `ambisonic_configs` buffer, which must therefore never be
+/// modified once the requests have been created.
+struct RawRequests {
+    requests: Vec<clap_audio_port_configuration_request>,
+    _ambisonic_configs: Vec<clap_ambisonic_config>,
+}
+
+impl RawRequests {
+    /// Returns [`None`] if any of the given requests has a surround channel map whose length
+    /// doesn't match its channel count.
+    fn new(requests: &[AudioPortConfigurationRequest]) -> Option<Self> {
+        let ambisonic_configs: Vec<_> = requests
+            .iter()
+            .map(|request| match request.details {
+                AudioPortRequestDetails::Ambisonic(config) => config.to_raw(),
+                _ => clap_ambisonic_config {
+                    ordering: 0,
+                    normalization: 0,
+                },
+            })
+            .collect();
+
+        let raw_requests = requests
+            .iter()
+            .zip(&ambisonic_configs)
+            .map(|(request, ambisonic_config)| {
+                let port_details = match request.details {
+                    AudioPortRequestDetails::None => core::ptr::null(),
+                    AudioPortRequestDetails::Surround(channel_map) => {
+                        if channel_map.len() != request.channel_count as usize {
+                            return None;
+                        }
+
+                        channel_map.as_raw().as_ptr().cast()
+                    }
+                    AudioPortRequestDetails::Ambisonic(_) => {
+                        (ambisonic_config as *const clap_ambisonic_config).cast()
+                    }
+                };
+
+                Some(clap_audio_port_configuration_request {
+                    is_input: request.is_input,
+                    port_index: request.port_index,
+                    channel_count: request.channel_count,
+                    port_type: request
+                        .port_type
+                        .map(|t| t.0.as_ptr())
+                        .unwrap_or(core::ptr::null()),
+                    port_details,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            requests: raw_requests,
+            _ambisonic_configs: ambisonic_configs,
+        })
+    }
+}
+
+impl PluginConfigurableAudioPorts {
+    /// Returns `true` if the plugin is able to apply the given configuration requests.
+    ///
+    /// This does not change the plugin's configuration. The plugin *must* be deactivated to call
+    /// this method.
+    ///
+    /// This always returns `false` if any of the given requests has a surround channel map whose
+    /// length doesn't match its channel count.
+    pub fn can_apply_configuration(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        requests: &[AudioPortConfigurationRequest],
+    ) -> bool {
+        let Some(can_apply_configuration) = plugin.use_extension(&self.0).can_apply_configuration
+        else {
+            return false;
+        };
+
+        let Some(raw) = RawRequests::new(requests) else {
+            return false;
+        };
+
+        // SAFETY: This type ensures the function pointer is valid. The raw requests are valid
+        // for the duration of the call.
+        unsafe {
+            can_apply_configuration(
+                plugin.as_raw(),
+                raw.requests.as_ptr(),
+                raw.requests.len() as u32,
+            )
+        }
+    }
+
+    /// Requests the plugin to apply the given configuration requests.
+    ///
+    /// The plugin *must* be deactivated to call this method.
+    ///
+    /// # Errors
+    ///
+    /// This may return an [`AudioPortConfigApplyError`] if the plugin declined or failed to
+    /// apply the given configuration, or if any of the given requests has a surround channel map
+    /// whose length doesn't match its channel count.
+    pub fn apply_configuration(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        requests: &[AudioPortConfigurationRequest],
+    ) -> Result<(), AudioPortConfigApplyError> {
+        let apply_configuration = plugin
+            .use_extension(&self.0)
+            .apply_configuration
+            .ok_or(AudioPortConfigApplyError)?;
+
+        let raw = RawRequests::new(requests).ok_or(AudioPortConfigApplyError)?;
+
+        // SAFETY: This type ensures the function pointer is valid. The raw requests are valid
+        // for the duration of the call.
+        let success = unsafe {
+            apply_configuration(
+                plugin.as_raw(),
+                raw.requests.as_ptr(),
+                raw.requests.len() as u32,
+            )
+        };
+
+        match success {
+            true => Ok(()),
+            false => Err(AudioPortConfigApplyError),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use super::*;
+use crate::utils::slice_from_external_parts;
+use clack_plugin::extensions::prelude::*;
+
+/// A list of [`AudioPortConfigurationRequest`]s, as received from the host.
+#[derive(Copy, Clone)]
+pub struct AudioPortConfigurationRequests<'a> {
+    raw: &'a [clap_audio_port_configuration_request],
+}
+
+impl<'a> AudioPortConfigurationRequests<'a> {
+    /// # Safety
+    ///
+    /// The user must ensure the provided pointer is valid for reads of `count` requests for the
+    /// lifetime `'a`, and that every one of those requests is valid.
+    #[inline]
+    unsafe fn from_raw(raw: *const clap_audio_port_configuration_request, count: u32) -> Self {
+        Self {
+            raw: slice_from_external_parts(raw, count as usize),
+        }
+    }
+
+    /// Returns the number of requests in this list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.raw.len()
+    }
+
+    /// Returns `true` if this list contains no requests.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.raw.is_empty()
+    }
+
+    /// Returns the request at the given index, or [`None`] if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<AudioPortConfigurationRequest<'a>> {
+        // SAFETY: this type ensures all requests are valid for 'a.
+        self.raw
+            .get(index)
+            .map(|raw| unsafe { AudioPortConfigurationRequest::from_raw(raw) })
+    }
+
+    /// Returns an iterator over all the requests in this list.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = AudioPortConfigurationRequest<'a>> + 'a {
+        // SAFETY: this type ensures all requests are valid for 'a.
+        self.raw
+            .iter()
+            .map(|raw| unsafe { AudioPortConfigurationRequest::from_raw(raw) })
+    }
+}
+
+/// Implementation of the Plugin-side of the Configurable Audio Ports extension.
+pub trait PluginConfigurableAudioPortsImpl {
+    /// Returns `true` if the plugin is able to apply the given configuration requests.
+    ///
+    /// This must not change the plugin's configuration.
+    fn can_apply_configuration(&mut self, requests: &AudioPortConfigurationRequests) -> bool;
+
+    /// Applies the given configuration requests.
+    ///
+    /// The plugin *must* be deactivated to call this method.
+    ///
+    /// # Errors
+    ///
+    /// This may return an error if the plugin declined or failed to apply the given
+    /// configuration. In that case, the plugin's configuration must be left unchanged.
+    fn apply_configuration(
+        &mut self,
+        requests: &AudioPortConfigurationRequests,
+    ) -> Result<(), PluginError>;
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginConfigurableAudioPorts
+where
+    for<'a> P::MainThread<'a>: PluginConfigurableAudioPortsImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_plugin_configurable_audio_ports {
+            can_apply_configuration: Some(can_apply_configuration::<P>),
+            apply_configuration: Some(apply_configuration::<P>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn can_apply_configuration<P: Plugin>(
+    plugin: *const clap_plugin,
+    requests: *const clap_audio_port_configuration_request,
+    request_count: u32,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginConfigurableAudioPortsImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        if p.is_active() {
+            return Err(PluginWrapperError::DeactivationRequiredForFunction(
+                "clap_plugin_configurable_audio_ports.can_apply_configuration",
+            ));
+        }
+
+        if requests.is_null() && request_count > 0 {
+            return Err(PluginWrapperError::NulPtr(
+                "clap_audio_port_configuration_request",
+            ));
+        }
+
+        let requests = AudioPortConfigurationRequests::from_raw(requests, request_count);
+
+        Ok(p.main_thread().as_mut().can_apply_configuration(&requests))
+    })
+    .unwrap_or(false)
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn apply_configuration<P: Plugin>(
+    plugin: *const clap_plugin,
+    requests: *const clap_audio_port_configuration_request,
+    request_count: u32,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginConfigurableAudioPortsImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        if p.is_active() {
+            return Err(PluginWrapperError::DeactivationRequiredForFunction(
+                "clap_plugin_configurable_audio_ports.apply_configuration",
+            ));
+        }
+
+        if requests.is_null() && request_count > 0 {
+            return Err(PluginWrapperError::NulPtr(
+                "clap_audio_port_configuration_request",
+            ));
+        }
+
+        let requests = AudioPortConfigurationRequests::from_raw(requests, request_count);
+
+        Ok(p.main_thread()
+            .as_mut()
+            .apply_configuration(&requests)
+            .is_ok())
+    })
+    .unwrap_or(false)
+}
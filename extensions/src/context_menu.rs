@@ -0,0 +1,569 @@
+#![deny(missing_docs)]
+
+//! Allows plugins and hosts to insert items into each other's context menus.
+//!
+//! Both sides can ask the other to populate a context menu for a given [`ContextMenuTarget`]
+//! (either the whole plugin or a specific parameter), and then to perform the action that was
+//! selected by the user.
+//!
+//! Menus are built through a [`ContextMenuBuilder`], which wraps an implementation of the
+//! [`ContextMenuBuilderImpl`] trait provided by the side that displays the menu.
+//!
+//! Additionally, plugins can ask the host to pop up its own context menu at a given position.
+
+use crate::utils::handle_panic;
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clack_common::utils::{cstr_from_nullable_ptr, ClapId};
+use clap_sys::ext::draft::context_menu::*;
+use std::error::Error;
+use std::ffi::{c_void, CStr};
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+
+/// The Plugin-side of the Context Menu extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginContextMenu(RawExtension<PluginExtensionSide, clap_plugin_context_menu>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginContextMenu {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_CONTEXT_MENU;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Context Menu extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostContextMenu(RawExtension<HostExtensionSide, clap_host_context_menu>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostContextMenu {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_CONTEXT_MENU;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The element a context menu is being built or performed for.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ContextMenuTarget {
+    /// The context menu applies to the whole plugin.
+    Global,
+    /// The context menu applies to the parameter with the given ID.
+    Param(ClapId),
+}
+
+impl ContextMenuTarget {
+    /// Reads the target from the raw C-FFI-compatible struct.
+    ///
+    /// This returns [`None`] if the target kind is unknown, or if the parameter ID is invalid.
+    #[inline]
+    pub const fn from_raw(raw: &clap_context_menu_target) -> Option<Self> {
+        match raw.kind {
+            CLAP_CONTEXT_MENU_TARGET_KIND_GLOBAL => Some(Self::Global),
+            CLAP_CONTEXT_MENU_TARGET_KIND_PARAM => match ClapId::from_raw(raw.id) {
+                Some(id) => Some(Self::Param(id)),
+                None => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the target as the raw C-FFI-compatible struct.
+    #[inline]
+    pub const fn to_raw(&self) -> clap_context_menu_target {
+        match self {
+            Self::Global => clap_context_menu_target {
+                kind: CLAP_CONTEXT_MENU_TARGET_KIND_GLOBAL,
+                id: 0,
+            },
+            Self::Param(id) => clap_context_menu_target {
+                kind: CLAP_CONTEXT_MENU_TARGET_KIND_PARAM,
+                id: id.get(),
+            },
+        }
+    }
+
+    /// Reads the target from a raw pointer, as received in a context menu callback.
+    ///
+    /// Per the CLAP specification, a null target means the menu applies to the whole plugin.
+    ///
+    /// # Safety
+    ///
+    /// The given pointer must either be null or valid for reads.
+    #[cfg(any(feature = "clack-host", feature = "clack-plugin"))]
+    unsafe fn from_raw_ptr(raw: *const clap_context_menu_target) -> Option<Self> {
+        match raw.as_ref() {
+            None => Some(Self::Global),
+            Some(raw) => Self::from_raw(raw),
+        }
+    }
+}
+
+/// The kind of a [`ContextMenuItem`].
+#[repr(u32)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ContextMenuItemKind {
+    /// A simple clickable entry. See [`ContextMenuItem::Entry`].
+    Entry = CLAP_CONTEXT_MENU_ITEM_ENTRY,
+    /// A clickable entry with a checkmark. See [`ContextMenuItem::CheckEntry`].
+    CheckEntry = CLAP_CONTEXT_MENU_ITEM_CHECK_ENTRY,
+    /// A separator line. See [`ContextMenuItem::Separator`].
+    Separator = CLAP_CONTEXT_MENU_ITEM_SEPARATOR,
+    /// The start of a submenu. See [`ContextMenuItem::BeginSubmenu`].
+    BeginSubmenu = CLAP_CONTEXT_MENU_ITEM_BEGIN_SUBMENU,
+    /// The end of a submenu. See [`ContextMenuItem::EndSubmenu`].
+    EndSubmenu = CLAP_CONTEXT_MENU_ITEM_END_SUBMENU,
+    /// A title. See [`ContextMenuItem::Title`].
+    Title = CLAP_CONTEXT_MENU_ITEM_TITLE,
+}
+
+impl ContextMenuItemKind {
+    /// Reads the item kind from the raw C-FFI-compatible integer type.
+    ///
+    /// This may return [`None`] if the given integer's value doesn't match any known item kind.
+    #[inline]
+    pub const fn from_raw(raw: clap_context_menu_item_kind) -> Option<Self> {
+        match raw {
+            CLAP_CONTEXT_MENU_ITEM_ENTRY => Some(Self::Entry),
+            CLAP_CONTEXT_MENU_ITEM_CHECK_ENTRY => Some(Self::CheckEntry),
+            CLAP_CONTEXT_MENU_ITEM_SEPARATOR => Some(Self::Separator),
+            CLAP_CONTEXT_MENU_ITEM_BEGIN_SUBMENU => Some(Self::BeginSubmenu),
+            CLAP_CONTEXT_MENU_ITEM_END_SUBMENU => Some(Self::EndSubmenu),
+            CLAP_CONTEXT_MENU_ITEM_TITLE => Some(Self::Title),
+            _ => None,
+        }
+    }
+
+    /// Returns the item kind as the raw C-FFI-compatible integer type.
+    #[inline]
+    pub const fn to_raw(self) -> clap_context_menu_item_kind {
+        self as _
+    }
+}
+
+/// An item of a context menu.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ContextMenuItem<'a> {
+    /// A simple clickable entry.
+    Entry {
+        /// The user-facing label of the entry.
+        label: &'a CStr,
+        /// Whether the entry can be clicked.
+        is_enabled: bool,
+        /// The ID of the action to perform when the entry is clicked.
+        action_id: ClapId,
+    },
+    /// A clickable entry with a checkmark.
+    CheckEntry {
+        /// The user-facing label of the entry.
+        label: &'a CStr,
+        /// Whether the entry can be clicked.
+        is_enabled: bool,
+        /// Whether the entry is currently checked.
+        is_checked: bool,
+        /// The ID of the action to perform when the entry is clicked.
+        action_id: ClapId,
+    },
+    /// A separator line.
+    Separator,
+    /// The start of a submenu. All following items belong to it, until the matching
+    /// [`EndSubmenu`](ContextMenuItem::EndSubmenu) item.
+    BeginSubmenu {
+        /// The user-facing label of the submenu.
+        label: &'a CStr,
+        /// Whether the submenu can be opened.
+        is_enabled: bool,
+    },
+    /// The end of the current submenu.
+    EndSubmenu,
+    /// A title, which cannot be clicked.
+    Title {
+        /// The user-facing title.
+        title: &'a CStr,
+        /// Whether the title is displayed as enabled.
+        is_enabled: bool,
+    },
+}
+
+impl<'a> ContextMenuItem<'a> {
+    /// Returns the kind of this item.
+    #[inline]
+    pub const fn kind(&self) -> ContextMenuItemKind {
+        match self {
+            Self::Entry { .. } => ContextMenuItemKind::Entry,
+            Self::CheckEntry { .. } => ContextMenuItemKind::CheckEntry,
+            Self::Separator => ContextMenuItemKind::Separator,
+            Self::BeginSubmenu { .. } => ContextMenuItemKind::BeginSubmenu,
+            Self::EndSubmenu => ContextMenuItemKind::EndSubmenu,
+            Self::Title { .. } => ContextMenuItemKind::Title,
+        }
+    }
+
+    /// Reads an item from its raw kind and C-FFI-compatible data.
+    ///
+    /// This returns [`None`] if the item kind is unknown, or if any of its fields is invalid.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the given data pointer is either null or points to the struct
+    /// matching the given item kind, and that all of its string pointers are either null or valid
+    /// for the lifetime `'a`.
+    unsafe fn from_raw(kind: clap_context_menu_item_kind, data: *const c_void) -> Option<Self> {
+        match ContextMenuItemKind::from_raw(kind)? {
+            ContextMenuItemKind::Entry => {
+                let raw = data.cast::<clap_context_menu_entry>().as_ref()?;
+
+                Some(Self::Entry {
+                    label: cstr_from_nullable_ptr(raw.label)?,
+                    is_enabled: raw.is_enabled,
+                    action_id: ClapId::from_raw(raw.action_id)?,
+                })
+            }
+            ContextMenuItemKind::CheckEntry => {
+                let raw = data.cast::<clap_context_menu_check_entry>().as_ref()?;
+
+                Some(Self::CheckEntry {
+                    label: cstr_from_nullable_ptr(raw.label)?,
+                    is_enabled: raw.is_enabled,
+                    is_checked: raw.is_checked,
+                    action_id: ClapId::from_raw(raw.action_id)?,
+                })
+            }
+            ContextMenuItemKind::Separator => Some(Self::Separator),
+            ContextMenuItemKind::BeginSubmenu => {
+                let raw = data.cast::<clap_context_menu_submenu>().as_ref()?;
+
+                Some(Self::BeginSubmenu {
+                    label: cstr_from_nullable_ptr(raw.label)?,
+                    is_enabled: raw.is_enabled,
+                })
+            }
+            ContextMenuItemKind::EndSubmenu => Some(Self::EndSubmenu),
+            ContextMenuItemKind::Title => {
+                let raw = data.cast::<clap_context_menu_item_title>().as_ref()?;
+
+                Some(Self::Title {
+                    title: cstr_from_nullable_ptr(raw.title)?,
+                    is_enabled: raw.is_enabled,
+                })
+            }
+        }
+    }
+}
+
+/// Errors that can occur while building or using context menus.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ContextMenuError {
+    /// The menu builder failed or declined to add an item.
+    AddItemError,
+    /// The other side failed or declined to populate a context menu.
+    PopulateError,
+    /// The other side failed or declined to perform a context menu action.
+    PerformError,
+    /// The host failed or declined to pop up its context menu.
+    PopupError,
+}
+
+impl Display for ContextMenuError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContextMenuError::AddItemError => f.write_str("Failed to add context menu item"),
+            ContextMenuError::PopulateError => f.write_str("Failed to populate context menu"),
+            ContextMenuError::PerformError => f.write_str("Failed to perform context menu action"),
+            ContextMenuError::PopupError => f.write_str("Failed to pop up context menu"),
+        }
+    }
+}
+
+impl Error for ContextMenuError {}
+
+/// An implementation of a context menu builder, which receives the items of a menu.
+///
+/// This is implemented by whichever side displays the context menu, and is wrapped into a
+/// [`ContextMenuBuilder`] to be passed to the other side.
+pub trait ContextMenuBuilderImpl {
+    /// Adds an item to the menu.
+    ///
+    /// # Errors
+    ///
+    /// This may return an error if the item could not be added, e.g. because its kind is not
+    /// supported by this builder.
+    fn add_item(&mut self, item: ContextMenuItem) -> Result<(), ContextMenuError>;
+
+    /// Returns `true` if this builder supports items of the given kind.
+    fn supports(&self, kind: ContextMenuItemKind) -> bool;
+}
+
+/// A context menu builder, which the items of a context menu can be added to.
+///
+/// Builders are either created from a [`ContextMenuBuilderImpl`] implementation using
+/// [`from_impl`](ContextMenuBuilder::from_impl), or received from the other side.
+#[repr(C)]
+pub struct ContextMenuBuilder<'a>(
+    clap_context_menu_builder,
+    PhantomData<(&'a mut clap_context_menu_builder, *const ())>,
+);
+
+impl<'a> ContextMenuBuilder<'a> {
+    /// Creates a new context menu builder for an existing [`ContextMenuBuilderImpl`] implementation.
+    pub fn from_impl<B: ContextMenuBuilderImpl + Sized + 'a>(builder: &'a mut B) -> Self {
+        Self(
+            clap_context_menu_builder {
+                ctx: builder as *mut B as *mut _,
+                add_item: Some(add_item::<B>),
+                supports: Some(supports::<B>),
+            },
+            PhantomData,
+        )
+    }
+
+    /// Creates a new context menu builder from a C FFI-compatible pointer.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the given `clap_context_menu_builder` instance is valid.
+    #[inline]
+    pub unsafe fn from_raw_mut(raw: &mut clap_context_menu_builder) -> &mut Self {
+        &mut *(raw as *mut _ as *mut _)
+    }
+
+    /// Returns this builder as a C FFI-compatible pointer.
+    #[inline]
+    pub fn as_raw_mut(&mut self) -> &mut clap_context_menu_builder {
+        &mut self.0
+    }
+
+    /// Returns `true` if this builder supports items of the given kind.
+    #[inline]
+    pub fn supports(&self, kind: ContextMenuItemKind) -> bool {
+        match self.0.supports {
+            None => false,
+            // SAFETY: this function pointer is guaranteed to be valid by from_raw_mut and from_impl
+            Some(supports) => unsafe { supports(&self.0, kind.to_raw()) },
+        }
+    }
+
+    /// Adds an item to the menu.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::AddItemError`] if the builder failed or declined to add the
+    /// item.
+    pub fn add_item(&mut self, item: ContextMenuItem) -> Result<(), ContextMenuError> {
+        let add_item = self.0.add_item.ok_or(ContextMenuError::AddItemError)?;
+
+        let kind = item.kind().to_raw();
+
+        // SAFETY: this function pointer is guaranteed to be valid by from_raw_mut and from_impl.
+        // All item data pointers are valid for the duration of the call.
+        let success = unsafe {
+            match item {
+                ContextMenuItem::Entry {
+                    label,
+                    is_enabled,
+                    action_id,
+                } => {
+                    let raw = clap_context_menu_entry {
+                        label: label.as_ptr(),
+                        is_enabled,
+                        action_id: action_id.get(),
+                    };
+                    add_item(&self.0, kind, &raw as *const _ as *const c_void)
+                }
+                ContextMenuItem::CheckEntry {
+                    label,
+                    is_enabled,
+                    is_checked,
+                    action_id,
+                } => {
+                    let raw = clap_context_menu_check_entry {
+                        label: label.as_ptr(),
+                        is_enabled,
+                        is_checked,
+                        action_id: action_id.get(),
+                    };
+                    add_item(&self.0, kind, &raw as *const _ as *const c_void)
+                }
+                ContextMenuItem::BeginSubmenu { label, is_enabled } => {
+                    let raw = clap_context_menu_submenu {
+                        label: label.as_ptr(),
+                        is_enabled,
+                    };
+                    add_item(&self.0, kind, &raw as *const _ as *const c_void)
+                }
+                ContextMenuItem::Title { title, is_enabled } => {
+                    let raw = clap_context_menu_item_title {
+                        title: title.as_ptr(),
+                        is_enabled,
+                    };
+                    add_item(&self.0, kind, &raw as *const _ as *const c_void)
+                }
+                ContextMenuItem::Separator | ContextMenuItem::EndSubmenu => {
+                    add_item(&self.0, kind, core::ptr::null())
+                }
+            }
+        };
+
+        match success {
+            true => Ok(()),
+            false => Err(ContextMenuError::AddItemError),
+        }
+    }
+
+    /// Adds a simple, enabled clickable entry to the menu.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::AddItemError`] if the builder failed or declined to add the
+    /// item.
+    #[inline]
+    pub fn add_entry(&mut self, label: &CStr, action_id: ClapId) -> Result<(), ContextMenuError> {
+        self.add_item(ContextMenuItem::Entry {
+            label,
+            is_enabled: true,
+            action_id,
+        })
+    }
+
+    /// Adds an enabled clickable entry with a checkmark to the menu.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::AddItemError`] if the builder failed or declined to add the
+    /// item.
+    #[inline]
+    pub fn add_check_entry(
+        &mut self,
+        label: &CStr,
+        action_id: ClapId,
+        is_checked: bool,
+    ) -> Result<(), ContextMenuError> {
+        self.add_item(ContextMenuItem::CheckEntry {
+            label,
+            is_enabled: true,
+            is_checked,
+            action_id,
+        })
+    }
+
+    /// Adds a separator line to the menu.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::AddItemError`] if the builder failed or declined to add the
+    /// item.
+    #[inline]
+    pub fn add_separator(&mut self) -> Result<(), ContextMenuError> {
+        self.add_item(ContextMenuItem::Separator)
+    }
+
+    /// Starts a new, enabled submenu. All following items will belong to it, until
+    /// [`end_submenu`](ContextMenuBuilder::end_submenu) is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::AddItemError`] if the builder failed or declined to add the
+    /// item.
+    #[inline]
+    pub fn begin_submenu(&mut self, label: &CStr) -> Result<(), ContextMenuError> {
+        self.add_item(ContextMenuItem::BeginSubmenu {
+            label,
+            is_enabled: true,
+        })
+    }
+
+    /// Ends the current submenu.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::AddItemError`] if the builder failed or declined to add the
+    /// item.
+    #[inline]
+    pub fn end_submenu(&mut self) -> Result<(), ContextMenuError> {
+        self.add_item(ContextMenuItem::EndSubmenu)
+    }
+
+    /// Adds an enabled title to the menu.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::AddItemError`] if the builder failed or declined to add the
+    /// item.
+    #[inline]
+    pub fn add_title(&mut self, title: &CStr) -> Result<(), ContextMenuError> {
+        self.add_item(ContextMenuItem::Title {
+            title,
+            is_enabled: true,
+        })
+    }
+}
+
+/// Returns the context of the given raw builder, or `None` if either the builder or its context
+/// is null.
+///
+/// # Safety
+///
+/// The given builder pointer must either be null or valid for reads.
+#[inline]
+unsafe fn builder_ctx<B>(builder: *const clap_context_menu_builder) -> Option<*mut B> {
+    let ctx = builder.as_ref()?.ctx;
+    (!ctx.is_null()).then_some(ctx.cast())
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn add_item<B: ContextMenuBuilderImpl + Sized>(
+    builder: *const clap_context_menu_builder,
+    item_kind: clap_context_menu_item_kind,
+    item_data: *const c_void,
+) -> bool {
+    handle_panic(|| {
+        let Some(builder) = builder_ctx::<B>(builder) else {
+            return false;
+        };
+
+        match ContextMenuItem::from_raw(item_kind, item_data) {
+            None => false,
+            Some(item) => (*builder).add_item(item).is_ok(),
+        }
+    })
+    .unwrap_or(false)
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn supports<B: ContextMenuBuilderImpl + Sized>(
+    builder: *const clap_context_menu_builder,
+    item_kind: clap_context_menu_item_kind,
+) -> bool {
+    handle_panic(|| {
+        let Some(builder) = builder_ctx::<B>(builder) else {
+            return false;
+        };
+
+        match ContextMenuItemKind::from_raw(item_kind) {
+            None => false,
+            Some(kind) => (*builder).supports(kind),
+        }
+    })
+    .unwrap_or(false)
+}
+
+#[cfg(feature = "clack-host")]
+mod host;
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin;
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,203 @@
+use super::*;
+use clack_host::extensions::prelude::*;
+
+impl PluginContextMenu {
+    /// Asks the plugin to insert its menu items for the given target into the given builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::PopulateError`] if the plugin failed or declined to populate
+    /// the menu.
+    pub fn populate<B: ContextMenuBuilderImpl>(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        target: ContextMenuTarget,
+        builder: &mut B,
+    ) -> Result<(), ContextMenuError> {
+        let populate = plugin
+            .use_extension(&self.0)
+            .populate
+            .ok_or(ContextMenuError::PopulateError)?;
+
+        let mut builder = ContextMenuBuilder::from_impl(builder);
+
+        // SAFETY: This type ensures the function pointer is valid.
+        match unsafe { populate(plugin.as_raw(), &target.to_raw(), builder.as_raw_mut()) } {
+            true => Ok(()),
+            false => Err(ContextMenuError::PopulateError),
+        }
+    }
+
+    /// Asks the plugin to perform the given action, which was selected by the user from one of
+    /// the plugin's menu items for the given target.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::PerformError`] if the plugin failed or declined to perform
+    /// the action.
+    pub fn perform(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        target: ContextMenuTarget,
+        action_id: ClapId,
+    ) -> Result<(), ContextMenuError> {
+        let perform = plugin
+            .use_extension(&self.0)
+            .perform
+            .ok_or(ContextMenuError::PerformError)?;
+
+        // SAFETY: This type ensures the function pointer is valid.
+        match unsafe { perform(plugin.as_raw(), &target.to_raw(), action_id.get()) } {
+            true => Ok(()),
+            false => Err(ContextMenuError::PerformError),
+        }
+    }
+}
+
+/// Implementation of the Host-side of the Context Menu extension.
+pub trait HostContextMenuImpl {
+    /// Inserts the host's menu items for the given target into the given builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the host failed or declined to populate the menu.
+    fn populate(
+        &mut self,
+        target: ContextMenuTarget,
+        builder: &mut ContextMenuBuilder,
+    ) -> Result<(), HostError>;
+
+    /// Performs the given action, which was selected by the user from one of the host's menu
+    /// items for the given target.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the host failed or declined to perform the action.
+    fn perform(&mut self, target: ContextMenuTarget, action_id: ClapId) -> Result<(), HostError>;
+
+    /// Returns `true` if the host can pop up its own context menu on behalf of the plugin.
+    fn can_popup(&mut self) -> bool;
+
+    /// Pops up the host's context menu for the given target, at the given position of the given
+    /// screen.
+    ///
+    /// The position is given in screen coordinates, relative to the plugin's window.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the host failed or declined to pop up the menu.
+    fn popup(
+        &mut self,
+        target: ContextMenuTarget,
+        screen_index: i32,
+        x: i32,
+        y: i32,
+    ) -> Result<(), HostError>;
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostContextMenu
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostContextMenuImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_host_context_menu {
+            populate: Some(populate::<H>),
+            perform: Some(perform::<H>),
+            can_popup: Some(can_popup::<H>),
+            popup: Some(popup::<H>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn populate<H: HostHandlers>(
+    host: *const clap_host,
+    target: *const clap_context_menu_target,
+    builder: *const clap_context_menu_builder,
+) -> bool
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostContextMenuImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        let target = ContextMenuTarget::from_raw_ptr(target).ok_or(
+            HostWrapperError::InvalidParameter("Invalid context menu target"),
+        )?;
+
+        if builder.is_null() {
+            return Err(HostWrapperError::InvalidParameter(
+                "Null context menu builder",
+            ));
+        }
+
+        let builder = ContextMenuBuilder::from_raw_mut(&mut *(builder as *mut _));
+
+        Ok(host
+            .main_thread()
+            .as_mut()
+            .populate(target, builder)
+            .is_ok())
+    })
+    .unwrap_or(false)
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn perform<H: HostHandlers>(
+    host: *const clap_host,
+    target: *const clap_context_menu_target,
+    action_id: u32,
+) -> bool
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostContextMenuImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        let target = ContextMenuTarget::from_raw_ptr(target).ok_or(
+            HostWrapperError::InvalidParameter("Invalid context menu target"),
+        )?;
+
+        let action_id = ClapId::from_raw(action_id).ok_or(HostWrapperError::InvalidParameter(
+            "Invalid context menu action ID",
+        ))?;
+
+        Ok(host
+            .main_thread()
+            .as_mut()
+            .perform(target, action_id)
+            .is_ok())
+    })
+    .unwrap_or(false)
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn can_popup<H: HostHandlers>(host: *const clap_host) -> bool
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostContextMenuImpl,
+{
+    HostWrapper::<H>::handle(host, |host| Ok(host.main_thread().as_mut().can_popup()))
+        .unwrap_or(false)
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn popup<H: HostHandlers>(
+    host: *const clap_host,
+    target: *const clap_context_menu_target,
+    screen_index: i32,
+    x: i32,
+    y: i32,
+) -> bool
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostContextMenuImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        let target = ContextMenuTarget::from_raw_ptr(target).ok_or(
+            HostWrapperError::InvalidParameter("Invalid context menu target"),
+        )?;
+
+        Ok(host
+            .main_thread()
+            .as_mut()
+            .popup(target, screen_index, x, y)
+            .is_ok())
+    })
+    .unwrap_or(false)
+}
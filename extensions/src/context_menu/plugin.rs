@@ -0,0 +1,178 @@
+use super::*;
+use clack_plugin::extensions::prelude::*;
+
+impl HostContextMenu {
+    /// Asks the host to insert its menu items for the given target into the given builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::PopulateError`] if the host failed or declined to populate
+    /// the menu.
+    pub fn populate<B: ContextMenuBuilderImpl>(
+        &self,
+        host: &mut HostMainThreadHandle,
+        target: ContextMenuTarget,
+        builder: &mut B,
+    ) -> Result<(), ContextMenuError> {
+        let populate = host
+            .use_extension(&self.0)
+            .populate
+            .ok_or(ContextMenuError::PopulateError)?;
+
+        let mut builder = ContextMenuBuilder::from_impl(builder);
+
+        // SAFETY: This type ensures the function pointer is valid.
+        match unsafe { populate(host.as_raw(), &target.to_raw(), builder.as_raw_mut()) } {
+            true => Ok(()),
+            false => Err(ContextMenuError::PopulateError),
+        }
+    }
+
+    /// Asks the host to perform the given action, which was selected by the user from one of
+    /// the host's menu items for the given target.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::PerformError`] if the host failed or declined to perform the
+    /// action.
+    pub fn perform(
+        &self,
+        host: &mut HostMainThreadHandle,
+        target: ContextMenuTarget,
+        action_id: ClapId,
+    ) -> Result<(), ContextMenuError> {
+        let perform = host
+            .use_extension(&self.0)
+            .perform
+            .ok_or(ContextMenuError::PerformError)?;
+
+        // SAFETY: This type ensures the function pointer is valid.
+        match unsafe { perform(host.as_raw(), &target.to_raw(), action_id.get()) } {
+            true => Ok(()),
+            false => Err(ContextMenuError::PerformError),
+        }
+    }
+
+    /// Returns `true` if the host can pop up its own context menu on behalf of the plugin.
+    pub fn can_popup(&self, host: &mut HostMainThreadHandle) -> bool {
+        match host.use_extension(&self.0).can_popup {
+            None => false,
+            // SAFETY: This type ensures the function pointer is valid.
+            Some(can_popup) => unsafe { can_popup(host.as_raw()) },
+        }
+    }
+
+    /// Asks the host to pop up its context menu for the given target, at the given position of
+    /// the given screen.
+    ///
+    /// The position is given in screen coordinates, relative to the plugin's window. This can only
+    /// be called if the plugin's GUI is visible, and if [`can_popup`](Self::can_popup) returned
+    /// `true`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextMenuError::PopupError`] if the host failed or declined to pop up the menu.
+    pub fn popup(
+        &self,
+        host: &mut HostMainThreadHandle,
+        target: ContextMenuTarget,
+        screen_index: i32,
+        x: i32,
+        y: i32,
+    ) -> Result<(), ContextMenuError> {
+        let popup = host
+            .use_extension(&self.0)
+            .popup
+            .ok_or(ContextMenuError::PopupError)?;
+
+        // SAFETY: This type ensures the function pointer is valid.
+        match unsafe { popup(host.as_raw(), &target.to_raw(), screen_index, x, y) } {
+            true => Ok(()),
+            false => Err(ContextMenuError::PopupError),
+        }
+    }
+}
+
+/// Implementation of the Plugin-side of the Context Menu extension.
+pub trait PluginContextMenuImpl {
+    /// Inserts the plugin's menu items for the given target into the given builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plugin failed or declined to populate the menu.
+    fn populate(
+        &mut self,
+        target: ContextMenuTarget,
+        builder: &mut ContextMenuBuilder,
+    ) -> Result<(), PluginError>;
+
+    /// Performs the given action, which was selected by the user from one of the plugin's menu
+    /// items for the given target.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plugin failed or declined to perform the action.
+    fn perform(&mut self, target: ContextMenuTarget, action_id: ClapId) -> Result<(), PluginError>;
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginContextMenu
+where
+    for<'a> P::MainThread<'a>: PluginContextMenuImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_plugin_context_menu {
+            populate: Some(populate::<P>),
+            perform: Some(perform::<P>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn populate<P: Plugin>(
+    plugin: *const clap_plugin,
+    target: *const clap_context_menu_target,
+    builder: *const clap_context_menu_builder,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginContextMenuImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        let target = ContextMenuTarget::from_raw_ptr(target).ok_or(
+            PluginWrapperError::InvalidParameter("Invalid context menu target"),
+        )?;
+
+        if builder.is_null() {
+            return Err(PluginWrapperError::NulPtr("clap_context_menu_builder"));
+        }
+
+        let builder = ContextMenuBuilder::from_raw_mut(&mut *(builder as *mut _));
+        p.main_thread().as_mut().populate(target, builder)?;
+        Ok(())
+    })
+    .is_some()
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn perform<P: Plugin>(
+    plugin: *const clap_plugin,
+    target: *const clap_context_menu_target,
+    action_id: u32,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginContextMenuImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        let target = ContextMenuTarget::from_raw_ptr(target).ok_or(
+            PluginWrapperError::InvalidParameter("Invalid context menu target"),
+        )?;
+
+        let action_id = ClapId::from_raw(action_id).ok_or(PluginWrapperError::InvalidParameter(
+            "Invalid context menu action ID",
+        ))?;
+
+        p.main_thread().as_mut().perform(target, action_id)?;
+        Ok(())
+    })
+    .is_some()
+}
@@ -0,0 +1,282 @@
+#![deny(missing_docs)]
+
+//! Allows the host to add and remove audio ports of the plugin.
+//!
+//! This is mostly useful for modular hosts, or plugins with a variable number of inputs or
+//! outputs (e.g. a mixer or a multi-output sampler). New ports are described using a channel
+//! count and port type, along with the same optional
+//! [port details](crate::configurable_audio_ports::AudioPortRequestDetails) as the
+//! [Configurable Audio Ports](crate::configurable_audio_ports) extension.
+//!
+//! Ports can only be added or removed while the plugin is deactivated. After a successful
+//! change, the host must re-read the plugin's audio ports using the
+//! [Audio Ports](crate::audio_ports) extension.
+
+use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::extensible_audio_ports::*;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+
+/// The Plugin-side of the Extensible Audio Ports extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginExtensibleAudioPorts(
+    RawExtension<PluginExtensionSide, clap_plugin_extensible_audio_ports>,
+);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginExtensibleAudioPorts {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_EXTENSIBLE_AUDIO_PORTS;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// An error that can occur as a plugin adds or removes an audio port.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct AudioPortChangeError {
+    removing: bool,
+}
+
+impl AudioPortChangeError {
+    /// Creates an error that occurred while adding an audio port.
+    #[inline]
+    pub const fn adding() -> Self {
+        Self { removing: false }
+    }
+
+    /// Creates an error that occurred while removing an audio port.
+    #[inline]
+    pub const fn removing() -> Self {
+        Self { removing: true }
+    }
+}
+
+impl Display for AudioPortChangeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.removing {
+            f.write_str("Failed to remove plugin audio port.")
+        } else {
+            f.write_str("Failed to add plugin audio port.")
+        }
+    }
+}
+
+impl Error for AudioPortChangeError {}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use crate::audio_ports::AudioPortType;
+    use crate::configurable_audio_ports::AudioPortRequestDetails;
+    use clack_host::extensions::prelude::*;
+    use clap_sys::ext::draft::ambisonic::clap_ambisonic_config;
+
+    impl PluginExtensibleAudioPorts {
+        /// Requests the plugin to add a new audio port.
+        ///
+        /// The plugin *must* be deactivated to call this method. On success, the plugin's audio
+        /// ports have changed, and the host must re-read them using the
+        /// [Audio Ports](crate::audio_ports) extension.
+        ///
+        /// # Errors
+        ///
+        /// This may return an [`AudioPortChangeError`] if the plugin declined or failed to add
+        /// the port, or if the given surround channel map's length doesn't match the channel count.
+        pub fn add_port(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            is_input: bool,
+            channel_count: u32,
+            port_type: Option<AudioPortType>,
+            port_details: AudioPortRequestDetails,
+        ) -> Result<(), AudioPortChangeError> {
+            let add_port = plugin
+                .use_extension(&self.0)
+                .add_port
+                .ok_or(AudioPortChangeError::adding())?;
+
+            let ambisonic_config: clap_ambisonic_config;
+            let raw_details = match port_details {
+                AudioPortRequestDetails::None => core::ptr::null(),
+                AudioPortRequestDetails::Surround(channel_map) => {
+                    if channel_map.len() != channel_count as usize {
+                        return Err(AudioPortChangeError::adding());
+                    }
+
+                    channel_map.as_raw().as_ptr().cast()
+                }
+                AudioPortRequestDetails::Ambisonic(config) => {
+                    ambisonic_config = config.to_raw();
+                    (&ambisonic_config as *const clap_ambisonic_config).cast()
+                }
+            };
+
+            // SAFETY: This type ensures the function pointer is valid. The port type and details
+            // pointers are either null or valid for the duration of the call.
+            let success = unsafe {
+                add_port(
+                    plugin.as_raw(),
+                    is_input,
+                    channel_count,
+                    port_type.map_or(core::ptr::null(), |t| t.0.as_ptr()),
+                    raw_details,
+                )
+            };
+
+            match success {
+                true => Ok(()),
+                false => Err(AudioPortChangeError::adding()),
+            }
+        }
+
+        /// Requests the plugin to remove the audio port at the given index.
+        ///
+        /// The plugin *must* be deactivated to call this method. On success, the plugin's audio
+        /// ports have changed, and the host must re-read them using the
+        /// [Audio Ports](crate::audio_ports) extension.
+        ///
+        /// # Errors
+        ///
+        /// This may return an [`AudioPortChangeError`] if the plugin declined or failed to remove
+        /// the port.
+        pub fn remove_port(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            is_input: bool,
+            index: u32,
+        ) -> Result<(), AudioPortChangeError> {
+            let remove_port = plugin
+                .use_extension(&self.0)
+                .remove_port
+                .ok_or(AudioPortChangeError::removing())?;
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let success = unsafe { remove_port(plugin.as_raw(), is_input, index) };
+
+            match success {
+                true => Ok(()),
+                false => Err(AudioPortChangeError::removing()),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use crate::audio_ports::AudioPortType;
+    use crate::configurable_audio_ports::AudioPortRequestDetails;
+    use clack_plugin::extensions::prelude::*;
+    use std::ffi::{c_char, c_void};
+
+    /// Implementation of the Plugin-side of the Extensible Audio Ports extension.
+    pub trait PluginExtensibleAudioPortsImpl {
+        /// Adds a new audio port with the given channel count, type and details.
+        ///
+        /// The plugin is always deactivated when this is called. On success, the host re-reads
+        /// the plugin's audio ports.
+        ///
+        /// # Errors
+        ///
+        /// This may return an error if the plugin declined or failed to add the port. In that
+        /// case, the plugin's audio ports must be left unchanged.
+        fn add_port(
+            &mut self,
+            is_input: bool,
+            channel_count: u32,
+            port_type: Option<AudioPortType>,
+            port_details: AudioPortRequestDetails,
+        ) -> Result<(), PluginError>;
+
+        /// Removes the audio port at the given index.
+        ///
+        /// The plugin is always deactivated when this is called. On success, the host re-reads
+        /// the plugin's audio ports.
+        ///
+        /// # Errors
+        ///
+        /// This may return an error if the plugin declined or failed to remove the port. In that
+        /// case, the plugin's audio ports must be left unchanged.
+        fn remove_port(&mut self, is_input: bool, index: u32) -> Result<(), PluginError>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginExtensibleAudioPorts
+    where
+        for<'a> P::MainThread<'a>: PluginExtensibleAudioPortsImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_extensible_audio_ports {
+                add_port: Some(add_port::<P>),
+                remove_port: Some(remove_port::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn add_port<P: Plugin>(
+        plugin: *const clap_plugin,
+        is_input: bool,
+        channel_count: u32,
+        port_type: *const c_char,
+        port_details: *const c_void,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginExtensibleAudioPortsImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if p.is_active() {
+                return Err(PluginWrapperError::DeactivationRequiredForFunction(
+                    "clap_plugin_extensible_audio_ports.add_port",
+                ));
+            }
+
+            let port_type = if port_type.is_null() {
+                None
+            } else {
+                Some(AudioPortType(CStr::from_ptr(port_type))).filter(|t| !t.0.is_empty())
+            };
+
+            let port_details =
+                AudioPortRequestDetails::from_raw(port_type, channel_count, port_details);
+
+            p.main_thread()
+                .as_mut()
+                .add_port(is_input, channel_count, port_type, port_details)?;
+
+            Ok(())
+        })
+        .is_some()
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn remove_port<P: Plugin>(
+        plugin: *const clap_plugin,
+        is_input: bool,
+        index: u32,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginExtensibleAudioPortsImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if p.is_active() {
+                return Err(PluginWrapperError::DeactivationRequiredForFunction(
+                    "clap_plugin_extensible_audio_ports.remove_port",
+                ));
+            }
+
+            p.main_thread().as_mut().remove_port(is_input, index)?;
+
+            Ok(())
+        })
+        .is_some()
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,118 @@
+//! Allows plugins to report the gain adjustment they are currently applying (e.g. the gain
+//! reduction of a compressor), so that the host can display it.
+
+#![deny(missing_docs)]
+
+use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
+use std::ffi::CStr;
+
+/// The C-FFI definitions of the Gain Adjustment Metering extension.
+///
+/// This extension is not part of the version of `clap-sys` currently in use, and is therefore
+/// defined here, following the CLAP headers.
+#[allow(non_camel_case_types)]
+mod sys {
+    use crate::utils::extension_id;
+    use clap_sys::plugin::clap_plugin;
+    use std::ffi::CStr;
+
+    pub const CLAP_EXT_GAIN_ADJUSTMENT_METERING: &CStr =
+        extension_id(b"clap.gain-adjustment-metering/0\0");
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_gain_adjustment_metering {
+        pub get_current_gain_adjustment:
+            Option<unsafe extern "C" fn(plugin: *const clap_plugin) -> f64>,
+    }
+}
+
+use sys::*;
+
+/// The Plugin-side of the Gain Adjustment Metering extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginGainAdjustmentMetering(
+    RawExtension<PluginExtensionSide, clap_plugin_gain_adjustment_metering>,
+);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginGainAdjustmentMetering {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_GAIN_ADJUSTMENT_METERING;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+
+    impl PluginGainAdjustmentMetering {
+        /// Returns the gain adjustment the plugin applied to the last sample of the most recently
+        /// processed block, in decibels.
+        ///
+        /// A negative value means the plugin is reducing the gain (e.g. a compressor), while a
+        /// positive value means it is adding gain (e.g. an expander). If the plugin doesn't
+        /// implement this function, `0.0` is returned.
+        #[inline]
+        pub fn get_current_gain_adjustment(&self, plugin: &mut PluginAudioProcessorHandle) -> f64 {
+            match plugin.use_extension(&self.0).get_current_gain_adjustment {
+                None => 0.0,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(get_current_gain_adjustment) => unsafe {
+                    get_current_gain_adjustment(plugin.as_raw())
+                },
+            }
+        }
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+
+    /// Implementation of the Plugin-side of the Gain Adjustment Metering extension.
+    pub trait PluginGainAdjustmentMeteringImpl {
+        /// Returns the gain adjustment applied to the last sample of the most recently processed
+        /// block, in decibels.
+        ///
+        /// This is the dynamic gain reduction (negative) or expansion (positive) applied by the
+        /// plugin, before any make-up gain. `0.0` means no gain adjustment is being applied.
+        fn get_current_gain_adjustment(&mut self) -> f64;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginGainAdjustmentMetering
+    where
+        for<'a> P::AudioProcessor<'a>: PluginGainAdjustmentMeteringImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_gain_adjustment_metering {
+                get_current_gain_adjustment: Some(get_current_gain_adjustment::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_current_gain_adjustment<P: Plugin>(plugin: *const clap_plugin) -> f64
+    where
+        for<'a> P::AudioProcessor<'a>: PluginGainAdjustmentMeteringImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            Ok(plugin
+                .audio_processor()?
+                .as_mut()
+                .get_current_gain_adjustment())
+        })
+        .unwrap_or(0.0)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
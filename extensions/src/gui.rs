@@ -377,6 +377,17 @@ impl GuiApiType<'_> {
         unsafe { CStr::from_bytes_with_nul_unchecked(b"wayland\0") },
     );
 
+    /// Represents a webview, in which the plugin's GUI is an HTML page displayed by the host.
+    ///
+    /// This API uses logical size for pixels.
+    ///
+    /// No window handle is exchanged with this API: the host creates and owns the webview, and
+    /// the plugin provides its contents through the Webview extension.
+    pub const WEBVIEW: GuiApiType<'static> = GuiApiType(
+        // SAFETY: this constant string is NULL-terminated
+        unsafe { CStr::from_bytes_with_nul_unchecked(b"webview\0") },
+    );
+
     /// Whether this API type can provide a `RawWindowHandle`.
     pub fn can_provide_raw_window_handle(&self) -> bool {
         self == &Self::WIN32 || self == &Self::COCOA || self == &Self::X11
@@ -385,7 +396,7 @@ impl GuiApiType<'_> {
     /// Determines whether this GUI API uses physical pixels or logical pixels.
     /// Returns `true` the API uses logical pixels, `false` if it uses physical pixels.
     pub fn uses_logical_size(&self) -> bool {
-        self == &Self::COCOA
+        self == &Self::COCOA || self == &Self::WEBVIEW
     }
 
     /// Returns `true` if this GUI API supports opening in an embedded window, `false` otherwise.
@@ -397,8 +408,8 @@ impl GuiApiType<'_> {
     /// Matches this GUI API to one of the standard APIs.
     ///
     /// If the value matches one of the [`WIN32`](Self::WIN32), [`COCOA`](Self::COCOA),
-    /// [`X11`](Self::X11), or [`WAYLAND`](Self::WAYLAND) constants, then that constant is
-    /// returned. Otherwise, [`None`] is returned.
+    /// [`X11`](Self::X11), [`WAYLAND`](Self::WAYLAND), or [`WEBVIEW`](Self::WEBVIEW) constants,
+    /// then that constant is returned. Otherwise, [`None`] is returned.
     ///
     /// # Example
     ///
@@ -431,6 +442,8 @@ impl GuiApiType<'_> {
             Some(Self::X11)
         } else if self == &Self::WAYLAND {
             Some(Self::WAYLAND)
+        } else if self == &Self::WEBVIEW {
+            Some(Self::WEBVIEW)
         } else {
             None
         }
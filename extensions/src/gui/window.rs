@@ -142,8 +142,9 @@ impl<'a> Window<'a> {
     /// Matches this window's GUI API to one of the standard APIs.
     ///
     /// If the value matches one of the [`WIN32`](GuiApiType::WIN32), [`COCOA`](GuiApiType::COCOA),
-    /// [`X11`](GuiApiType::X11), or [`WAYLAND`](GuiApiType::WAYLAND) constants, then a window
-    /// with that constant as its API type is returned. Otherwise, [`None`] is returned.
+    /// [`X11`](GuiApiType::X11), [`WAYLAND`](GuiApiType::WAYLAND), or
+    /// [`WEBVIEW`](GuiApiType::WEBVIEW) constants, then a window with that constant as its API
+    /// type is returned. Otherwise, [`None`] is returned.
     pub fn to_standard_api_type(&self) -> Option<Window<'static>> {
         Some(Window {
             raw: clap_window {
@@ -1,30 +1,60 @@
 #![doc(html_logo_url = "https://raw.githubusercontent.com/prokopyl/clack/main/logo.svg")]
 #![deny(clippy::undocumented_unsafe_blocks)]
 
+#[cfg(feature = "ambisonic")]
+pub mod ambisonic;
 #[cfg(feature = "audio-ports")]
 pub mod audio_ports;
+#[cfg(feature = "audio-ports-activation")]
+pub mod audio_ports_activation;
 #[cfg(feature = "audio-ports-config")]
 pub mod audio_ports_config;
+#[cfg(feature = "configurable-audio-ports")]
+pub mod configurable_audio_ports;
+#[cfg(feature = "context-menu")]
+pub mod context_menu;
 #[cfg(feature = "event-registry")]
 pub mod event_registry;
+#[cfg(feature = "extensible-audio-ports")]
+pub mod extensible_audio_ports;
+#[cfg(feature = "gain-adjustment-metering")]
+pub mod gain_adjustment_metering;
 #[cfg(feature = "gui")]
 pub mod gui;
 #[cfg(feature = "latency")]
 pub mod latency;
 #[cfg(feature = "log")]
 pub mod log;
+#[cfg(feature = "mini-curve-display")]
+pub mod mini_curve_display;
 #[cfg(feature = "note-name")]
 pub mod note_name;
 #[cfg(feature = "note-ports")]
 pub mod note_ports;
+#[cfg(feature = "param-indication")]
+pub mod param_indication;
 #[cfg(feature = "params")]
 pub mod params;
 #[cfg(all(unix, feature = "posix-fd"))]
 pub mod posix_fd;
+#[cfg(feature = "preset-load")]
+pub mod preset_load;
+#[cfg(feature = "project-location")]
+pub mod project_location;
+#[cfg(feature = "remote-controls")]
+pub mod remote_controls;
 #[cfg(feature = "render")]
 pub mod render;
+#[cfg(feature = "resource-directory")]
+pub mod resource_directory;
+#[cfg(feature = "scratch-memory")]
+pub mod scratch_memory;
 #[cfg(feature = "state")]
 pub mod state;
+#[cfg(feature = "state-context")]
+pub mod state_context;
+#[cfg(feature = "surround")]
+pub mod surround;
 #[cfg(feature = "tail")]
 pub mod tail;
 #[cfg(feature = "thread-check")]
@@ -33,8 +63,20 @@ pub mod thread_check;
 pub mod thread_pool;
 #[cfg(feature = "timer")]
 pub mod timer;
+#[cfg(feature = "track-info")]
+pub mod track_info;
+#[cfg(feature = "transport-control")]
+pub mod transport_control;
+#[cfg(feature = "triggers")]
+pub mod triggers;
+#[cfg(feature = "tuning")]
+pub mod tuning;
+#[cfg(feature = "undo")]
+pub mod undo;
 #[cfg(feature = "voice-info")]
 pub mod voice_info;
+#[cfg(feature = "webview")]
+pub mod webview;
 
 pub(crate) mod utils;
 
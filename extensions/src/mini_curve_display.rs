@@ -0,0 +1,737 @@
+//! Allows plugins to provide small curves (e.g. an EQ's frequency response, or a compressor's
+//! transfer curve) that the host can display, for example in a mixer's insert slot.
+//!
+//! Hosts first retrieve the number of curves using
+//! [`PluginMiniCurveDisplay::get_curve_count`], then render them into a
+//! [`MiniCurveRenderBuffer`], which holds pre-allocated values for each curve.
+//!
+//! Curves are only rendered while they are observed (see
+//! [`PluginMiniCurveDisplay::set_observed`]). Static curves are only re-rendered when the plugin
+//! notifies the host with [`HostMiniCurveDisplay::changed`], while dynamic ones are periodically
+//! re-rendered by the host.
+
+#![deny(missing_docs)]
+
+use crate::utils::slice_from_external_parts;
+use bitflags::bitflags;
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use std::ffi::CStr;
+
+/// The C-FFI definitions of the Mini Curve Display extension.
+///
+/// This extension is not part of the version of `clap-sys` currently in use, and is therefore
+/// defined here, following the CLAP headers.
+#[allow(non_camel_case_types)]
+mod sys {
+    use crate::utils::extension_id;
+    use clap_sys::host::clap_host;
+    use clap_sys::plugin::clap_plugin;
+    use std::ffi::{c_char, CStr};
+
+    pub const CLAP_EXT_MINI_CURVE_DISPLAY: &CStr = extension_id(b"clap.mini-curve-display/3\0");
+
+    pub const CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_UNSPECIFIED: i32 = 0;
+    pub const CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_SCALE: i32 = 1;
+    pub const CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_RESPONSE: i32 = 2;
+    pub const CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_PHASE_RESPONSE: i32 = 3;
+    pub const CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TRANSFER_CURVE: i32 = 4;
+    pub const CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_REDUCTION: i32 = 5;
+    pub const CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TIME_SERIES: i32 = 6;
+
+    pub const CLAP_MINI_CURVE_DISPLAY_CURVE_CHANGED: u32 = 1 << 0;
+    pub const CLAP_MINI_CURVE_DISPLAY_AXIS_NAME_CHANGED: u32 = 1 << 1;
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_mini_curve_display_curve_hints {
+        pub x_min: f64,
+        pub x_max: f64,
+        pub y_min: f64,
+        pub y_max: f64,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_mini_curve_display_curve_data {
+        pub curve_kind: i32,
+        pub values: *mut u16,
+        pub values_count: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_mini_curve_display {
+        pub get_curve_count: Option<unsafe extern "C" fn(plugin: *const clap_plugin) -> u32>,
+        pub render: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                curves: *mut clap_mini_curve_display_curve_data,
+                curves_size: u32,
+            ) -> u32,
+        >,
+        pub set_observed:
+            Option<unsafe extern "C" fn(plugin: *const clap_plugin, is_observed: bool)>,
+        pub get_axis_name: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                curve_index: u32,
+                x_name: *mut c_char,
+                y_name: *mut c_char,
+                name_capacity: u32,
+            ) -> bool,
+        >,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_host_mini_curve_display {
+        pub get_hints: Option<
+            unsafe extern "C" fn(
+                host: *const clap_host,
+                kind: u32,
+                hints: *mut clap_mini_curve_display_curve_hints,
+            ) -> bool,
+        >,
+        pub set_dynamic: Option<unsafe extern "C" fn(host: *const clap_host, is_dynamic: bool)>,
+        pub changed: Option<unsafe extern "C" fn(host: *const clap_host, flags: u32)>,
+    }
+}
+
+use sys::*;
+
+/// The Plugin-side of the Mini Curve Display extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginMiniCurveDisplay(
+    RawExtension<PluginExtensionSide, clap_plugin_mini_curve_display>,
+);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginMiniCurveDisplay {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_MINI_CURVE_DISPLAY;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Mini Curve Display extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostMiniCurveDisplay(RawExtension<HostExtensionSide, clap_host_mini_curve_display>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostMiniCurveDisplay {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_MINI_CURVE_DISPLAY;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The kind of data a curve represents.
+///
+/// The host can use this to paint the curve with a meaningful color or scale.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum MiniCurveKind {
+    /// The kind of the curve is not specified.
+    Unspecified,
+    /// A gain scale, e.g. a simple volume curve.
+    GainScale,
+    /// A gain response, e.g. an EQ's frequency response.
+    GainResponse,
+    /// A phase response.
+    PhaseResponse,
+    /// A transfer curve, e.g. a compressor's or a saturator's.
+    TransferCurve,
+    /// A gain reduction over time, e.g. a compressor's.
+    GainReduction,
+    /// A generic series of values over time.
+    TimeSeries,
+}
+
+impl MiniCurveKind {
+    /// Gets a [`MiniCurveKind`] from its raw, C-FFI compatible value.
+    ///
+    /// This returns [`None`] if the kind is unknown.
+    pub const fn from_raw(raw: i32) -> Option<Self> {
+        match raw {
+            CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_UNSPECIFIED => Some(Self::Unspecified),
+            CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_SCALE => Some(Self::GainScale),
+            CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_RESPONSE => Some(Self::GainResponse),
+            CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_PHASE_RESPONSE => Some(Self::PhaseResponse),
+            CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TRANSFER_CURVE => Some(Self::TransferCurve),
+            CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_REDUCTION => Some(Self::GainReduction),
+            CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TIME_SERIES => Some(Self::TimeSeries),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw, C-FFI compatible value of this kind.
+    pub const fn to_raw(self) -> i32 {
+        match self {
+            Self::Unspecified => CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_UNSPECIFIED,
+            Self::GainScale => CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_SCALE,
+            Self::GainResponse => CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_RESPONSE,
+            Self::PhaseResponse => CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_PHASE_RESPONSE,
+            Self::TransferCurve => CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TRANSFER_CURVE,
+            Self::GainReduction => CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_GAIN_REDUCTION,
+            Self::TimeSeries => CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_TIME_SERIES,
+        }
+    }
+}
+
+/// The ranges of the axes of a curve, as provided by the host.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MiniCurveHints {
+    /// The lowest value of the X axis.
+    pub x_min: f64,
+    /// The highest value of the X axis.
+    pub x_max: f64,
+    /// The lowest value of the Y axis.
+    pub y_min: f64,
+    /// The highest value of the Y axis.
+    pub y_max: f64,
+}
+
+impl MiniCurveHints {
+    #[cfg(feature = "clack-plugin")]
+    #[inline]
+    fn from_raw(raw: &clap_mini_curve_display_curve_hints) -> Self {
+        Self {
+            x_min: raw.x_min,
+            x_max: raw.x_max,
+            y_min: raw.y_min,
+            y_max: raw.y_max,
+        }
+    }
+
+    #[cfg(feature = "clack-host")]
+    #[inline]
+    fn to_raw(self) -> clap_mini_curve_display_curve_hints {
+        clap_mini_curve_display_curve_hints {
+            x_min: self.x_min,
+            x_max: self.x_max,
+            y_min: self.y_min,
+            y_max: self.y_max,
+        }
+    }
+}
+
+bitflags! {
+    /// Flags describing what changed about the plugin's curves.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct MiniCurveChangeFlags: u32 {
+        /// The content of the curves changed.
+        ///
+        /// This can only be used if the curves are observed and static.
+        const CURVE_CHANGED = CLAP_MINI_CURVE_DISPLAY_CURVE_CHANGED;
+        /// The names of the curves' axes changed.
+        ///
+        /// This can only be used if the curves are observed.
+        const AXIS_NAME_CHANGED = CLAP_MINI_CURVE_DISPLAY_AXIS_NAME_CHANGED;
+    }
+}
+
+/// A single curve, made of values pre-allocated by the host.
+///
+/// The value at index `0` is the leftmost one. The value `1` is at the bottom of the display,
+/// and `u16::MAX - 1` at the top. The values `0` and `u16::MAX` are not painted.
+#[repr(transparent)]
+pub struct MiniCurve {
+    inner: clap_mini_curve_display_curve_data,
+}
+
+impl MiniCurve {
+    /// Returns the kind of this curve, or [`None`] if it is unknown.
+    #[inline]
+    pub fn kind(&self) -> Option<MiniCurveKind> {
+        MiniCurveKind::from_raw(self.inner.curve_kind)
+    }
+
+    /// Sets the kind of this curve.
+    #[inline]
+    pub fn set_kind(&mut self, kind: MiniCurveKind) {
+        self.inner.curve_kind = kind.to_raw();
+    }
+
+    /// Returns the values of this curve.
+    #[inline]
+    pub fn values(&self) -> &[u16] {
+        // SAFETY: The values buffer is valid for values_count elements, per the CLAP spec.
+        unsafe { slice_from_external_parts(self.inner.values, self.inner.values_count as usize) }
+    }
+
+    /// Returns the values of this curve, to be filled in.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [u16] {
+        // SAFETY: The values buffer is valid for values_count elements, per the CLAP spec.
+        unsafe {
+            crate::utils::slice_from_external_parts_mut(
+                self.inner.values,
+                self.inner.values_count as usize,
+            )
+        }
+    }
+}
+
+impl std::fmt::Debug for MiniCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MiniCurve")
+            .field("kind", &self.kind())
+            .field("values", &self.values())
+            .finish()
+    }
+}
+
+/// The names of the axes of a curve.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct MiniCurveAxisNames<'a> {
+    /// The name of the X axis.
+    pub x_name: &'a [u8],
+    /// The name of the Y axis.
+    pub y_name: &'a [u8],
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+    use clap_sys::string_sizes::CLAP_NAME_SIZE;
+    use std::ffi::c_char;
+
+    /// A host-owned buffer the plugin renders its curves into.
+    ///
+    /// This pre-allocates the values of a given number of curves, which can be reused across
+    /// renders.
+    pub struct MiniCurveRenderBuffer {
+        curves: Vec<MiniCurve>,
+        values: Vec<Box<[u16]>>,
+    }
+
+    impl MiniCurveRenderBuffer {
+        /// Creates a new buffer, holding `curve_count` curves of `values_count` values each.
+        ///
+        /// The number of values usually matches the width of the display area, in pixels.
+        pub fn new(curve_count: usize, values_count: u32) -> Self {
+            let values: Vec<Box<[u16]>> = (0..curve_count)
+                .map(|_| vec![0; values_count as usize].into_boxed_slice())
+                .collect();
+
+            let curves = values
+                .iter()
+                .map(|_| MiniCurve {
+                    inner: clap_mini_curve_display_curve_data {
+                        curve_kind: CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_UNSPECIFIED,
+                        values: core::ptr::null_mut(),
+                        values_count: 0,
+                    },
+                })
+                .collect();
+
+            let mut buffer = Self { curves, values };
+            buffer.reset();
+            buffer
+        }
+
+        /// Resets the curves to point to their own values, in case the plugin tampered with them.
+        fn reset(&mut self) {
+            for (curve, values) in self.curves.iter_mut().zip(self.values.iter_mut()) {
+                curve.inner = clap_mini_curve_display_curve_data {
+                    curve_kind: CLAP_MINI_CURVE_DISPLAY_CURVE_KIND_UNSPECIFIED,
+                    values: values.as_mut_ptr(),
+                    values_count: values.len() as u32,
+                };
+            }
+        }
+    }
+
+    /// A host-owned buffer the plugin writes the names of a curve's axes into.
+    pub struct MiniCurveAxisNamesBuffer {
+        x_name: [u8; CLAP_NAME_SIZE],
+        y_name: [u8; CLAP_NAME_SIZE],
+    }
+
+    impl Default for MiniCurveAxisNamesBuffer {
+        #[inline]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl MiniCurveAxisNamesBuffer {
+        /// Creates a new, empty axis names buffer.
+        #[inline]
+        pub const fn new() -> Self {
+            Self {
+                x_name: [0; CLAP_NAME_SIZE],
+                y_name: [0; CLAP_NAME_SIZE],
+            }
+        }
+    }
+
+    /// Reads a nul-terminated string from the given buffer, up to its end.
+    fn read_name(buffer: &[u8]) -> &[u8] {
+        let len = buffer.iter().position(|b| *b == 0).unwrap_or(buffer.len());
+        &buffer[..len]
+    }
+
+    impl PluginMiniCurveDisplay {
+        /// Returns the number of curves the plugin wants to display.
+        #[inline]
+        pub fn get_curve_count(&self, plugin: &mut PluginMainThreadHandle) -> u32 {
+            match plugin.use_extension(&self.0).get_curve_count {
+                None => 0,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(get_curve_count) => unsafe { get_curve_count(plugin.as_raw()) },
+            }
+        }
+
+        /// Renders the plugin's curves into the given buffer, and returns the rendered curves.
+        ///
+        /// The curves are meant to be stacked: the first curve is painted first, and each
+        /// following curve is painted over the previous ones.
+        ///
+        /// This must only be called while the curves are observed (see
+        /// [`set_observed`](PluginMiniCurveDisplay::set_observed)).
+        pub fn render<'b>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            buffer: &'b mut MiniCurveRenderBuffer,
+        ) -> &'b [MiniCurve] {
+            let Some(render) = plugin.use_extension(&self.0).render else {
+                return &[];
+            };
+
+            buffer.reset();
+            let curves = buffer.curves.as_mut_slice();
+
+            // SAFETY: This type ensures the function pointer is valid. The curves are all valid,
+            // and MiniCurve is repr(transparent).
+            let rendered = unsafe {
+                render(
+                    plugin.as_raw(),
+                    curves.as_mut_ptr().cast(),
+                    curves.len() as u32,
+                )
+            };
+
+            &buffer.curves[..(rendered as usize).min(buffer.curves.len())]
+        }
+
+        /// Informs the plugin whether its curves are currently observed.
+        ///
+        /// When the curves become observed, their contents and axis names are implicitly
+        /// invalidated, and should be fetched again.
+        #[inline]
+        pub fn set_observed(&self, plugin: &mut PluginMainThreadHandle, is_observed: bool) {
+            if let Some(set_observed) = plugin.use_extension(&self.0).set_observed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { set_observed(plugin.as_raw(), is_observed) }
+            }
+        }
+
+        /// Retrieves the names of the axes of the curve at the given index, using the given
+        /// buffer.
+        ///
+        /// This returns [`None`] if the plugin failed to provide the names.
+        pub fn get_axis_name<'b>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            curve_index: u32,
+            buffer: &'b mut MiniCurveAxisNamesBuffer,
+        ) -> Option<MiniCurveAxisNames<'b>> {
+            let get_axis_name = plugin.use_extension(&self.0).get_axis_name?;
+
+            // SAFETY: This type ensures the function pointer is valid. Both buffers are valid for
+            // CLAP_NAME_SIZE bytes.
+            let success = unsafe {
+                get_axis_name(
+                    plugin.as_raw(),
+                    curve_index,
+                    buffer.x_name.as_mut_ptr().cast::<c_char>(),
+                    buffer.y_name.as_mut_ptr().cast::<c_char>(),
+                    CLAP_NAME_SIZE as u32,
+                )
+            };
+
+            success.then(|| MiniCurveAxisNames {
+                x_name: read_name(&buffer.x_name),
+                y_name: read_name(&buffer.y_name),
+            })
+        }
+    }
+
+    /// Implementation of the Host-side of the Mini Curve Display extension.
+    pub trait HostMiniCurveDisplayImpl {
+        /// Returns the ranges the host uses for the axes of the given kind of curve, if any.
+        fn get_hints(&mut self, kind: MiniCurveKind) -> Option<MiniCurveHints>;
+        /// Called when the plugin marks its curves as static or dynamic.
+        ///
+        /// Static curves are only re-rendered when the plugin calls
+        /// [`changed`](HostMiniCurveDisplayImpl::changed), while dynamic curves should be
+        /// periodically re-rendered by the host.
+        fn set_dynamic(&mut self, is_dynamic: bool);
+        /// Called when the plugin's curves or axis names changed.
+        fn changed(&mut self, flags: MiniCurveChangeFlags);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostMiniCurveDisplay
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostMiniCurveDisplayImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_mini_curve_display {
+                get_hints: Some(get_hints::<H>),
+                set_dynamic: Some(set_dynamic::<H>),
+                changed: Some(changed::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_hints<H: HostHandlers>(
+        host: *const clap_host,
+        kind: u32,
+        hints: *mut clap_mini_curve_display_curve_hints,
+    ) -> bool
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostMiniCurveDisplayImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            if hints.is_null() {
+                return Err(HostWrapperError::InvalidParameter(
+                    "Null clap_mini_curve_display_curve_hints",
+                ));
+            }
+
+            let kind = i32::try_from(kind)
+                .ok()
+                .and_then(MiniCurveKind::from_raw)
+                .ok_or(HostWrapperError::InvalidParameter("Unknown curve kind"))?;
+
+            match host.main_thread().as_mut().get_hints(kind) {
+                None => Ok(false),
+                Some(value) => {
+                    hints.write(value.to_raw());
+                    Ok(true)
+                }
+            }
+        })
+        .unwrap_or(false)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn set_dynamic<H: HostHandlers>(host: *const clap_host, is_dynamic: bool)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostMiniCurveDisplayImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().set_dynamic(is_dynamic);
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn changed<H: HostHandlers>(host: *const clap_host, flags: u32)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostMiniCurveDisplayImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread()
+                .as_mut()
+                .changed(MiniCurveChangeFlags::from_bits_truncate(flags));
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use crate::utils::slice_from_external_parts_mut;
+    use clack_plugin::extensions::prelude::*;
+    use std::ffi::c_char;
+    use std::mem::MaybeUninit;
+
+    impl HostMiniCurveDisplay {
+        /// Returns the ranges the host uses for the axes of the given kind of curve, if any.
+        pub fn get_hints(
+            &self,
+            host: &mut HostMainThreadHandle,
+            kind: MiniCurveKind,
+        ) -> Option<MiniCurveHints> {
+            let get_hints = host.use_extension(&self.0).get_hints?;
+            let mut hints = MaybeUninit::<clap_mini_curve_display_curve_hints>::uninit();
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let success =
+                unsafe { get_hints(host.as_raw(), kind.to_raw() as u32, hints.as_mut_ptr()) };
+
+            // SAFETY: The host successfully initialized the hints.
+            success.then(|| MiniCurveHints::from_raw(unsafe { hints.assume_init_ref() }))
+        }
+
+        /// Marks the plugin's curves as static or dynamic.
+        ///
+        /// Curves are initially considered static, but plugins should still explicitly set this.
+        #[inline]
+        pub fn set_dynamic(&self, host: &mut HostMainThreadHandle, is_dynamic: bool) {
+            if let Some(set_dynamic) = host.use_extension(&self.0).set_dynamic {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { set_dynamic(host.as_raw(), is_dynamic) }
+            }
+        }
+
+        /// Informs the host that the plugin's curves or axis names changed.
+        #[inline]
+        pub fn changed(&self, host: &mut HostMainThreadHandle, flags: MiniCurveChangeFlags) {
+            if let Some(changed) = host.use_extension(&self.0).changed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { changed(host.as_raw(), flags.bits()) }
+            }
+        }
+    }
+
+    /// A writer the plugin uses to provide the names of a curve's axes to the host.
+    pub struct MiniCurveAxisNamesWriter<'a> {
+        x_name: &'a mut [u8],
+        y_name: &'a mut [u8],
+        is_set: bool,
+    }
+
+    impl MiniCurveAxisNamesWriter<'_> {
+        /// Writes the given axis names.
+        ///
+        /// If either name doesn't fit in the host-provided buffers, nothing is written and the
+        /// call is considered to have failed.
+        pub fn set(&mut self, names: &MiniCurveAxisNames) {
+            if names.x_name.len() >= self.x_name.len() || names.y_name.len() >= self.y_name.len() {
+                return;
+            }
+
+            self.x_name[..names.x_name.len()].copy_from_slice(names.x_name);
+            self.x_name[names.x_name.len()] = 0;
+            self.y_name[..names.y_name.len()].copy_from_slice(names.y_name);
+            self.y_name[names.y_name.len()] = 0;
+
+            self.is_set = true;
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Mini Curve Display extension.
+    pub trait PluginMiniCurveDisplayImpl {
+        /// Returns the number of curves the plugin wants to display.
+        ///
+        /// The space available for the curves is small, so this should be kept low.
+        fn get_curve_count(&mut self) -> u32;
+        /// Renders the curves into the given host-provided curves, and returns the number of
+        /// curves that were rendered.
+        ///
+        /// The curves are stacked: the first curve is painted first, and each following curve is
+        /// painted over the previous ones.
+        fn render(&mut self, curves: &mut [MiniCurve]) -> u32;
+        /// Called when the host starts or stops observing the curves.
+        fn set_observed(&mut self, is_observed: bool);
+        /// Writes the names of the axes of the curve at the given index into the given writer.
+        ///
+        /// If nothing is written, the host will consider the call to have failed.
+        fn get_axis_name(&mut self, curve_index: u32, writer: &mut MiniCurveAxisNamesWriter);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginMiniCurveDisplay
+    where
+        for<'a> P::MainThread<'a>: PluginMiniCurveDisplayImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_mini_curve_display {
+                get_curve_count: Some(get_curve_count::<P>),
+                render: Some(render::<P>),
+                set_observed: Some(set_observed::<P>),
+                get_axis_name: Some(get_axis_name::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_curve_count<P: Plugin>(plugin: *const clap_plugin) -> u32
+    where
+        for<'a> P::MainThread<'a>: PluginMiniCurveDisplayImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| Ok(p.main_thread().as_mut().get_curve_count()))
+            .unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn render<P: Plugin>(
+        plugin: *const clap_plugin,
+        curves: *mut clap_mini_curve_display_curve_data,
+        curves_size: u32,
+    ) -> u32
+    where
+        for<'a> P::MainThread<'a>: PluginMiniCurveDisplayImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            let curves =
+                slice_from_external_parts_mut(curves.cast::<MiniCurve>(), curves_size as usize);
+
+            let rendered = p.main_thread().as_mut().render(curves);
+            Ok(rendered.min(curves_size))
+        })
+        .unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn set_observed<P: Plugin>(plugin: *const clap_plugin, is_observed: bool)
+    where
+        for<'a> P::MainThread<'a>: PluginMiniCurveDisplayImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            p.main_thread().as_mut().set_observed(is_observed);
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_axis_name<P: Plugin>(
+        plugin: *const clap_plugin,
+        curve_index: u32,
+        x_name: *mut c_char,
+        y_name: *mut c_char,
+        name_capacity: u32,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginMiniCurveDisplayImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if x_name.is_null() || y_name.is_null() {
+                return Err(PluginWrapperError::NulPtr("Axis name"));
+            }
+
+            let mut writer = MiniCurveAxisNamesWriter {
+                x_name: slice_from_external_parts_mut(x_name.cast(), name_capacity as usize),
+                y_name: slice_from_external_parts_mut(y_name.cast(), name_capacity as usize),
+                is_set: false,
+            };
+
+            p.main_thread()
+                .as_mut()
+                .get_axis_name(curve_index, &mut writer);
+            Ok(writer.is_set)
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,249 @@
+#![deny(missing_docs)]
+
+//! Allows the host to tell the plugin how its parameters are mapped to physical controllers, and
+//! what their automation state is.
+//!
+//! This information can then be displayed by the plugin in its GUI, e.g. by drawing a colored
+//! outline around a mapped knob, or by showing an indicator when a parameter is being automated.
+//!
+//! See the [Params](crate::params) extension for the parameters themselves.
+
+use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::param_indication::*;
+use std::ffi::CStr;
+
+/// The Plugin-side of the Param Indication extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginParamIndication(RawExtension<PluginExtensionSide, clap_plugin_param_indication>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginParamIndication {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_PARAM_INDICATION;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The automation state of a parameter, as indicated by the host.
+#[repr(u32)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum AutomationState {
+    /// The host has no automation for this parameter.
+    #[default]
+    None = CLAP_PARAM_INDICATION_AUTOMATION_NONE,
+    /// The host has automation for this parameter, but it isn't playing.
+    Present = CLAP_PARAM_INDICATION_AUTOMATION_PRESENT,
+    /// The host is playing automation for this parameter.
+    Playing = CLAP_PARAM_INDICATION_AUTOMATION_PLAYING,
+    /// The host is recording automation for this parameter.
+    Recording = CLAP_PARAM_INDICATION_AUTOMATION_RECORDING,
+    /// The host should play automation for this parameter, but the user has started to adjust it
+    /// and is overriding it.
+    Overriding = CLAP_PARAM_INDICATION_AUTOMATION_OVERRIDING,
+}
+
+impl AutomationState {
+    /// Reads the automation state from the raw C-FFI-compatible integer type.
+    ///
+    /// This may return [`None`] if the given integer's value doesn't match any known state.
+    #[inline]
+    pub const fn from_raw(raw: u32) -> Option<Self> {
+        match raw {
+            CLAP_PARAM_INDICATION_AUTOMATION_NONE => Some(Self::None),
+            CLAP_PARAM_INDICATION_AUTOMATION_PRESENT => Some(Self::Present),
+            CLAP_PARAM_INDICATION_AUTOMATION_PLAYING => Some(Self::Playing),
+            CLAP_PARAM_INDICATION_AUTOMATION_RECORDING => Some(Self::Recording),
+            CLAP_PARAM_INDICATION_AUTOMATION_OVERRIDING => Some(Self::Overriding),
+            _ => None,
+        }
+    }
+
+    /// Returns the automation state as the raw C-FFI-compatible integer type.
+    #[inline]
+    pub const fn to_raw(self) -> u32 {
+        self as _
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_common::utils::{ClapId, Color};
+    use clack_host::extensions::prelude::*;
+
+    impl PluginParamIndication {
+        /// Informs the plugin whether the given parameter is mapped to a physical controller.
+        ///
+        /// The `color`, `label` and `description` are optional hints for the plugin to display the
+        /// mapping, and are ignored if `has_mapping` is `false`. The label is meant to be a short
+        /// indication (e.g. `"CC 7"`), while the description can be longer.
+        pub fn set_mapping(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            param_id: ClapId,
+            has_mapping: bool,
+            color: Option<Color>,
+            label: Option<&CStr>,
+            description: Option<&CStr>,
+        ) {
+            let Some(set_mapping) = plugin.use_extension(&self.0).set_mapping else {
+                return;
+            };
+
+            let color = color.map(Color::to_raw);
+
+            // SAFETY: This type ensures the function pointer is valid. All given pointers are
+            // either null or valid for the duration of the call.
+            unsafe {
+                set_mapping(
+                    plugin.as_raw(),
+                    param_id.get(),
+                    has_mapping,
+                    color.as_ref().map_or(core::ptr::null(), |c| c),
+                    label.map_or(core::ptr::null(), CStr::as_ptr),
+                    description.map_or(core::ptr::null(), CStr::as_ptr),
+                )
+            }
+        }
+
+        /// Informs the plugin of the automation state of the given parameter.
+        ///
+        /// The `color` is an optional hint for the plugin to display the automation state.
+        pub fn set_automation(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            param_id: ClapId,
+            automation_state: AutomationState,
+            color: Option<Color>,
+        ) {
+            let Some(set_automation) = plugin.use_extension(&self.0).set_automation else {
+                return;
+            };
+
+            let color = color.map(Color::to_raw);
+
+            // SAFETY: This type ensures the function pointer is valid. The color pointer is either
+            // null or valid for the duration of the call.
+            unsafe {
+                set_automation(
+                    plugin.as_raw(),
+                    param_id.get(),
+                    automation_state.to_raw(),
+                    color.as_ref().map_or(core::ptr::null(), |c| c),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_common::utils::{cstr_from_nullable_ptr, ClapId, Color};
+    use clack_plugin::extensions::prelude::*;
+    use clap_sys::color::clap_color;
+    use std::os::raw::c_char;
+
+    /// Implementation of the Plugin-side of the Param Indication extension.
+    pub trait PluginParamIndicationImpl {
+        /// Sets whether the given parameter is mapped to a physical controller.
+        ///
+        /// The `color`, `label` and `description` are optional hints for the plugin to display the
+        /// mapping, and must be ignored if `has_mapping` is `false`.
+        fn set_mapping(
+            &mut self,
+            param_id: ClapId,
+            has_mapping: bool,
+            color: Option<Color>,
+            label: Option<&CStr>,
+            description: Option<&CStr>,
+        );
+
+        /// Sets the automation state of the given parameter.
+        ///
+        /// The `color` is an optional hint for the plugin to display the automation state.
+        fn set_automation(
+            &mut self,
+            param_id: ClapId,
+            automation_state: AutomationState,
+            color: Option<Color>,
+        );
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginParamIndication
+    where
+        for<'a> P::MainThread<'a>: PluginParamIndicationImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_param_indication {
+                set_mapping: Some(set_mapping::<P>),
+                set_automation: Some(set_automation::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn set_mapping<P: Plugin>(
+        plugin: *const clap_plugin,
+        param_id: u32,
+        has_mapping: bool,
+        color: *const clap_color,
+        label: *const c_char,
+        description: *const c_char,
+    ) where
+        for<'a> P::MainThread<'a>: PluginParamIndicationImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            let param_id = ClapId::from_raw(param_id)
+                .ok_or(PluginWrapperError::InvalidParameter("Invalid parameter ID"))?;
+
+            let label = cstr_from_nullable_ptr(label);
+            let description = cstr_from_nullable_ptr(description);
+
+            plugin.main_thread().as_mut().set_mapping(
+                param_id,
+                has_mapping,
+                color.as_ref().map(|c| Color::from_raw(*c)),
+                label,
+                description,
+            );
+
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn set_automation<P: Plugin>(
+        plugin: *const clap_plugin,
+        param_id: u32,
+        automation_state: u32,
+        color: *const clap_color,
+    ) where
+        for<'a> P::MainThread<'a>: PluginParamIndicationImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            let param_id = ClapId::from_raw(param_id)
+                .ok_or(PluginWrapperError::InvalidParameter("Invalid parameter ID"))?;
+
+            let automation_state = AutomationState::from_raw(automation_state).ok_or(
+                PluginWrapperError::InvalidParameter("Invalid parameter automation state"),
+            )?;
+
+            plugin.main_thread().as_mut().set_automation(
+                param_id,
+                automation_state,
+                color.as_ref().map(|c| Color::from_raw(*c)),
+            );
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
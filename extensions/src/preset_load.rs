@@ -0,0 +1,325 @@
+#![deny(missing_docs)]
+
+//! Allows the host to ask the plugin to load a preset from a given location.
+//!
+//! Presets are identified by a [`PresetLocation`], which is either a file on disk or a location
+//! internal to the plugin (e.g. factory presets bundled in the plugin's binary), and an optional
+//! load key, which identifies a preset inside a location that may contain several of them.
+//!
+//! Plugins report the outcome of a load back to the host using the `loaded` and `on_error` calls.
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::preset_load::*;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+
+/// The Plugin-side of the Preset Load extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginPresetLoad(RawExtension<PluginExtensionSide, clap_plugin_preset_load>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginPresetLoad {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_PRESET_LOAD;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Preset Load extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostPresetLoad(RawExtension<HostExtensionSide, clap_host_preset_load>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostPresetLoad {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_PRESET_LOAD;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The location of a preset.
+///
+/// This is the same type as the [`Location`](clack_common::factory::preset_discovery::Location)
+/// used by the Preset Discovery factory.
+pub use clack_common::factory::preset_discovery::Location as PresetLocation;
+
+/// An error that can occur as the plugin loads a preset.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct PresetLoadError;
+
+impl Display for PresetLoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Failed to load plugin preset")
+    }
+}
+
+impl Error for PresetLoadError {}
+
+#[cfg(any(feature = "clack-host", feature = "clack-plugin"))]
+#[inline]
+fn load_key_ptr(load_key: Option<&CStr>) -> *const std::ffi::c_char {
+    load_key.map_or(core::ptr::null(), CStr::as_ptr)
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_common::utils::cstr_from_nullable_ptr;
+    use clack_host::extensions::prelude::*;
+    use clap_sys::factory::draft::preset_discovery::clap_preset_discovery_location_kind;
+    use std::ffi::c_char;
+
+    impl PluginPresetLoad {
+        /// Asks the plugin to load the preset at the given location.
+        ///
+        /// The `load_key` identifies the preset inside the location, if the location contains
+        /// several of them.
+        ///
+        /// Note that the plugin may load the preset asynchronously, in which case it will later
+        /// notify the host of the outcome through the [`HostPresetLoadImpl`] callbacks.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`PresetLoadError`] if the plugin failed or declined to load the preset.
+        pub fn from_location(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            location: PresetLocation,
+            load_key: Option<&CStr>,
+        ) -> Result<(), PresetLoadError> {
+            let from_location = plugin
+                .use_extension(&self.0)
+                .from_location
+                .ok_or(PresetLoadError)?;
+
+            // SAFETY: This type ensures the function pointer is valid. All given pointers are
+            // either null or valid for the duration of the call.
+            let success = unsafe {
+                from_location(
+                    plugin.as_raw(),
+                    location.kind(),
+                    location.location_ptr(),
+                    load_key_ptr(load_key),
+                )
+            };
+
+            match success {
+                true => Ok(()),
+                false => Err(PresetLoadError),
+            }
+        }
+    }
+
+    /// Implementation of the Host-side of the Preset Load extension.
+    pub trait HostPresetLoadImpl {
+        /// Called by the plugin when it failed to load the given preset.
+        ///
+        /// The `os_error` is the operating system error code, if applicable, or `0` otherwise.
+        /// The `message` is a user-friendly description of the error, if available.
+        fn on_error(
+            &mut self,
+            location: PresetLocation,
+            load_key: Option<&CStr>,
+            os_error: i32,
+            message: Option<&CStr>,
+        );
+
+        /// Called by the plugin when it successfully loaded the given preset.
+        fn loaded(&mut self, location: PresetLocation, load_key: Option<&CStr>);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostPresetLoad
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostPresetLoadImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_preset_load {
+                on_error: Some(on_error::<H>),
+                loaded: Some(loaded::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn on_error<H: HostHandlers>(
+        host: *const clap_host,
+        location_kind: clap_preset_discovery_location_kind,
+        location: *const c_char,
+        load_key: *const c_char,
+        os_error: i32,
+        msg: *const c_char,
+    ) where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostPresetLoadImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            let location = PresetLocation::from_raw(location_kind, location).ok_or(
+                HostWrapperError::InvalidParameter("Invalid preset location"),
+            )?;
+
+            host.main_thread().as_mut().on_error(
+                location,
+                cstr_from_nullable_ptr(load_key),
+                os_error,
+                cstr_from_nullable_ptr(msg),
+            );
+
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn loaded<H: HostHandlers>(
+        host: *const clap_host,
+        location_kind: clap_preset_discovery_location_kind,
+        location: *const c_char,
+        load_key: *const c_char,
+    ) where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostPresetLoadImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            let location = PresetLocation::from_raw(location_kind, location).ok_or(
+                HostWrapperError::InvalidParameter("Invalid preset location"),
+            )?;
+
+            host.main_thread()
+                .as_mut()
+                .loaded(location, cstr_from_nullable_ptr(load_key));
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_common::utils::cstr_from_nullable_ptr;
+    use clack_plugin::extensions::prelude::*;
+    use clap_sys::factory::draft::preset_discovery::clap_preset_discovery_location_kind;
+    use std::ffi::c_char;
+
+    impl HostPresetLoad {
+        /// Notifies the host that the plugin failed to load the given preset.
+        ///
+        /// The `os_error` is the operating system error code, if applicable, or `0` otherwise.
+        /// The `message` is a user-friendly description of the error.
+        pub fn on_error(
+            &self,
+            host: &mut HostMainThreadHandle,
+            location: PresetLocation,
+            load_key: Option<&CStr>,
+            os_error: i32,
+            message: &CStr,
+        ) {
+            if let Some(on_error) = host.use_extension(&self.0).on_error {
+                // SAFETY: This type ensures the function pointer is valid. All given pointers are
+                // either null or valid for the duration of the call.
+                unsafe {
+                    on_error(
+                        host.as_raw(),
+                        location.kind(),
+                        location.location_ptr(),
+                        load_key_ptr(load_key),
+                        os_error,
+                        message.as_ptr(),
+                    )
+                }
+            }
+        }
+
+        /// Notifies the host that the plugin successfully loaded the given preset.
+        pub fn loaded(
+            &self,
+            host: &mut HostMainThreadHandle,
+            location: PresetLocation,
+            load_key: Option<&CStr>,
+        ) {
+            if let Some(loaded) = host.use_extension(&self.0).loaded {
+                // SAFETY: This type ensures the function pointer is valid. All given pointers are
+                // either null or valid for the duration of the call.
+                unsafe {
+                    loaded(
+                        host.as_raw(),
+                        location.kind(),
+                        location.location_ptr(),
+                        load_key_ptr(load_key),
+                    )
+                }
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Preset Load extension.
+    pub trait PluginPresetLoadImpl {
+        /// Loads the preset at the given location.
+        ///
+        /// The `load_key` identifies the preset inside the location, if the location contains
+        /// several of them.
+        ///
+        /// The plugin may load the preset asynchronously, in which case it must notify the host
+        /// of the outcome through the [`HostPresetLoad`] extension.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the preset could not be loaded.
+        #[allow(clippy::wrong_self_convention)] // Matches the CLAP function name.
+        fn from_location(
+            &mut self,
+            location: PresetLocation,
+            load_key: Option<&CStr>,
+        ) -> Result<(), PluginError>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginPresetLoad
+    where
+        for<'a> P::MainThread<'a>: PluginPresetLoadImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_preset_load {
+                from_location: Some(from_location::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn from_location<P: Plugin>(
+        plugin: *const clap_plugin,
+        location_kind: clap_preset_discovery_location_kind,
+        location: *const c_char,
+        load_key: *const c_char,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginPresetLoadImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            let location = PresetLocation::from_raw(location_kind, location).ok_or(
+                PluginWrapperError::InvalidParameter("Invalid preset location"),
+            )?;
+
+            plugin
+                .main_thread()
+                .as_mut()
+                .from_location(location, cstr_from_nullable_ptr(load_key))?;
+
+            Ok(())
+        })
+        .is_some()
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,374 @@
+//! Allows the host to tell the plugin where it sits within the project or session.
+//!
+//! The location is given as a path of [`ProjectLocationElement`]s, going from the project itself
+//! down to the device (i.e. the plugin instance) at the end of the path. Each element may carry a
+//! name, a color and its index within its parent element, which the plugin can use to e.g. adapt
+//! its GUI to the track it is on.
+
+#![deny(missing_docs)]
+
+use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
+use clack_common::utils::Color;
+use std::ffi::CStr;
+
+/// The C-FFI definitions of the Project Location extension.
+///
+/// This extension is not part of the version of `clap-sys` currently in use, and is therefore
+/// defined here, following the CLAP headers.
+#[allow(non_camel_case_types, dead_code)]
+mod sys {
+    use crate::utils::extension_id;
+    use clap_sys::color::clap_color;
+    use clap_sys::plugin::clap_plugin;
+    use clap_sys::string_sizes::{CLAP_NAME_SIZE, CLAP_PATH_SIZE};
+    use std::ffi::{c_char, CStr};
+
+    pub const CLAP_EXT_PROJECT_LOCATION: &CStr = extension_id(b"clap.project-location/2\0");
+
+    pub const CLAP_PROJECT_LOCATION_PROJECT: u32 = 1;
+    pub const CLAP_PROJECT_LOCATION_TRACK_GROUP: u32 = 2;
+    pub const CLAP_PROJECT_LOCATION_TRACK: u32 = 3;
+    pub const CLAP_PROJECT_LOCATION_DEVICE: u32 = 4;
+    pub const CLAP_PROJECT_LOCATION_NESTED_DEVICE_CHAIN: u32 = 5;
+
+    pub const CLAP_PROJECT_LOCATION_INSTRUMENT_TRACK: u32 = 1;
+    pub const CLAP_PROJECT_LOCATION_AUDIO_TRACK: u32 = 2;
+    pub const CLAP_PROJECT_LOCATION_HYBRID_TRACK: u32 = 3;
+    pub const CLAP_PROJECT_LOCATION_RETURN_TRACK: u32 = 4;
+    pub const CLAP_PROJECT_LOCATION_MASTER_TRACK: u32 = 5;
+
+    pub const CLAP_PROJECT_LOCATION_HAS_INDEX: u64 = 1 << 0;
+    pub const CLAP_PROJECT_LOCATION_HAS_COLOR: u64 = 1 << 1;
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_project_location_element {
+        pub flags: u64,
+        pub kind: u32,
+        pub track_kind: u32,
+        pub index: u32,
+        pub id: [c_char; CLAP_PATH_SIZE],
+        pub name: [c_char; CLAP_NAME_SIZE],
+        pub color: clap_color,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_project_location {
+        pub set: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                path: *const clap_project_location_element,
+                num_elements: u32,
+            ),
+        >,
+    }
+}
+
+use sys::*;
+
+/// The Plugin-side of the Project Location extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginProjectLocation(RawExtension<PluginExtensionSide, clap_plugin_project_location>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginProjectLocation {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_PROJECT_LOCATION;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The kind of an element of a project location.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ProjectLocationKind {
+    /// The document, project or session itself.
+    Project,
+    /// A group of tracks (e.g. a bus or a folder track).
+    ///
+    /// It can contain other track groups, tracks, and devices. The first device of a track group
+    /// has the index of the last track or track group of this group + 1.
+    TrackGroup,
+    /// A single track, which contains a serial chain of devices.
+    Track,
+    /// A single device, which can contain nested device chains.
+    Device,
+    /// A serial chain of devices nested within a device.
+    NestedDeviceChain,
+}
+
+impl ProjectLocationKind {
+    /// Gets a [`ProjectLocationKind`] from its raw, C-FFI compatible value.
+    ///
+    /// This returns [`None`] if the kind is unknown.
+    #[inline]
+    pub const fn from_raw(raw: u32) -> Option<Self> {
+        match raw {
+            CLAP_PROJECT_LOCATION_PROJECT => Some(Self::Project),
+            CLAP_PROJECT_LOCATION_TRACK_GROUP => Some(Self::TrackGroup),
+            CLAP_PROJECT_LOCATION_TRACK => Some(Self::Track),
+            CLAP_PROJECT_LOCATION_DEVICE => Some(Self::Device),
+            CLAP_PROJECT_LOCATION_NESTED_DEVICE_CHAIN => Some(Self::NestedDeviceChain),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw, C-FFI compatible value of this kind.
+    #[inline]
+    pub const fn to_raw(self) -> u32 {
+        match self {
+            Self::Project => CLAP_PROJECT_LOCATION_PROJECT,
+            Self::TrackGroup => CLAP_PROJECT_LOCATION_TRACK_GROUP,
+            Self::Track => CLAP_PROJECT_LOCATION_TRACK,
+            Self::Device => CLAP_PROJECT_LOCATION_DEVICE,
+            Self::NestedDeviceChain => CLAP_PROJECT_LOCATION_NESTED_DEVICE_CHAIN,
+        }
+    }
+}
+
+/// The kind of a track in a project location.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ProjectLocationTrackKind {
+    /// An instrument track.
+    Instrument,
+    /// An audio track.
+    Audio,
+    /// A track which handles both notes and audio.
+    Hybrid,
+    /// A return (or effect) track.
+    Return,
+    /// The master track.
+    Master,
+}
+
+impl ProjectLocationTrackKind {
+    /// Gets a [`ProjectLocationTrackKind`] from its raw, C-FFI compatible value.
+    ///
+    /// This returns [`None`] if the track kind is unknown.
+    #[inline]
+    pub const fn from_raw(raw: u32) -> Option<Self> {
+        match raw {
+            CLAP_PROJECT_LOCATION_INSTRUMENT_TRACK => Some(Self::Instrument),
+            CLAP_PROJECT_LOCATION_AUDIO_TRACK => Some(Self::Audio),
+            CLAP_PROJECT_LOCATION_HYBRID_TRACK => Some(Self::Hybrid),
+            CLAP_PROJECT_LOCATION_RETURN_TRACK => Some(Self::Return),
+            CLAP_PROJECT_LOCATION_MASTER_TRACK => Some(Self::Master),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw, C-FFI compatible value of this track kind.
+    #[inline]
+    pub const fn to_raw(self) -> u32 {
+        match self {
+            Self::Instrument => CLAP_PROJECT_LOCATION_INSTRUMENT_TRACK,
+            Self::Audio => CLAP_PROJECT_LOCATION_AUDIO_TRACK,
+            Self::Hybrid => CLAP_PROJECT_LOCATION_HYBRID_TRACK,
+            Self::Return => CLAP_PROJECT_LOCATION_RETURN_TRACK,
+            Self::Master => CLAP_PROJECT_LOCATION_MASTER_TRACK,
+        }
+    }
+}
+
+/// An element of the path to a plugin's location within the project.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ProjectLocationElement {
+    /// The kind of this element.
+    pub kind: ProjectLocationKind,
+    /// The kind of track this element is, if known.
+    ///
+    /// This is only meaningful if [`kind`](ProjectLocationElement::kind) is
+    /// [`ProjectLocationKind::Track`].
+    pub track_kind: Option<ProjectLocationTrackKind>,
+    /// The index of this element within its parent element, if any.
+    pub index: Option<u32>,
+    /// The internal ID of this element.
+    ///
+    /// This is not meant to be displayed to the user, but can be used for quick lookups.
+    pub id: String,
+    /// The user-facing name of this element.
+    pub name: String,
+    /// The color of this element, if any.
+    pub color: Option<Color>,
+}
+
+impl ProjectLocationElement {
+    /// Creates a new element of the given kind, with no name, ID, index or color.
+    #[inline]
+    pub fn new(kind: ProjectLocationKind) -> Self {
+        Self {
+            kind,
+            track_kind: None,
+            index: None,
+            id: String::new(),
+            name: String::new(),
+            color: None,
+        }
+    }
+
+    /// Reads an element from the raw, C-FFI compatible struct.
+    ///
+    /// Any invalid UTF-8 in the ID or name is replaced with
+    /// [`U+FFFD REPLACEMENT CHARACTER`](char::REPLACEMENT_CHARACTER). This returns [`None`] if
+    /// the element's kind is unknown.
+    #[cfg(feature = "clack-plugin")]
+    fn from_raw(raw: &clap_project_location_element) -> Option<Self> {
+        let kind = ProjectLocationKind::from_raw(raw.kind)?;
+        let has = |flag| raw.flags & flag != 0;
+
+        let track_kind = if kind == ProjectLocationKind::Track {
+            ProjectLocationTrackKind::from_raw(raw.track_kind)
+        } else {
+            None
+        };
+
+        Some(Self {
+            kind,
+            track_kind,
+            index: has(CLAP_PROJECT_LOCATION_HAS_INDEX).then_some(raw.index),
+            id: String::from_utf8_lossy(crate::utils::data_from_array_buf(&raw.id)).into_owned(),
+            name: String::from_utf8_lossy(crate::utils::data_from_array_buf(&raw.name))
+                .into_owned(),
+            color: has(CLAP_PROJECT_LOCATION_HAS_COLOR).then(|| Color::from_raw(raw.color)),
+        })
+    }
+
+    /// Returns this element as the raw, C-FFI compatible struct.
+    ///
+    /// The ID and name are truncated if they don't fit in the raw struct's buffers.
+    #[cfg(feature = "clack-host")]
+    fn to_raw(&self) -> clap_project_location_element {
+        let mut flags = 0;
+        if self.index.is_some() {
+            flags |= CLAP_PROJECT_LOCATION_HAS_INDEX;
+        }
+        if self.color.is_some() {
+            flags |= CLAP_PROJECT_LOCATION_HAS_COLOR;
+        }
+
+        let mut raw = clap_project_location_element {
+            flags,
+            kind: self.kind.to_raw(),
+            track_kind: self.track_kind.map_or(0, ProjectLocationTrackKind::to_raw),
+            index: self.index.unwrap_or(0),
+            id: [0; clap_sys::string_sizes::CLAP_PATH_SIZE],
+            name: [0; clap_sys::string_sizes::CLAP_NAME_SIZE],
+            color: self.color.unwrap_or(Color::from_rgb(0, 0, 0)).to_raw(),
+        };
+
+        // SAFETY: both buffers are valid for writes, and don't overlap with the given strings.
+        unsafe {
+            crate::utils::write_to_array_buf(&mut raw.id, self.id.as_bytes());
+            crate::utils::write_to_array_buf(&mut raw.name, self.name.as_bytes());
+        }
+
+        raw
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+
+    impl PluginProjectLocation {
+        /// Informs the plugin of its location within the project.
+        ///
+        /// The path goes from the outermost element (usually the [`Project`]) to the plugin
+        /// itself, which must be the last element and of the [`Device`] kind.
+        ///
+        /// [`Project`]: ProjectLocationKind::Project
+        /// [`Device`]: ProjectLocationKind::Device
+        pub fn set(&self, plugin: &mut PluginMainThreadHandle, path: &[ProjectLocationElement]) {
+            let Some(set) = plugin.use_extension(&self.0).set else {
+                return;
+            };
+
+            let raw_path: Vec<_> = path.iter().map(ProjectLocationElement::to_raw).collect();
+
+            // SAFETY: This type ensures the function pointer is valid. The path buffer is valid
+            // for reads of its whole length for the duration of the call.
+            unsafe { set(plugin.as_raw(), raw_path.as_ptr(), raw_path.len() as u32) }
+        }
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use crate::utils::slice_from_external_parts;
+    use clack_plugin::extensions::prelude::*;
+
+    /// An iterator over the elements of the path to a plugin's location within the project,
+    /// from the outermost element to the plugin itself.
+    ///
+    /// Each element is read into an owned [`ProjectLocationElement`]. Elements of an unknown kind
+    /// are skipped.
+    #[derive(Clone)]
+    pub struct ProjectLocationElements<'a> {
+        raw: core::slice::Iter<'a, clap_project_location_element>,
+    }
+
+    impl Iterator for ProjectLocationElements<'_> {
+        type Item = ProjectLocationElement;
+
+        #[inline]
+        fn next(&mut self) -> Option<Self::Item> {
+            self.raw.by_ref().find_map(ProjectLocationElement::from_raw)
+        }
+
+        #[inline]
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (0, Some(self.raw.len()))
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Project Location extension.
+    pub trait PluginProjectLocationImpl {
+        /// Called when the location of the plugin within the project has changed.
+        ///
+        /// The last element of the path is the plugin itself.
+        fn set(&mut self, path: ProjectLocationElements);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginProjectLocation
+    where
+        for<'a> P::MainThread<'a>: PluginProjectLocationImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_project_location {
+                set: Some(set::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn set<P: Plugin>(
+        plugin: *const clap_plugin,
+        path: *const clap_project_location_element,
+        num_elements: u32,
+    ) where
+        for<'a> P::MainThread<'a>: PluginProjectLocationImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            if path.is_null() && num_elements > 0 {
+                return Err(PluginWrapperError::NulPtr("clap_project_location_element"));
+            }
+
+            let path = ProjectLocationElements {
+                raw: slice_from_external_parts(path, num_elements as usize).iter(),
+            };
+
+            plugin.main_thread().as_mut().set(path);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
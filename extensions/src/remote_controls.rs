@@ -0,0 +1,96 @@
+#![deny(missing_docs)]
+
+//! Allows plugins to expose pages of parameters, for hosts to map onto hardware controllers.
+//!
+//! Each [`RemoteControlsPage`] maps up to [`REMOTE_CONTROLS_COUNT`] parameters onto the knobs of
+//! a controller. Pages can be grouped into sections, and some of them may only be relevant to the
+//! currently loaded preset.
+//!
+//! Plugins notify the host that their pages have changed using the `changed` call, and can
+//! suggest the host to switch to a given page using the `suggest_page` call.
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clack_common::utils::ClapId;
+use clap_sys::ext::draft::remote_controls::*;
+use std::ffi::CStr;
+
+/// The maximum number of parameters that can be mapped in a single [`RemoteControlsPage`].
+pub const REMOTE_CONTROLS_COUNT: usize = CLAP_REMOTE_CONTROLS_COUNT;
+
+/// The Plugin-side of the Remote Controls extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginRemoteControls(RawExtension<PluginExtensionSide, clap_plugin_remote_controls>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginRemoteControls {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_REMOTE_CONTROLS;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Remote Controls extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostRemoteControls(RawExtension<HostExtensionSide, clap_host_remote_controls>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostRemoteControls {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_REMOTE_CONTROLS;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// A page of parameters to be mapped onto a hardware controller.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct RemoteControlsPage {
+    /// The name of the section this page belongs to.
+    pub section_name: String,
+    /// The unique identifier of this page.
+    pub page_id: ClapId,
+    /// The user-facing name of this page.
+    pub page_name: String,
+    /// The IDs of the parameters mapped on this page, or [`None`] for unmapped controls.
+    pub param_ids: [Option<ClapId>; REMOTE_CONTROLS_COUNT],
+    /// Whether this page is specific to the currently loaded preset.
+    pub is_for_preset: bool,
+}
+
+impl RemoteControlsPage {
+    /// Reads a page from the raw, C-FFI compatible struct.
+    ///
+    /// Any invalid UTF-8 in the page or section names is replaced with
+    /// [`U+FFFD REPLACEMENT CHARACTER`](char::REPLACEMENT_CHARACTER).
+    ///
+    /// This returns [`None`] if the page ID is invalid.
+    pub fn from_raw(raw: &clap_remote_controls_page) -> Option<Self> {
+        use crate::utils::data_from_array_buf;
+
+        Some(Self {
+            section_name: String::from_utf8_lossy(data_from_array_buf(&raw.section_name))
+                .into_owned(),
+            page_id: ClapId::from_raw(raw.page_id)?,
+            page_name: String::from_utf8_lossy(data_from_array_buf(&raw.page_name)).into_owned(),
+            param_ids: raw.param_ids.map(ClapId::from_raw),
+            is_for_preset: raw.is_for_preset,
+        })
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host;
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin;
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,97 @@
+use super::*;
+use clack_host::extensions::prelude::*;
+use std::mem::MaybeUninit;
+
+impl PluginRemoteControls {
+    /// Returns the number of [`RemoteControlsPage`]s the plugin exposes.
+    pub fn count(&self, plugin: &mut PluginMainThreadHandle) -> u32 {
+        match plugin.use_extension(&self.0).count {
+            None => 0,
+            // SAFETY: This type ensures the function pointer is valid.
+            Some(count) => unsafe { count(plugin.as_raw()) },
+        }
+    }
+
+    /// Retrieves the [`RemoteControlsPage`] at the given index.
+    ///
+    /// This returns [`None`] if the plugin failed to provide the page, or if it provided a page
+    /// with an invalid ID.
+    pub fn get(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        page_index: u32,
+    ) -> Option<RemoteControlsPage> {
+        let mut page = MaybeUninit::zeroed();
+
+        // SAFETY: This type ensures the function pointer is valid.
+        let success = unsafe {
+            plugin.use_extension(&self.0).get?(plugin.as_raw(), page_index, page.as_mut_ptr())
+        };
+
+        if success {
+            // SAFETY: we just checked the buffer was successfully written to.
+            RemoteControlsPage::from_raw(unsafe { page.assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Retrieves all of the [`RemoteControlsPage`]s the plugin exposes.
+    ///
+    /// Pages the plugin failed to provide are skipped.
+    pub fn get_all_pages(&self, plugin: &mut PluginMainThreadHandle) -> Vec<RemoteControlsPage> {
+        (0..self.count(plugin))
+            .filter_map(|index| self.get(plugin, index))
+            .collect()
+    }
+}
+
+/// Implementation of the Host-side of the Remote Controls extension.
+pub trait HostRemoteControlsImpl {
+    /// Informs the host that the plugin's remote controls pages have changed, and need to be
+    /// rescanned.
+    fn changed(&mut self);
+
+    /// Suggests the host to show the page with the given ID.
+    fn suggest_page(&mut self, page_id: ClapId);
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostRemoteControls
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostRemoteControlsImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_host_remote_controls {
+            changed: Some(changed::<H>),
+            suggest_page: Some(suggest_page::<H>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn changed<H: HostHandlers>(host: *const clap_host)
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostRemoteControlsImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        host.main_thread().as_mut().changed();
+
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn suggest_page<H: HostHandlers>(host: *const clap_host, page_id: u32)
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostRemoteControlsImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        let page_id = ClapId::from_raw(page_id)
+            .ok_or(HostWrapperError::InvalidParameter("Invalid page ID"))?;
+
+        host.main_thread().as_mut().suggest_page(page_id);
+
+        Ok(())
+    });
+}
@@ -0,0 +1,125 @@
+use super::*;
+use crate::utils::write_to_array_buf;
+use clack_plugin::extensions::prelude::*;
+use std::mem::MaybeUninit;
+use std::ptr::addr_of_mut;
+
+/// A host-provided buffer for the plugin to write a [`RemoteControlsPage`] into.
+pub struct RemoteControlsPageWriter<'a> {
+    buf: &'a mut MaybeUninit<clap_remote_controls_page>,
+    is_set: bool,
+}
+
+impl RemoteControlsPageWriter<'_> {
+    /// # Safety
+    ///
+    /// The user must ensure the provided pointer is aligned and points to a valid allocation.
+    /// However, it doesn't have to be initialized.
+    unsafe fn new(raw: *mut clap_remote_controls_page) -> Self {
+        Self {
+            // SAFETY: MaybeUninit<T> and T have same memory representation
+            buf: unsafe { &mut *raw.cast() },
+            is_set: false,
+        }
+    }
+
+    /// Writes the given page into the host-provided buffer.
+    ///
+    /// Section and page names that are too long to fit in the buffer are truncated.
+    #[inline]
+    pub fn set(&mut self, page: &RemoteControlsPage) {
+        let buf = self.buf.as_mut_ptr();
+
+        // SAFETY: all pointers come from `buf`, which is valid for writes and well-aligned
+        unsafe {
+            addr_of_mut!((*buf).page_id).write(page.page_id.get());
+            addr_of_mut!((*buf).param_ids).write(page.param_ids.map(ClapId::optional_to_raw));
+            addr_of_mut!((*buf).is_for_preset).write(page.is_for_preset);
+
+            write_to_array_buf(
+                addr_of_mut!((*buf).section_name),
+                page.section_name.as_bytes(),
+            );
+            write_to_array_buf(addr_of_mut!((*buf).page_name), page.page_name.as_bytes());
+        }
+
+        self.is_set = true;
+    }
+}
+
+impl HostRemoteControls {
+    /// Informs the host that the plugin's remote controls pages have changed, and need to be
+    /// rescanned.
+    #[inline]
+    pub fn changed(&self, host: &mut HostMainThreadHandle) {
+        if let Some(changed) = host.use_extension(&self.0).changed {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { changed(host.as_raw()) }
+        }
+    }
+
+    /// Suggests the host to show the page with the given ID.
+    #[inline]
+    pub fn suggest_page(&self, host: &mut HostMainThreadHandle, page_id: ClapId) {
+        if let Some(suggest_page) = host.use_extension(&self.0).suggest_page {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { suggest_page(host.as_raw(), page_id.get()) }
+        }
+    }
+}
+
+/// Implementation of the Plugin-side of the Remote Controls extension.
+pub trait PluginRemoteControlsImpl {
+    /// Returns the number of [`RemoteControlsPage`]s the plugin exposes.
+    fn count(&mut self) -> u32;
+
+    /// Retrieves the [`RemoteControlsPage`] at the given index.
+    ///
+    /// The plugin gets passed a host-provided mutable buffer to write the page into, to avoid any
+    /// unnecessary allocations.
+    fn get(&mut self, page_index: u32, writer: &mut RemoteControlsPageWriter);
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginRemoteControls
+where
+    for<'a> P::MainThread<'a>: PluginRemoteControlsImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_plugin_remote_controls {
+            count: Some(count::<P>),
+            get: Some(get::<P>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn count<P: Plugin>(plugin: *const clap_plugin) -> u32
+where
+    for<'a> P::MainThread<'a>: PluginRemoteControlsImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| Ok(p.main_thread().as_mut().count())).unwrap_or(0)
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn get<P: Plugin>(
+    plugin: *const clap_plugin,
+    page_index: u32,
+    page: *mut clap_remote_controls_page,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginRemoteControlsImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        if page.is_null() {
+            return Err(PluginWrapperError::NulPtr(
+                "clap_remote_controls_page output",
+            ));
+        }
+
+        let mut writer = RemoteControlsPageWriter::new(page);
+        p.main_thread().as_mut().get(page_index, &mut writer);
+        Ok(writer.is_set)
+    })
+    .unwrap_or(false)
+}
@@ -0,0 +1,382 @@
+#![deny(missing_docs)]
+
+//! Allows plugins to store files in a directory provided by the host, such as the project's
+//! folder.
+//!
+//! The plugin first requests a directory using [`HostResourceDirectory::request_directory`].
+//! The host then provides it through [`PluginResourceDirectory::set_directory`], and may move it
+//! at any time (e.g. when the project is saved elsewhere) by providing a new path.
+//!
+//! A directory can either be private to a plugin instance, or shared between all the instances
+//! of the project. The host can also ask the plugin to gather all the files it uses into the
+//! directory (see [`PluginResourceDirectory::collect`]), and then list them.
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::resource_directory::*;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+
+/// The Plugin-side of the Resource Directory extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginResourceDirectory(
+    RawExtension<PluginExtensionSide, clap_plugin_resource_directory>,
+);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginResourceDirectory {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_RESOURCE_DIRECTORY;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Resource Directory extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostResourceDirectory(RawExtension<HostExtensionSide, clap_host_resource_directory>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostResourceDirectory {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_RESOURCE_DIRECTORY;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// An error raised when a path cannot be passed through the CLAP API, because it is not valid
+/// UTF-8 or contains a nul byte.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct InvalidPathError;
+
+impl Display for InvalidPathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Path is not valid UTF-8 or contains a nul byte")
+    }
+}
+
+impl Error for InvalidPathError {}
+
+/// Converts the given path to a C string, as expected by the CLAP API.
+#[cfg(any(feature = "clack-host", feature = "clack-plugin"))]
+fn path_to_c_string(path: &std::path::Path) -> Result<std::ffi::CString, InvalidPathError> {
+    let path = path.to_str().ok_or(InvalidPathError)?;
+    std::ffi::CString::new(path).map_err(|_| InvalidPathError)
+}
+
+/// Reads a path from the given C string.
+#[cfg(any(feature = "clack-host", feature = "clack-plugin"))]
+fn path_from_c_str(path: &CStr) -> Option<&std::path::Path> {
+    path.to_str().ok().map(std::path::Path::new)
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+    use std::ffi::c_char;
+    use std::path::{Path, PathBuf};
+
+    /// The initial size of the buffer used to retrieve file paths from the plugin.
+    const INITIAL_PATH_BUFFER_SIZE: usize = 1024;
+
+    impl PluginResourceDirectory {
+        /// Provides the plugin with its resource directory, or [`None`] if the directory was
+        /// taken away.
+        ///
+        /// This may be called again with a different path if the host moved the directory.
+        ///
+        /// # Errors
+        ///
+        /// This returns an [`InvalidPathError`] if the given path cannot be passed to the plugin.
+        pub fn set_directory(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            path: Option<&Path>,
+            is_shared: bool,
+        ) -> Result<(), InvalidPathError> {
+            let path = path.map(path_to_c_string).transpose()?;
+
+            if let Some(set_directory) = plugin.use_extension(&self.0).set_directory {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe {
+                    set_directory(
+                        plugin.as_raw(),
+                        path.as_deref().map_or(core::ptr::null(), CStr::as_ptr),
+                        is_shared,
+                    )
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Asks the plugin to put all the files it uses into its resource directory.
+        ///
+        /// If `all` is `false`, only the files that aren't already in the directory are
+        /// collected.
+        #[inline]
+        pub fn collect(&self, plugin: &mut PluginMainThreadHandle, all: bool) {
+            if let Some(collect) = plugin.use_extension(&self.0).collect {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { collect(plugin.as_raw(), all) }
+            }
+        }
+
+        /// Returns the number of files the plugin uses.
+        #[inline]
+        pub fn get_files_count(&self, plugin: &mut PluginMainThreadHandle) -> u32 {
+            match plugin.use_extension(&self.0).get_files_count {
+                None => 0,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(get_files_count) => unsafe { get_files_count(plugin.as_raw()) },
+            }
+        }
+
+        /// Returns the path of the file at the given index.
+        ///
+        /// This returns [`None`] if the plugin failed to provide a valid, complete path.
+        pub fn get_file_path(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            index: u32,
+        ) -> Option<PathBuf> {
+            let get_file_path = plugin.use_extension(&self.0).get_file_path?;
+            let mut buffer = vec![0u8; INITIAL_PATH_BUFFER_SIZE];
+
+            // If the path is truncated, the plugin returns its full length, and the call is retried
+            // once with a large enough buffer.
+            for _ in 0..2 {
+                let buffer_size = u32::try_from(buffer.len()).ok()?;
+
+                // SAFETY: This type ensures the function pointer is valid.
+                let length = unsafe {
+                    get_file_path(
+                        plugin.as_raw(),
+                        index,
+                        buffer.as_mut_ptr().cast::<c_char>(),
+                        buffer_size,
+                    )
+                };
+                let length = usize::try_from(length).ok()?;
+
+                if length >= buffer.len() {
+                    buffer.resize(length + 1, 0);
+                    continue;
+                }
+
+                let path = CStr::from_bytes_until_nul(&buffer).ok()?;
+                return path_from_c_str(path).map(Path::to_path_buf);
+            }
+
+            None
+        }
+    }
+
+    /// Implementation of the Host-side of the Resource Directory extension.
+    pub trait HostResourceDirectoryImpl {
+        /// Requests the host to provide a resource directory, either shared or private to this
+        /// plugin instance.
+        ///
+        /// Returns `true` if the host will provide the directory.
+        fn request_directory(&mut self, is_shared: bool) -> bool;
+        /// Informs the host that the plugin no longer needs the given resource directory.
+        fn release_directory(&mut self, is_shared: bool);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostResourceDirectory
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostResourceDirectoryImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_resource_directory {
+                request_directory: Some(request_directory::<H>),
+                release_directory: Some(release_directory::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_directory<H: HostHandlers>(
+        host: *const clap_host,
+        is_shared: bool,
+    ) -> bool
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostResourceDirectoryImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            Ok(host.main_thread().as_mut().request_directory(is_shared))
+        })
+        .unwrap_or(false)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn release_directory<H: HostHandlers>(host: *const clap_host, is_shared: bool)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostResourceDirectoryImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().release_directory(is_shared);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use crate::utils::slice_from_external_parts_mut;
+    use clack_common::utils::cstr_from_nullable_ptr;
+    use clack_plugin::extensions::prelude::*;
+    use std::ffi::c_char;
+    use std::path::{Path, PathBuf};
+
+    impl HostResourceDirectory {
+        /// Requests the host to provide a resource directory, either shared between all the
+        /// plugin instances of the project, or private to this instance.
+        ///
+        /// Returns `true` if the host will provide the directory, through
+        /// [`PluginResourceDirectoryImpl::set_directory`].
+        #[inline]
+        pub fn request_directory(&self, host: &mut HostMainThreadHandle, is_shared: bool) -> bool {
+            match host.use_extension(&self.0).request_directory {
+                None => false,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(request_directory) => unsafe { request_directory(host.as_raw(), is_shared) },
+            }
+        }
+
+        /// Informs the host that the plugin no longer needs the given resource directory.
+        #[inline]
+        pub fn release_directory(&self, host: &mut HostMainThreadHandle, is_shared: bool) {
+            if let Some(release_directory) = host.use_extension(&self.0).release_directory {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { release_directory(host.as_raw(), is_shared) }
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Resource Directory extension.
+    pub trait PluginResourceDirectoryImpl {
+        /// Sets the resource directory, or [`None`] if the directory was taken away.
+        ///
+        /// This may be called again with a different path if the host moved the directory, in
+        /// which case the plugin should update the paths of the files it uses.
+        fn set_directory(&mut self, path: Option<&Path>, is_shared: bool);
+        /// Puts all the files the plugin uses into the resource directory.
+        ///
+        /// If `all` is `false`, only the files that aren't already in the directory need to be
+        /// collected.
+        fn collect(&mut self, all: bool);
+        /// Returns the number of files the plugin uses.
+        fn get_files_count(&mut self) -> u32;
+        /// Returns the path of the file at the given index, or [`None`] if there is none.
+        fn get_file_path(&mut self, index: u32) -> Option<PathBuf>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginResourceDirectory
+    where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_resource_directory {
+                set_directory: Some(set_directory::<P>),
+                collect: Some(collect::<P>),
+                get_files_count: Some(get_files_count::<P>),
+                get_file_path: Some(get_file_path::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn set_directory<P: Plugin>(
+        plugin: *const clap_plugin,
+        path: *const c_char,
+        is_shared: bool,
+    ) where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            let path = match cstr_from_nullable_ptr(path) {
+                None => None,
+                Some(path) => Some(path_from_c_str(path).ok_or(
+                    PluginWrapperError::InvalidParameter("Invalid directory path"),
+                )?),
+            };
+
+            p.main_thread().as_mut().set_directory(path, is_shared);
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn collect<P: Plugin>(plugin: *const clap_plugin, all: bool)
+    where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            p.main_thread().as_mut().collect(all);
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_files_count<P: Plugin>(plugin: *const clap_plugin) -> u32
+    where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| Ok(p.main_thread().as_mut().get_files_count()))
+            .unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_file_path<P: Plugin>(
+        plugin: *const clap_plugin,
+        index: u32,
+        path: *mut c_char,
+        path_size: u32,
+    ) -> i32
+    where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            let Some(file_path) = p.main_thread().as_mut().get_file_path(index) else {
+                return Ok(-1);
+            };
+
+            let file_path = path_to_c_string(&file_path)
+                .map_err(|_| PluginWrapperError::InvalidParameter("Invalid file path"))?;
+            let file_path = file_path.as_bytes();
+
+            // The full length is returned even if the path gets truncated, so that the host can
+            // retry with a large enough buffer.
+            let length = i32::try_from(file_path.len())
+                .map_err(|_| PluginWrapperError::InvalidParameter("File path is too long"))?;
+
+            let buffer = slice_from_external_parts_mut(path.cast::<u8>(), path_size as usize);
+            if let Some((nul, available)) = buffer.split_last_mut() {
+                let written = file_path.len().min(available.len());
+                available[..written].copy_from_slice(&file_path[..written]);
+                *available.get_mut(written).unwrap_or(nul) = 0;
+            }
+
+            Ok(length)
+        })
+        .unwrap_or(-1)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
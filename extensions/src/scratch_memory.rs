@@ -0,0 +1,304 @@
+//! Allows plugins to use temporary memory provided by the host during processing.
+//!
+//! Instead of each plugin allocating its own temporary buffers, the host can provide a single
+//! memory region shared between all the plugins it processes on a given thread.
+//!
+//! Plugins reserve the amount of scratch memory they need while being activated, using
+//! [`HostScratchMemory::reserve`], which returns a [`ScratchMemoryReservation`]. Using that
+//! reservation, they can then access that memory during each `process` call, through a
+//! [`ScratchArena`] which lends out properly aligned slices for the duration of that call.
+//!
+//! The contents of the scratch memory are not preserved between `process` calls.
+
+#![deny(missing_docs)]
+
+use clack_common::extensions::{Extension, HostExtensionSide, RawExtension};
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+
+/// The C-FFI definitions of the Scratch Memory extension.
+///
+/// This extension is not part of the version of `clap-sys` currently in use, and is therefore
+/// defined here, following the CLAP headers.
+#[allow(non_camel_case_types)]
+mod sys {
+    use crate::utils::extension_id;
+    use clap_sys::host::clap_host;
+    use std::ffi::{c_void, CStr};
+
+    pub const CLAP_EXT_SCRATCH_MEMORY: &CStr = extension_id(b"clap.scratch-memory/1\0");
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_host_scratch_memory {
+        pub reserve: Option<
+            unsafe extern "C" fn(
+                host: *const clap_host,
+                scratch_size_bytes: u32,
+                max_concurrency_hint: u32,
+            ) -> bool,
+        >,
+        pub access: Option<unsafe extern "C" fn(host: *const clap_host) -> *mut c_void>,
+    }
+}
+
+use sys::*;
+
+/// The Host-side of the Scratch Memory extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostScratchMemory(RawExtension<HostExtensionSide, clap_host_scratch_memory>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostScratchMemory {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_SCRATCH_MEMORY;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// A reservation of scratch memory, which was accepted by the host.
+///
+/// This is returned by [`HostScratchMemory::reserve`], and is needed to access the reserved
+/// memory.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ScratchMemoryReservation {
+    scratch_size_bytes: u32,
+}
+
+impl ScratchMemoryReservation {
+    /// Returns the size of the reserved scratch memory, in bytes.
+    #[inline]
+    pub fn size(&self) -> u32 {
+        self.scratch_size_bytes
+    }
+}
+
+/// A bump allocator over a region of scratch memory.
+///
+/// This lends out slices of the memory region, which all remain valid for as long as the arena's
+/// borrow of the memory does (i.e. for the duration of a single `process` call, when the memory
+/// is provided by the host). Memory is never reused within the lifetime of the arena.
+pub struct ScratchArena<'a> {
+    remaining: &'a mut [MaybeUninit<u8>],
+}
+
+impl<'a> ScratchArena<'a> {
+    /// Creates a new arena over the given memory region.
+    ///
+    /// This is useful to fall back to a plugin-owned buffer if the host doesn't support the
+    /// Scratch Memory extension.
+    #[inline]
+    pub fn new(memory: &'a mut [MaybeUninit<u8>]) -> Self {
+        Self { remaining: memory }
+    }
+
+    /// Returns the number of bytes that haven't been lent out yet.
+    ///
+    /// Because of alignment requirements, not all of those bytes may be usable.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.remaining.len()
+    }
+
+    /// Lends out a slice of `len` values, all initialized to the given `value`.
+    ///
+    /// This returns [`None`] if there isn't enough memory left in the arena.
+    pub fn alloc_slice<T: Copy>(&mut self, len: usize, value: T) -> Option<&'a mut [T]> {
+        let remaining = core::mem::take(&mut self.remaining);
+
+        let padding = remaining.as_ptr().align_offset(core::mem::align_of::<T>());
+        let total_size = len
+            .checked_mul(core::mem::size_of::<T>())
+            .and_then(|size| size.checked_add(padding))
+            .filter(|size| *size <= remaining.len());
+
+        let Some(total_size) = total_size else {
+            self.remaining = remaining;
+            return None;
+        };
+
+        let (slot, rest) = remaining.split_at_mut(total_size);
+        self.remaining = rest;
+
+        // SAFETY: The slot is at least `len * size_of::<T>()` bytes long after the padding, and
+        // the padding makes the pointer aligned for T.
+        let data = unsafe { slot.as_mut_ptr().add(padding).cast::<T>() };
+
+        for i in 0..len {
+            // SAFETY: See above, the pointer is valid for writes of `len` elements.
+            unsafe { data.add(i).write(value) };
+        }
+
+        // SAFETY: All `len` elements were just initialized, and the memory is exclusively
+        // borrowed for 'a, since it was split off of the remaining memory.
+        Some(unsafe { core::slice::from_raw_parts_mut(data, len) })
+    }
+
+    /// Lends out a slice of `len` samples, all initialized to `0.0`.
+    ///
+    /// This returns [`None`] if there isn't enough memory left in the arena.
+    #[inline]
+    pub fn alloc_f32(&mut self, len: usize) -> Option<&'a mut [f32]> {
+        self.alloc_slice(len, 0.0)
+    }
+
+    /// Lends out a slice of `len` samples, all initialized to `0.0`.
+    ///
+    /// This returns [`None`] if there isn't enough memory left in the arena.
+    #[inline]
+    pub fn alloc_f64(&mut self, len: usize) -> Option<&'a mut [f64]> {
+        self.alloc_slice(len, 0.0)
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+    use std::ffi::c_void;
+
+    /// Implementation of the Host-side of the Scratch Memory extension.
+    ///
+    /// This is implemented by the host's audio processor, which exists for as long as the plugin
+    /// is active.
+    pub trait HostScratchMemoryImpl {
+        /// Reserves the given amount of scratch memory for the plugin.
+        ///
+        /// `max_concurrency_hint` is the maximum number of threads that may access the scratch
+        /// memory concurrently, or `0` if unspecified. Any new call replaces the previous
+        /// reservations.
+        ///
+        /// This is called while the plugin is being activated. Returns `true` on success.
+        fn reserve(&mut self, scratch_size_bytes: u32, max_concurrency_hint: u32) -> bool;
+        /// Returns the size of the last successful reservation, in bytes, or `0` if none was made.
+        fn reserved_size(&self) -> u32;
+        /// Returns the scratch memory reserved for the plugin, or [`None`] if none was reserved.
+        ///
+        /// The returned slice must be at least [`reserved_size`](Self::reserved_size) bytes long,
+        /// otherwise no memory is provided to the plugin at all. The plugin may write to it until
+        /// the end of the current `process` call.
+        fn access(&mut self) -> Option<&mut [MaybeUninit<u8>]>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostScratchMemory
+    where
+        for<'a> <H as HostHandlers>::AudioProcessor<'a>: HostScratchMemoryImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_scratch_memory {
+                reserve: Some(reserve::<H>),
+                access: Some(access::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn reserve<H: HostHandlers>(
+        host: *const clap_host,
+        scratch_size_bytes: u32,
+        max_concurrency_hint: u32,
+    ) -> bool
+    where
+        for<'a> <H as HostHandlers>::AudioProcessor<'a>: HostScratchMemoryImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            Ok(host
+                .audio_processor()?
+                .as_mut()
+                .reserve(scratch_size_bytes, max_concurrency_hint))
+        })
+        .unwrap_or(false)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn access<H: HostHandlers>(host: *const clap_host) -> *mut c_void
+    where
+        for<'a> <H as HostHandlers>::AudioProcessor<'a>: HostScratchMemoryImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            let processor = host.audio_processor()?.as_mut();
+            let reserved_size = processor.reserved_size() as usize;
+
+            Ok(processor
+                .access()
+                .filter(|memory| memory.len() >= reserved_size)
+                .map_or(core::ptr::null_mut(), |memory| memory.as_mut_ptr().cast()))
+        })
+        .unwrap_or(core::ptr::null_mut())
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+
+    impl HostScratchMemory {
+        /// Asks the host to reserve the given amount of scratch memory for this plugin.
+        ///
+        /// This must be called while the plugin is being activated. `max_concurrency_hint` is the
+        /// maximum number of threads that may access the scratch memory concurrently, or `0` if
+        /// unspecified.
+        ///
+        /// This may be called multiple times (e.g. with decreasing sizes until the host accepts),
+        /// in which case each call replaces the previous reservations. Returns [`None`] if the
+        /// host refused to reserve the memory.
+        #[inline]
+        pub fn reserve(
+            &self,
+            host: &mut HostAudioProcessorHandle,
+            scratch_size_bytes: u32,
+            max_concurrency_hint: u32,
+        ) -> Option<ScratchMemoryReservation> {
+            let reserve = host.use_extension(&self.0).reserve?;
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let reserved =
+                unsafe { reserve(host.as_raw(), scratch_size_bytes, max_concurrency_hint) };
+
+            reserved.then_some(ScratchMemoryReservation { scratch_size_bytes })
+        }
+
+        /// Accesses the scratch memory reserved for this plugin during the current `process`
+        /// call, as a [`ScratchArena`].
+        ///
+        /// This returns [`None`] if the host didn't provide any memory.
+        ///
+        /// # Safety
+        ///
+        /// The given `reservation` must be the one returned by the last successful call to
+        /// [`reserve`](HostScratchMemory::reserve) during the current activation, as the host only
+        /// provides as much memory as was last reserved.
+        pub unsafe fn access<'a>(
+            &self,
+            host: &'a mut HostAudioProcessorHandle,
+            reservation: &ScratchMemoryReservation,
+        ) -> Option<ScratchArena<'a>> {
+            let access = host.use_extension(&self.0).access?;
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let memory = unsafe { access(host.as_raw()) }.cast::<MaybeUninit<u8>>();
+            if memory.is_null() {
+                return None;
+            }
+
+            // SAFETY: The host guarantees the memory is valid for at least the last reserved size
+            // until the end of the current process call, which the caller guarantees is the size of
+            // the given reservation. The host handle is also exclusively borrowed for as long as the
+            // arena exists.
+            let memory = unsafe {
+                core::slice::from_raw_parts_mut(memory, reservation.scratch_size_bytes as usize)
+            };
+
+            Some(ScratchArena::new(memory))
+        }
+    }
+}
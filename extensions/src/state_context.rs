@@ -0,0 +1,313 @@
+#![deny(missing_docs)]
+
+//! Allows plugins to save and restore different state depending on why it is being saved.
+//!
+//! This extension complements the [State](crate::state) extension, by giving the plugin a
+//! [`StateContextType`] indicating whether the state is saved for a preset, to duplicate the
+//! plugin instance (e.g. when the user duplicates a track), or as part of the host's project.
+//!
+//! Like the State extension, this uses the [`InputStream`](clack_common::stream::InputStream)
+//! and [`OutputStream`](clack_common::stream::OutputStream) types.
+//!
+//! On the host side, [`PluginState::save_with_context`](crate::state::PluginState::save_with_context)
+//! and [`PluginState::load_with_context`](crate::state::PluginState::load_with_context) can be
+//! used to transparently fall back to the plain State extension if the plugin doesn't implement
+//! this one.
+
+use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
+use std::ffi::CStr;
+
+/// The C-FFI definitions of the State Context extension.
+///
+/// The version of `clap-sys` currently in use only provides the draft version of this extension,
+/// so the stable version is defined here, following the CLAP headers.
+#[allow(non_camel_case_types)]
+mod sys {
+    use crate::utils::extension_id;
+    use clap_sys::plugin::clap_plugin;
+    use clap_sys::stream::{clap_istream, clap_ostream};
+    use std::ffi::CStr;
+
+    pub const CLAP_EXT_STATE_CONTEXT: &CStr = extension_id(b"clap.state-context/2\0");
+
+    pub type clap_plugin_state_context_type = u32;
+
+    pub const CLAP_STATE_CONTEXT_FOR_PRESET: clap_plugin_state_context_type = 1;
+    pub const CLAP_STATE_CONTEXT_FOR_DUPLICATE: clap_plugin_state_context_type = 2;
+    pub const CLAP_STATE_CONTEXT_FOR_PROJECT: clap_plugin_state_context_type = 3;
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_state_context {
+        pub save: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                stream: *const clap_ostream,
+                context_type: clap_plugin_state_context_type,
+            ) -> bool,
+        >,
+        pub load: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                stream: *const clap_istream,
+                context_type: clap_plugin_state_context_type,
+            ) -> bool,
+        >,
+    }
+}
+
+use sys::*;
+
+/// The Plugin-side of the State Context extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginStateContext(RawExtension<PluginExtensionSide, clap_plugin_state_context>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginStateContext {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_STATE_CONTEXT;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The reason why the plugin's state is being saved or loaded.
+#[repr(u32)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum StateContextType {
+    /// The state is saved into, or loaded from, a preset.
+    ForPreset = CLAP_STATE_CONTEXT_FOR_PRESET,
+    /// The state is used to duplicate the plugin instance, e.g. when the user duplicates a
+    /// track.
+    ForDuplicate = CLAP_STATE_CONTEXT_FOR_DUPLICATE,
+    /// The state is saved into, or loaded from, the host's project.
+    ForProject = CLAP_STATE_CONTEXT_FOR_PROJECT,
+}
+
+impl StateContextType {
+    /// Reads the context type from the raw C-FFI-compatible integer type.
+    ///
+    /// This may return [`None`] if the given integer's value doesn't match any known context type.
+    #[inline]
+    pub const fn from_raw(raw: clap_plugin_state_context_type) -> Option<Self> {
+        match raw {
+            CLAP_STATE_CONTEXT_FOR_PRESET => Some(Self::ForPreset),
+            CLAP_STATE_CONTEXT_FOR_DUPLICATE => Some(Self::ForDuplicate),
+            CLAP_STATE_CONTEXT_FOR_PROJECT => Some(Self::ForProject),
+            _ => None,
+        }
+    }
+
+    /// Returns the context type as the raw C-FFI-compatible integer type.
+    #[inline]
+    pub const fn to_raw(self) -> clap_plugin_state_context_type {
+        self as _
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use crate::state::{PluginState, StateError};
+    use clack_common::stream::{InputStream, OutputStream};
+    use clack_host::extensions::prelude::*;
+    use std::io::{Read, Write};
+
+    impl PluginStateContext {
+        /// Loads the plugin's state from the given reader, for the given context.
+        ///
+        /// # Errors
+        ///
+        /// Returns a [`StateError`] if the plugin failed or declined to load the state.
+        pub fn load<R: Read>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            reader: &mut R,
+            context_type: StateContextType,
+        ) -> Result<(), StateError> {
+            let load = plugin
+                .use_extension(&self.0)
+                .load
+                .ok_or(StateError::loading())?;
+
+            let mut stream = InputStream::from_reader(reader);
+
+            // SAFETY: This type ensures the function pointer is valid.
+            match unsafe { load(plugin.as_raw(), stream.as_raw_mut(), context_type.to_raw()) } {
+                true => Ok(()),
+                false => Err(StateError::loading()),
+            }
+        }
+
+        /// Saves the plugin's state into the given writer, for the given context.
+        ///
+        /// # Errors
+        ///
+        /// Returns a [`StateError`] if the plugin failed or declined to save the state.
+        pub fn save<W: Write>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            writer: &mut W,
+            context_type: StateContextType,
+        ) -> Result<(), StateError> {
+            let save = plugin
+                .use_extension(&self.0)
+                .save
+                .ok_or(StateError::saving())?;
+
+            let mut stream = OutputStream::from_writer(writer);
+
+            // SAFETY: This type ensures the function pointer is valid.
+            match unsafe { save(plugin.as_raw(), stream.as_raw_mut(), context_type.to_raw()) } {
+                true => Ok(()),
+                false => Err(StateError::saving()),
+            }
+        }
+    }
+
+    impl PluginState {
+        /// Loads the plugin's state from the given reader, for the given context.
+        ///
+        /// If the plugin implements the [State Context](crate::state_context) extension, the
+        /// context is passed on to it. Otherwise, this falls back to a plain
+        /// [`load`](PluginState::load).
+        ///
+        /// # Errors
+        ///
+        /// Returns a [`StateError`] if the plugin failed or declined to load the state.
+        pub fn load_with_context<R: Read>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            reader: &mut R,
+            context_type: StateContextType,
+        ) -> Result<(), StateError> {
+            match plugin.get_extension::<PluginStateContext>() {
+                Some(state_context) => state_context.load(plugin, reader, context_type),
+                None => self.load(plugin, reader),
+            }
+        }
+
+        /// Saves the plugin's state into the given writer, for the given context.
+        ///
+        /// If the plugin implements the [State Context](crate::state_context) extension, the
+        /// context is passed on to it. Otherwise, this falls back to a plain
+        /// [`save`](PluginState::save).
+        ///
+        /// # Errors
+        ///
+        /// Returns a [`StateError`] if the plugin failed or declined to save the state.
+        pub fn save_with_context<W: Write>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            writer: &mut W,
+            context_type: StateContextType,
+        ) -> Result<(), StateError> {
+            match plugin.get_extension::<PluginStateContext>() {
+                Some(state_context) => state_context.save(plugin, writer, context_type),
+                None => self.save(plugin, writer),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_common::stream::{InputStream, OutputStream};
+    use clack_plugin::extensions::prelude::*;
+    use clap_sys::stream::{clap_istream, clap_ostream};
+
+    /// Implementation of the Plugin-side of the State Context extension.
+    pub trait PluginStateContextImpl {
+        /// Saves the plugin's state into the given output stream, for the given context.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the state could not be saved.
+        fn save(
+            &mut self,
+            output: &mut OutputStream,
+            context_type: StateContextType,
+        ) -> Result<(), PluginError>;
+
+        /// Loads the plugin's state from the given input stream, for the given context.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the state could not be loaded.
+        fn load(
+            &mut self,
+            input: &mut InputStream,
+            context_type: StateContextType,
+        ) -> Result<(), PluginError>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginStateContext
+    where
+        for<'a> P::MainThread<'a>: PluginStateContextImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_state_context {
+                save: Some(save::<P>),
+                load: Some(load::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn load<P: Plugin>(
+        plugin: *const clap_plugin,
+        stream: *const clap_istream,
+        context_type: clap_plugin_state_context_type,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginStateContextImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            let context_type = StateContextType::from_raw(context_type).ok_or(
+                PluginWrapperError::InvalidParameter("Invalid state context type"),
+            )?;
+
+            if stream.is_null() {
+                return Err(PluginWrapperError::NulPtr("clap_istream"));
+            }
+
+            let input = InputStream::from_raw_mut(&mut *(stream as *mut _));
+            p.main_thread().as_mut().load(input, context_type)?;
+            Ok(())
+        })
+        .is_some()
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn save<P: Plugin>(
+        plugin: *const clap_plugin,
+        stream: *const clap_ostream,
+        context_type: clap_plugin_state_context_type,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginStateContextImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            let context_type = StateContextType::from_raw(context_type).ok_or(
+                PluginWrapperError::InvalidParameter("Invalid state context type"),
+            )?;
+
+            if stream.is_null() {
+                return Err(PluginWrapperError::NulPtr("clap_ostream"));
+            }
+
+            let output = OutputStream::from_raw_mut(&mut *(stream as *mut _));
+            p.main_thread().as_mut().save(output, context_type)?;
+            Ok(())
+        })
+        .is_some()
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,277 @@
+#![deny(missing_docs)]
+
+//! Allows the plugin to describe the channel layout of its surround audio ports.
+//!
+//! This extension is used together with the [Audio Ports](crate::audio_ports) extension: ports
+//! using the [`AudioPortType::SURROUND`](crate::audio_ports::AudioPortType::SURROUND) type can
+//! then be queried for their [`SurroundChannelMap`], which assigns a [`SurroundChannel`] speaker
+//! position to each of the port's channels.
+//!
+//! The host can also check whether the plugin supports a given set of speaker positions, using
+//! a [`SurroundChannelMask`].
+//!
+//! Plugins notify the host that their channel maps have changed using the `changed` call. This
+//! can only happen while the plugin is deactivated.
+
+use bitflags::bitflags;
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::surround::*;
+use std::ffi::CStr;
+
+/// The Plugin-side of the Surround extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginSurround(RawExtension<PluginExtensionSide, clap_plugin_surround>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginSurround {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_SURROUND;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Surround extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostSurround(RawExtension<HostExtensionSide, clap_host_surround>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostSurround {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_SURROUND;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// A speaker position in a surround channel layout.
+#[repr(u8)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum SurroundChannel {
+    /// Front Left.
+    FrontLeft = CLAP_SURROUND_FL as u8,
+    /// Front Right.
+    FrontRight = CLAP_SURROUND_FR as u8,
+    /// Front Center.
+    FrontCenter = CLAP_SURROUND_FC as u8,
+    /// Low Frequency Effects.
+    LowFrequency = CLAP_SURROUND_LFE as u8,
+    /// Back Left.
+    BackLeft = CLAP_SURROUND_BL as u8,
+    /// Back Right.
+    BackRight = CLAP_SURROUND_BR as u8,
+    /// Front Left of Center.
+    FrontLeftCenter = CLAP_SURROUND_FLC as u8,
+    /// Front Right of Center.
+    FrontRightCenter = CLAP_SURROUND_FRC as u8,
+    /// Back Center.
+    BackCenter = CLAP_SURROUND_BC as u8,
+    /// Side Left.
+    SideLeft = CLAP_SURROUND_SL as u8,
+    /// Side Right.
+    SideRight = CLAP_SURROUND_SR as u8,
+    /// Top Center.
+    TopCenter = CLAP_SURROUND_TC as u8,
+    /// Top Front Left.
+    TopFrontLeft = CLAP_SURROUND_TFL as u8,
+    /// Top Front Center.
+    TopFrontCenter = CLAP_SURROUND_TFC as u8,
+    /// Top Front Right.
+    TopFrontRight = CLAP_SURROUND_TFR as u8,
+    /// Top Back Left.
+    TopBackLeft = CLAP_SURROUND_TBL as u8,
+    /// Top Back Center.
+    TopBackCenter = CLAP_SURROUND_TBC as u8,
+    /// Top Back Right.
+    TopBackRight = CLAP_SURROUND_TBR as u8,
+}
+
+impl SurroundChannel {
+    /// Reads the speaker position from its raw C-FFI-compatible identifier.
+    ///
+    /// This may return [`None`] if the given identifier doesn't match any known speaker position.
+    #[inline]
+    pub const fn from_raw(raw: u8) -> Option<Self> {
+        use SurroundChannel::*;
+
+        Some(match raw as u32 {
+            CLAP_SURROUND_FL => FrontLeft,
+            CLAP_SURROUND_FR => FrontRight,
+            CLAP_SURROUND_FC => FrontCenter,
+            CLAP_SURROUND_LFE => LowFrequency,
+            CLAP_SURROUND_BL => BackLeft,
+            CLAP_SURROUND_BR => BackRight,
+            CLAP_SURROUND_FLC => FrontLeftCenter,
+            CLAP_SURROUND_FRC => FrontRightCenter,
+            CLAP_SURROUND_BC => BackCenter,
+            CLAP_SURROUND_SL => SideLeft,
+            CLAP_SURROUND_SR => SideRight,
+            CLAP_SURROUND_TC => TopCenter,
+            CLAP_SURROUND_TFL => TopFrontLeft,
+            CLAP_SURROUND_TFC => TopFrontCenter,
+            CLAP_SURROUND_TFR => TopFrontRight,
+            CLAP_SURROUND_TBL => TopBackLeft,
+            CLAP_SURROUND_TBC => TopBackCenter,
+            CLAP_SURROUND_TBR => TopBackRight,
+            _ => return None,
+        })
+    }
+
+    /// Returns the raw C-FFI-compatible identifier of this speaker position.
+    #[inline]
+    pub const fn to_raw(self) -> u8 {
+        self as u8
+    }
+}
+
+bitflags! {
+    /// A set of [`SurroundChannel`] speaker positions.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct SurroundChannelMask: u64 {
+        /// Front Left.
+        const FRONT_LEFT = 1 << CLAP_SURROUND_FL;
+        /// Front Right.
+        const FRONT_RIGHT = 1 << CLAP_SURROUND_FR;
+        /// Front Center.
+        const FRONT_CENTER = 1 << CLAP_SURROUND_FC;
+        /// Low Frequency Effects.
+        const LOW_FREQUENCY = 1 << CLAP_SURROUND_LFE;
+        /// Back Left.
+        const BACK_LEFT = 1 << CLAP_SURROUND_BL;
+        /// Back Right.
+        const BACK_RIGHT = 1 << CLAP_SURROUND_BR;
+        /// Front Left of Center.
+        const FRONT_LEFT_CENTER = 1 << CLAP_SURROUND_FLC;
+        /// Front Right of Center.
+        const FRONT_RIGHT_CENTER = 1 << CLAP_SURROUND_FRC;
+        /// Back Center.
+        const BACK_CENTER = 1 << CLAP_SURROUND_BC;
+        /// Side Left.
+        const SIDE_LEFT = 1 << CLAP_SURROUND_SL;
+        /// Side Right.
+        const SIDE_RIGHT = 1 << CLAP_SURROUND_SR;
+        /// Top Center.
+        const TOP_CENTER = 1 << CLAP_SURROUND_TC;
+        /// Top Front Left.
+        const TOP_FRONT_LEFT = 1 << CLAP_SURROUND_TFL;
+        /// Top Front Center.
+        const TOP_FRONT_CENTER = 1 << CLAP_SURROUND_TFC;
+        /// Top Front Right.
+        const TOP_FRONT_RIGHT = 1 << CLAP_SURROUND_TFR;
+        /// Top Back Left.
+        const TOP_BACK_LEFT = 1 << CLAP_SURROUND_TBL;
+        /// Top Back Center.
+        const TOP_BACK_CENTER = 1 << CLAP_SURROUND_TBC;
+        /// Top Back Right.
+        const TOP_BACK_RIGHT = 1 << CLAP_SURROUND_TBR;
+    }
+}
+
+impl SurroundChannelMask {
+    /// Returns `true` if the given speaker position is part of this mask.
+    #[inline]
+    pub fn has_channel(&self, channel: SurroundChannel) -> bool {
+        self.contains(channel.into())
+    }
+}
+
+impl From<SurroundChannel> for SurroundChannelMask {
+    #[inline]
+    fn from(channel: SurroundChannel) -> Self {
+        SurroundChannelMask::from_bits_truncate(1 << channel.to_raw())
+    }
+}
+
+impl FromIterator<SurroundChannel> for SurroundChannelMask {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = SurroundChannel>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(SurroundChannelMask::empty(), |mask, channel| {
+                mask | channel.into()
+            })
+    }
+}
+
+/// A surround channel map, which assigns a speaker position to each channel of an audio port.
+///
+/// The speaker positions are stored using their raw identifiers, as positions unknown to this
+/// version of Clack may be received from other plugins or hosts.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct SurroundChannelMap<'a> {
+    raw: &'a [u8],
+}
+
+impl<'a> SurroundChannelMap<'a> {
+    /// Creates a channel map from the given list of speaker positions, one per channel.
+    #[inline]
+    pub const fn new(channels: &'a [SurroundChannel]) -> Self {
+        Self {
+            // SAFETY: SurroundChannel is repr(u8), so a slice of it is also a valid slice of u8.
+            raw: unsafe { core::slice::from_raw_parts(channels.as_ptr().cast(), channels.len()) },
+        }
+    }
+
+    /// Creates a channel map from the given list of raw speaker position identifiers,
+    /// one per channel.
+    #[inline]
+    pub const fn from_raw(raw: &'a [u8]) -> Self {
+        Self { raw }
+    }
+
+    /// Returns the raw speaker position identifiers of this map, one per channel.
+    #[inline]
+    pub const fn as_raw(&self) -> &'a [u8] {
+        self.raw
+    }
+
+    /// Returns the number of channels in this map.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.raw.len()
+    }
+
+    /// Returns `true` if this map contains no channels.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.raw.is_empty()
+    }
+
+    /// Returns the speaker position of the channel at the given index.
+    ///
+    /// If the index is out of bounds, or if the position is unknown, this returns [`None`].
+    #[inline]
+    pub fn get(&self, channel_index: usize) -> Option<SurroundChannel> {
+        SurroundChannel::from_raw(*self.raw.get(channel_index)?)
+    }
+
+    /// Returns an iterator over the speaker position of each channel of this map.
+    ///
+    /// Positions that are unknown to this version of Clack are returned as [`None`].
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Option<SurroundChannel>> + 'a {
+        self.raw.iter().map(|raw| SurroundChannel::from_raw(*raw))
+    }
+
+    /// Returns the set of all the known speaker positions used in this map.
+    #[inline]
+    pub fn to_mask(&self) -> SurroundChannelMask {
+        self.iter().flatten().collect()
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host;
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin;
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,84 @@
+use super::*;
+use clack_host::extensions::prelude::*;
+
+impl PluginSurround {
+    /// Returns `true` if the plugin supports the given set of speaker positions.
+    pub fn is_channel_mask_supported(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        channel_mask: SurroundChannelMask,
+    ) -> bool {
+        match plugin.use_extension(&self.0).is_channel_mask_supported {
+            None => false,
+            // SAFETY: This type ensures the function pointer is valid.
+            Some(is_channel_mask_supported) => unsafe {
+                is_channel_mask_supported(plugin.as_raw(), channel_mask.bits())
+            },
+        }
+    }
+
+    /// Retrieves the [`SurroundChannelMap`] of the given audio port.
+    ///
+    /// The plugin gets passed a host-provided mutable buffer to write the channel map into, to
+    /// avoid any unnecessary allocations. This buffer must be at least as large as the channel
+    /// count of the given port, otherwise the returned map may be truncated.
+    ///
+    /// If the plugin failed to provide a channel map, an empty map is returned.
+    pub fn get_channel_map<'b>(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        is_input: bool,
+        port_index: u32,
+        buffer: &'b mut [u8],
+    ) -> SurroundChannelMap<'b> {
+        let Some(get_channel_map) = plugin.use_extension(&self.0).get_channel_map else {
+            return SurroundChannelMap::default();
+        };
+
+        let capacity = u32::try_from(buffer.len()).unwrap_or(u32::MAX);
+
+        // SAFETY: This type ensures the function pointer is valid. The buffer is valid for
+        // writes of up to `capacity` bytes.
+        let written = unsafe {
+            get_channel_map(
+                plugin.as_raw(),
+                is_input,
+                port_index,
+                buffer.as_mut_ptr(),
+                capacity,
+            )
+        };
+
+        let written = core::cmp::min(written, capacity) as usize;
+        SurroundChannelMap::from_raw(&buffer[..written])
+    }
+}
+
+/// Implementation of the Host-side of the Surround extension.
+pub trait HostSurroundImpl {
+    /// Informs the host that the plugin's channel maps have changed, and need to be queried again.
+    fn changed(&mut self);
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostSurround
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostSurroundImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_host_surround {
+            changed: Some(changed::<H>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn changed<H: HostHandlers>(host: *const clap_host)
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostSurroundImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        host.main_thread().as_mut().changed();
+        Ok(())
+    });
+}
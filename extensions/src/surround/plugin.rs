@@ -0,0 +1,128 @@
+use super::*;
+use crate::utils::slice_from_external_parts_mut;
+use clack_plugin::extensions::prelude::*;
+use std::mem::MaybeUninit;
+
+/// A helper struct to write a [`SurroundChannelMap`] into the host's provided buffer.
+pub struct SurroundChannelMapWriter<'a> {
+    buf: &'a mut [MaybeUninit<u8>],
+    written: usize,
+}
+
+impl<'a> SurroundChannelMapWriter<'a> {
+    #[inline]
+    fn new(buf: &'a mut [MaybeUninit<u8>]) -> Self {
+        Self { buf, written: 0 }
+    }
+
+    /// Returns the maximum number of channels the host's buffer can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Writes the given [`SurroundChannelMap`] into the host's buffer.
+    ///
+    /// If the map is larger than the buffer's [capacity](Self::capacity), it is truncated.
+    #[inline]
+    pub fn set(&mut self, channel_map: SurroundChannelMap) {
+        let channel_map = channel_map.as_raw();
+        let len = core::cmp::min(channel_map.len(), self.buf.len());
+
+        for (dst, src) in self.buf.iter_mut().zip(&channel_map[..len]) {
+            dst.write(*src);
+        }
+
+        self.written = len;
+    }
+}
+
+/// Implementation of the Plugin-side of the Surround extension.
+pub trait PluginSurroundImpl {
+    /// Returns `true` if the plugin supports the given set of speaker positions.
+    fn is_channel_mask_supported(&mut self, channel_mask: SurroundChannelMask) -> bool;
+
+    /// Retrieves the [`SurroundChannelMap`] of the given audio port.
+    ///
+    /// The plugin gets passed a host-provided mutable buffer to write the channel map into, to
+    /// avoid any unnecessary allocations.
+    fn get_channel_map(
+        &mut self,
+        is_input: bool,
+        port_index: u32,
+        writer: &mut SurroundChannelMapWriter,
+    );
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginSurround
+where
+    for<'a> P::MainThread<'a>: PluginSurroundImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_plugin_surround {
+            is_channel_mask_supported: Some(is_channel_mask_supported::<P>),
+            get_channel_map: Some(get_channel_map::<P>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn is_channel_mask_supported<P: Plugin>(
+    plugin: *const clap_plugin,
+    channel_mask: u64,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginSurroundImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        Ok(p.main_thread()
+            .as_mut()
+            .is_channel_mask_supported(SurroundChannelMask::from_bits_truncate(channel_mask)))
+    })
+    .unwrap_or(false)
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn get_channel_map<P: Plugin>(
+    plugin: *const clap_plugin,
+    is_input: bool,
+    port_index: u32,
+    channel_map: *mut u8,
+    channel_map_capacity: u32,
+) -> u32
+where
+    for<'a> P::MainThread<'a>: PluginSurroundImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        if channel_map.is_null() && channel_map_capacity > 0 {
+            return Err(PluginWrapperError::NulPtr("channel_map"));
+        }
+
+        let buf = slice_from_external_parts_mut(
+            channel_map.cast::<MaybeUninit<u8>>(),
+            channel_map_capacity as usize,
+        );
+
+        let mut writer = SurroundChannelMapWriter::new(buf);
+        p.main_thread()
+            .as_mut()
+            .get_channel_map(is_input, port_index, &mut writer);
+
+        Ok(writer.written as u32)
+    })
+    .unwrap_or(0)
+}
+
+impl HostSurround {
+    /// Informs the host that the plugin's channel maps have changed, and need to be queried again.
+    ///
+    /// The plugin *must* be deactivated to call this method.
+    #[inline]
+    pub fn changed(&self, host: &mut HostMainThreadHandle) {
+        if let Some(changed) = host.use_extension(&self.0).changed {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { changed(host.as_raw()) }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+#![deny(missing_docs)]
+
+//! Allows plugins to retrieve information about the track they are on.
+//!
+//! This includes the track's name and color, as well as its audio channel layout, which
+//! plugins can use to e.g. adapt their GUI or pick a default configuration.
+//!
+//! Hosts notify the plugin that the track's information has changed using the `changed` call,
+//! after which the plugin can query it again.
+
+use crate::audio_ports::AudioPortType;
+use bitflags::bitflags;
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clack_common::utils::cstr_from_nullable_ptr;
+use clack_common::utils::Color;
+use clap_sys::ext::draft::track_info::*;
+use std::ffi::{CStr, CString};
+
+/// The Plugin-side of the Track Info extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginTrackInfo(RawExtension<PluginExtensionSide, clap_plugin_track_info>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginTrackInfo {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_TRACK_INFO;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Track Info extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostTrackInfo(RawExtension<HostExtensionSide, clap_host_track_info>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostTrackInfo {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_TRACK_INFO;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+bitflags! {
+    /// Flags describing the kind of track a plugin is on.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    pub struct TrackInfoFlags: u64 {
+        /// The track is a return track.
+        const IS_FOR_RETURN_TRACK = CLAP_TRACK_INFO_IS_FOR_RETURN_TRACK;
+        /// The track is a bus track.
+        const IS_FOR_BUS = CLAP_TRACK_INFO_IS_FOR_BUS;
+        /// The track is the master track.
+        const IS_FOR_MASTER = CLAP_TRACK_INFO_IS_FOR_MASTER;
+    }
+}
+
+/// Information about the track a plugin is on.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct TrackInfo {
+    /// The user-facing name of the track, if any.
+    pub name: Option<String>,
+    /// The color of the track, if any.
+    pub color: Option<Color>,
+    /// The number of audio channels of the track, if known.
+    pub audio_channel_count: Option<u32>,
+    /// The audio port type of the track, if known.
+    ///
+    /// This is only meaningful if [`audio_channel_count`](TrackInfo::audio_channel_count) is
+    /// also set.
+    pub audio_port_type: Option<CString>,
+    /// Flags describing the kind of track.
+    pub flags: TrackInfoFlags,
+}
+
+impl TrackInfo {
+    /// Returns the audio port type of the track, if known.
+    #[inline]
+    pub fn audio_port_type(&self) -> Option<AudioPortType<'_>> {
+        self.audio_port_type
+            .as_deref()
+            .map(AudioPortType)
+            .filter(|_| self.audio_channel_count.is_some())
+    }
+
+    /// Reads the track information from the raw, C-FFI compatible struct.
+    ///
+    /// Any invalid UTF-8 in the track name is replaced with
+    /// [`U+FFFD REPLACEMENT CHARACTER`](char::REPLACEMENT_CHARACTER).
+    ///
+    /// # Safety
+    ///
+    /// The `audio_port_type` pointer of the given struct must either be null or point to a valid,
+    /// NULL-terminated C string.
+    pub unsafe fn from_raw(raw: &clap_track_info) -> Self {
+        let has = |flag| raw.flags & flag != 0;
+
+        let name = has(CLAP_TRACK_INFO_HAS_TRACK_NAME).then(|| {
+            String::from_utf8_lossy(crate::utils::data_from_array_buf(&raw.name)).into_owned()
+        });
+
+        let color = has(CLAP_TRACK_INFO_HAS_TRACK_COLOR).then(|| Color::from_raw(raw.color));
+
+        let (audio_channel_count, audio_port_type) = if has(CLAP_TRACK_INFO_HAS_AUDIO_CHANNEL) {
+            let port_type = cstr_from_nullable_ptr(raw.audio_port_type).map(CStr::to_owned);
+
+            (Some(raw.audio_channel_count.max(0) as u32), port_type)
+        } else {
+            (None, None)
+        };
+
+        Self {
+            name,
+            color,
+            audio_channel_count,
+            audio_port_type,
+            flags: TrackInfoFlags::from_bits_truncate(raw.flags),
+        }
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+    use std::mem::MaybeUninit;
+    use std::ptr::addr_of_mut;
+
+    impl PluginTrackInfo {
+        /// Informs the plugin that the track's information has changed.
+        #[inline]
+        pub fn changed(&self, plugin: &mut PluginMainThreadHandle) {
+            if let Some(changed) = plugin.use_extension(&self.0).changed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { changed(plugin.as_raw()) }
+            }
+        }
+    }
+
+    /// Implementation of the Host-side of the Track Info extension.
+    pub trait HostTrackInfoImpl {
+        /// Returns the information of the track the plugin is on, or [`None`] if it is unavailable.
+        ///
+        /// The returned information is borrowed from the host, as the port type string must stay
+        /// valid while the plugin reads it.
+        fn get(&mut self) -> Option<&TrackInfo>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostTrackInfo
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTrackInfoImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_track_info {
+                get: Some(get::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get<H: HostHandlers>(
+        host: *const clap_host,
+        info: *mut clap_track_info,
+    ) -> bool
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTrackInfoImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            if info.is_null() {
+                return Err(HostWrapperError::InvalidParameter("Null clap_track_info"));
+            }
+
+            let Some(track_info) = host.main_thread().as_mut().get() else {
+                return Ok(false);
+            };
+
+            let info = info.cast::<MaybeUninit<clap_track_info>>();
+            write_raw(&mut *info, track_info);
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    /// Writes the given track info into the given (possibly uninitialized) raw buffer.
+    ///
+    /// The written port type pointer borrows from the given track info.
+    fn write_raw(buf: &mut MaybeUninit<clap_track_info>, info: &TrackInfo) {
+        let buf = buf.as_mut_ptr();
+
+        let mut flags = info.flags.bits();
+
+        // SAFETY: all pointers come from `buf`, which is valid for writes and well-aligned
+        unsafe {
+            match &info.name {
+                Some(name) => {
+                    flags |= CLAP_TRACK_INFO_HAS_TRACK_NAME;
+                    crate::utils::write_to_array_buf(addr_of_mut!((*buf).name), name.as_bytes());
+                }
+                None => crate::utils::write_to_array_buf(addr_of_mut!((*buf).name), b""),
+            }
+
+            match info.color {
+                Some(color) => {
+                    flags |= CLAP_TRACK_INFO_HAS_TRACK_COLOR;
+                    addr_of_mut!((*buf).color).write(color.to_raw());
+                }
+                None => addr_of_mut!((*buf).color).write(Color::from_rgb(0, 0, 0).to_raw()),
+            }
+
+            match info.audio_channel_count {
+                Some(channel_count) => {
+                    flags |= CLAP_TRACK_INFO_HAS_AUDIO_CHANNEL;
+                    addr_of_mut!((*buf).audio_channel_count)
+                        .write(channel_count.min(i32::MAX as u32) as i32);
+                    addr_of_mut!((*buf).audio_port_type).write(
+                        info.audio_port_type
+                            .as_deref()
+                            .map_or(core::ptr::null(), CStr::as_ptr),
+                    );
+                }
+                None => {
+                    addr_of_mut!((*buf).audio_channel_count).write(0);
+                    addr_of_mut!((*buf).audio_port_type).write(core::ptr::null());
+                }
+            }
+
+            addr_of_mut!((*buf).flags).write(flags);
+        }
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+    use std::mem::MaybeUninit;
+
+    impl HostTrackInfo {
+        /// Retrieves the information of the track the plugin is on.
+        ///
+        /// This returns [`None`] if the host failed to provide it.
+        pub fn get(&self, host: &mut HostMainThreadHandle) -> Option<TrackInfo> {
+            let mut info = MaybeUninit::zeroed();
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let success =
+                unsafe { host.use_extension(&self.0).get?(host.as_raw(), info.as_mut_ptr()) };
+
+            if success {
+                // SAFETY: we just checked the buffer was successfully written to.
+                Some(unsafe { TrackInfo::from_raw(info.assume_init_ref()) })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Track Info extension.
+    pub trait PluginTrackInfoImpl {
+        /// Called when the information of the track the plugin is on has changed.
+        ///
+        /// The plugin can then query the new information using [`HostTrackInfo::get`].
+        fn changed(&mut self);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginTrackInfo
+    where
+        for<'a> P::MainThread<'a>: PluginTrackInfoImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_track_info {
+                changed: Some(changed::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn changed<P: Plugin>(plugin: *const clap_plugin)
+    where
+        for<'a> P::MainThread<'a>: PluginTrackInfoImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            plugin.main_thread().as_mut().changed();
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,335 @@
+#![deny(missing_docs)]
+
+//! Allows plugins to control the host's transport.
+//!
+//! This lets plugins such as sequencers or DAW controllers request the host to start, stop or
+//! pause playback, jump to a given position, or to change the loop region and recording state.
+//!
+//! All of these are only requests: the host is free to ignore them, and the plugin should only
+//! rely on the transport information it receives during processing.
+
+use clack_common::extensions::{Extension, HostExtensionSide, RawExtension};
+use clap_sys::ext::draft::transport_control::*;
+use std::ffi::CStr;
+
+/// The Host-side of the Transport Control extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostTransportControl(RawExtension<HostExtensionSide, clap_host_transport_control>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostTransportControl {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_TRANSPORT_CONTROL;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_common::utils::BeatTime;
+    use clack_host::extensions::prelude::*;
+    use clap_sys::fixedpoint::clap_beattime;
+
+    /// Implementation of the Host-side of the Transport Control extension.
+    ///
+    /// All of these methods are requests from the plugin, which the host is free to ignore.
+    pub trait HostTransportControlImpl {
+        /// Requests the host to start playback.
+        fn request_start(&mut self);
+        /// Requests the host to stop playback.
+        fn request_stop(&mut self);
+        /// Requests the host to resume playback, if it was paused.
+        fn request_continue(&mut self);
+        /// Requests the host to pause playback.
+        fn request_pause(&mut self);
+        /// Requests the host to toggle between playing and stopped.
+        fn request_toggle_play(&mut self);
+        /// Requests the host to jump the transport to the given position.
+        fn request_jump(&mut self, position: BeatTime);
+        /// Requests the host to change the loop region.
+        fn request_loop_region(&mut self, start: BeatTime, duration: BeatTime);
+        /// Requests the host to toggle looping.
+        fn request_toggle_loop(&mut self);
+        /// Requests the host to enable or disable looping.
+        fn request_enable_loop(&mut self, is_enabled: bool);
+        /// Requests the host to start or stop recording.
+        fn request_record(&mut self, is_recording: bool);
+        /// Requests the host to toggle recording.
+        fn request_toggle_record(&mut self);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostTransportControl
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_transport_control {
+                request_start: Some(request_start::<H>),
+                request_stop: Some(request_stop::<H>),
+                request_continue: Some(request_continue::<H>),
+                request_pause: Some(request_pause::<H>),
+                request_toggle_play: Some(request_toggle_play::<H>),
+                request_jump: Some(request_jump::<H>),
+                request_loop_region: Some(request_loop_region::<H>),
+                request_toggle_loop: Some(request_toggle_loop::<H>),
+                request_enable_loop: Some(request_enable_loop::<H>),
+                request_record: Some(request_record::<H>),
+                request_toggle_record: Some(request_toggle_record::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_start<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().request_start();
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_stop<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().request_stop();
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_continue<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().request_continue();
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_pause<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().request_pause();
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_toggle_play<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().request_toggle_play();
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_jump<H: HostHandlers>(
+        host: *const clap_host,
+        position: clap_beattime,
+    ) where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread()
+                .as_mut()
+                .request_jump(BeatTime::from_bits(position));
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_loop_region<H: HostHandlers>(
+        host: *const clap_host,
+        start: clap_beattime,
+        duration: clap_beattime,
+    ) where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread()
+                .as_mut()
+                .request_loop_region(BeatTime::from_bits(start), BeatTime::from_bits(duration));
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_toggle_loop<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().request_toggle_loop();
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_enable_loop<H: HostHandlers>(
+        host: *const clap_host,
+        is_enabled: bool,
+    ) where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().request_enable_loop(is_enabled);
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_record<H: HostHandlers>(host: *const clap_host, is_recording: bool)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().request_record(is_recording);
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_toggle_record<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTransportControlImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().request_toggle_record();
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_common::utils::BeatTime;
+    use clack_plugin::extensions::prelude::*;
+
+    impl HostTransportControl {
+        /// Requests the host to start playback.
+        #[inline]
+        pub fn request_start(&self, host: &mut HostMainThreadHandle) {
+            if let Some(request_start) = host.use_extension(&self.0).request_start {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_start(host.as_raw()) }
+            }
+        }
+
+        /// Requests the host to stop playback.
+        #[inline]
+        pub fn request_stop(&self, host: &mut HostMainThreadHandle) {
+            if let Some(request_stop) = host.use_extension(&self.0).request_stop {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_stop(host.as_raw()) }
+            }
+        }
+
+        /// Requests the host to resume playback, if it was paused.
+        #[inline]
+        pub fn request_continue(&self, host: &mut HostMainThreadHandle) {
+            if let Some(request_continue) = host.use_extension(&self.0).request_continue {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_continue(host.as_raw()) }
+            }
+        }
+
+        /// Requests the host to pause playback.
+        #[inline]
+        pub fn request_pause(&self, host: &mut HostMainThreadHandle) {
+            if let Some(request_pause) = host.use_extension(&self.0).request_pause {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_pause(host.as_raw()) }
+            }
+        }
+
+        /// Requests the host to toggle between playing and stopped.
+        #[inline]
+        pub fn request_toggle_play(&self, host: &mut HostMainThreadHandle) {
+            if let Some(request_toggle_play) = host.use_extension(&self.0).request_toggle_play {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_toggle_play(host.as_raw()) }
+            }
+        }
+
+        /// Requests the host to jump the transport to the given position.
+        #[inline]
+        pub fn request_jump(&self, host: &mut HostMainThreadHandle, position: BeatTime) {
+            if let Some(request_jump) = host.use_extension(&self.0).request_jump {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_jump(host.as_raw(), position.to_bits()) }
+            }
+        }
+
+        /// Requests the host to change the loop region.
+        #[inline]
+        pub fn request_loop_region(
+            &self,
+            host: &mut HostMainThreadHandle,
+            start: BeatTime,
+            duration: BeatTime,
+        ) {
+            if let Some(request_loop_region) = host.use_extension(&self.0).request_loop_region {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_loop_region(host.as_raw(), start.to_bits(), duration.to_bits()) }
+            }
+        }
+
+        /// Requests the host to toggle looping.
+        #[inline]
+        pub fn request_toggle_loop(&self, host: &mut HostMainThreadHandle) {
+            if let Some(request_toggle_loop) = host.use_extension(&self.0).request_toggle_loop {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_toggle_loop(host.as_raw()) }
+            }
+        }
+
+        /// Requests the host to enable or disable looping.
+        #[inline]
+        pub fn request_enable_loop(&self, host: &mut HostMainThreadHandle, is_enabled: bool) {
+            if let Some(request_enable_loop) = host.use_extension(&self.0).request_enable_loop {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_enable_loop(host.as_raw(), is_enabled) }
+            }
+        }
+
+        /// Requests the host to start or stop recording.
+        #[inline]
+        pub fn request_record(&self, host: &mut HostMainThreadHandle, is_recording: bool) {
+            if let Some(request_record) = host.use_extension(&self.0).request_record {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_record(host.as_raw(), is_recording) }
+            }
+        }
+
+        /// Requests the host to toggle recording.
+        #[inline]
+        pub fn request_toggle_record(&self, host: &mut HostMainThreadHandle) {
+            if let Some(request_toggle_record) = host.use_extension(&self.0).request_toggle_record {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { request_toggle_record(host.as_raw()) }
+            }
+        }
+    }
+}
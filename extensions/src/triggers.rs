@@ -0,0 +1,387 @@
+#![deny(missing_docs)]
+
+//! Allows plugins to expose triggers to the host.
+//!
+//! Triggers are similar to parameters, except they do not have a value: they can only be fired,
+//! e.g. to play a drum pad or to reset a sequencer. Like parameters, they can be automated by
+//! the host, possibly per note ID, key, channel or port.
+//!
+//! Triggers are fired using [`TriggerEvent`]s, which belong to the [`TriggersEventSpace`] rather
+//! than the core event space. Plugins must therefore query that space's ID using the
+//! [Event Registry](crate::event_registry) extension to recognize them.
+
+use bitflags::bitflags;
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clack_common::utils::{ClapId, Cookie};
+use clap_sys::ext::draft::triggers::*;
+use std::ffi::CStr;
+
+pub use clack_common::events::event_types::TriggerEvent;
+pub use clack_common::events::spaces::TriggersEventSpace;
+
+/// The Plugin-side of the Triggers extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginTriggers(RawExtension<PluginExtensionSide, clap_plugin_triggers>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginTriggers {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_TRIGGERS;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Triggers extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostTriggers(RawExtension<HostExtensionSide, clap_host_triggers>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostTriggers {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_TRIGGERS;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+bitflags! {
+    /// Flags describing how a trigger can be automated.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct TriggerInfoFlags: u32 {
+        /// The trigger can be automated per note ID.
+        const IS_AUTOMATABLE_PER_NOTE_ID = CLAP_TRIGGER_IS_AUTOMATABLE_PER_NOTE_ID;
+        /// The trigger can be automated per key.
+        const IS_AUTOMATABLE_PER_KEY = CLAP_TRIGGER_IS_AUTOMATABLE_PER_KEY;
+        /// The trigger can be automated per channel.
+        const IS_AUTOMATABLE_PER_CHANNEL = CLAP_TRIGGER_IS_AUTOMATABLE_PER_CHANNEL;
+        /// The trigger can be automated per port.
+        const IS_AUTOMATABLE_PER_PORT = CLAP_TRIGGER_IS_AUTOMATABLE_PER_PORT;
+    }
+}
+
+bitflags! {
+    /// Flags describing what the host should rescan about the plugin's triggers.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct TriggerRescanFlags: u32 {
+        /// The triggers' info (names, modules, ...) have changed.
+        const INFO = CLAP_TRIGGER_RESCAN_INFO;
+        /// The list of triggers itself has changed.
+        ///
+        /// This can only be requested while the plugin is deactivated.
+        const ALL = CLAP_TRIGGER_RESCAN_ALL;
+    }
+}
+
+impl TriggerRescanFlags {
+    /// Returns `true` if any of the given flags that are set imply that a plugin instance's restart
+    /// is needed before triggers can be rescanned.
+    #[inline]
+    pub fn requires_restart(&self) -> bool {
+        self.contains(Self::ALL)
+    }
+}
+
+bitflags! {
+    /// Flags describing what the host should clear about a given trigger.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct TriggerClearFlags: u32 {
+        /// Clears all references to the trigger.
+        const ALL = CLAP_TRIGGER_CLEAR_ALL;
+        /// Clears all automations of the trigger.
+        const AUTOMATIONS = CLAP_TRIGGER_CLEAR_AUTOMATIONS;
+    }
+}
+
+/// Information about a plugin's trigger.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TriggerInfo<'a> {
+    /// The stable identifier of the trigger.
+    pub id: ClapId,
+    /// Flags describing how the trigger can be automated.
+    pub flags: TriggerInfoFlags,
+    /// An opaque pointer the host will attach to trigger events targeting this trigger.
+    pub cookie: Cookie,
+    /// The user-facing name of the trigger.
+    pub name: &'a [u8],
+    /// The module path of the trigger, e.g. `"drums/kick"`, used to group triggers.
+    pub module: &'a [u8],
+}
+
+impl<'a> TriggerInfo<'a> {
+    /// Reads the trigger information from the raw, C-FFI compatible struct.
+    ///
+    /// This returns [`None`] if the trigger's ID is invalid.
+    pub fn from_raw(raw: &'a clap_trigger_info) -> Option<Self> {
+        Some(Self {
+            id: ClapId::from_raw(raw.id)?,
+            flags: TriggerInfoFlags::from_bits_truncate(raw.flags),
+            cookie: Cookie::from_raw(raw.cookie),
+            name: crate::utils::data_from_array_buf(&raw.name),
+            module: crate::utils::data_from_array_buf(&raw.module),
+        })
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+    use clap_sys::id::clap_id;
+    use std::mem::MaybeUninit;
+
+    /// A buffer the plugin writes a [`TriggerInfo`] into.
+    #[derive(Clone)]
+    pub struct TriggerInfoBuffer {
+        inner: MaybeUninit<clap_trigger_info>,
+    }
+
+    impl Default for TriggerInfoBuffer {
+        #[inline]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl TriggerInfoBuffer {
+        /// Creates a new, empty trigger info buffer.
+        #[inline]
+        pub fn new() -> Self {
+            Self {
+                inner: MaybeUninit::zeroed(),
+            }
+        }
+    }
+
+    impl PluginTriggers {
+        /// Returns the number of triggers the plugin exposes.
+        pub fn count(&self, plugin: &mut PluginMainThreadHandle) -> u32 {
+            match plugin.use_extension(&self.0).count {
+                None => 0,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(count) => unsafe { count(plugin.as_raw()) },
+            }
+        }
+
+        /// Retrieves the information about the trigger at the given index, using the given
+        /// buffer.
+        ///
+        /// This returns [`None`] if the plugin failed to provide valid information.
+        pub fn get_info<'b>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            index: u32,
+            buffer: &'b mut TriggerInfoBuffer,
+        ) -> Option<TriggerInfo<'b>> {
+            // SAFETY: This type ensures the function pointer is valid.
+            let success = unsafe {
+                plugin.use_extension(&self.0).get_info?(
+                    plugin.as_raw(),
+                    index,
+                    buffer.inner.as_mut_ptr(),
+                )
+            };
+
+            if success {
+                // SAFETY: we just checked the buffer was successfully written to.
+                TriggerInfo::from_raw(unsafe { buffer.inner.assume_init_ref() })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Implementation of the Host-side of the Triggers extension.
+    pub trait HostTriggersImpl {
+        /// Rescans the plugin's triggers, according to the given flags.
+        fn rescan(&mut self, flags: TriggerRescanFlags);
+        /// Clears the references to the given trigger, according to the given flags.
+        fn clear(&mut self, trigger_id: ClapId, flags: TriggerClearFlags);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostTriggers
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTriggersImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_triggers {
+                rescan: Some(rescan::<H>),
+                clear: Some(clear::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn rescan<H: HostHandlers>(
+        host: *const clap_host,
+        flags: clap_trigger_rescan_flags,
+    ) where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTriggersImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread()
+                .as_mut()
+                .rescan(TriggerRescanFlags::from_bits_truncate(flags));
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn clear<H: HostHandlers>(
+        host: *const clap_host,
+        trigger_id: clap_id,
+        flags: clap_trigger_clear_flags,
+    ) where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTriggersImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            let trigger_id = ClapId::from_raw(trigger_id)
+                .ok_or(HostWrapperError::InvalidParameter("Invalid trigger_id"))?;
+
+            host.main_thread()
+                .as_mut()
+                .clear(trigger_id, TriggerClearFlags::from_bits_truncate(flags));
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use crate::utils::write_to_array_buf;
+    use clack_plugin::extensions::prelude::*;
+    use std::mem::MaybeUninit;
+
+    /// A writer the plugin uses to provide a [`TriggerInfo`] to the host.
+    pub struct TriggerInfoWriter<'a> {
+        buf: &'a mut MaybeUninit<clap_trigger_info>,
+        is_set: bool,
+    }
+
+    impl TriggerInfoWriter<'_> {
+        /// # Safety
+        ///
+        /// The user must ensure the provided pointer is aligned and points to a valid allocation.
+        /// However, it doesn't have to be initialized.
+        unsafe fn new(raw: *mut clap_trigger_info) -> Self {
+            Self {
+                // SAFETY: MaybeUninit<T> and T have same memory representation
+                buf: unsafe { &mut *raw.cast() },
+                is_set: false,
+            }
+        }
+
+        /// Writes the given trigger information.
+        #[inline]
+        pub fn set(&mut self, info: &TriggerInfo) {
+            let buf = self.buf.as_mut_ptr();
+
+            // SAFETY: all pointers come from `buf`, which is valid for writes and well-aligned
+            unsafe {
+                core::ptr::addr_of_mut!((*buf).id).write(info.id.get());
+                core::ptr::addr_of_mut!((*buf).flags).write(info.flags.bits());
+                core::ptr::addr_of_mut!((*buf).cookie).write(info.cookie.as_raw());
+
+                write_to_array_buf(core::ptr::addr_of_mut!((*buf).name), info.name);
+                write_to_array_buf(core::ptr::addr_of_mut!((*buf).module), info.module);
+            }
+
+            self.is_set = true;
+        }
+    }
+
+    impl HostTriggers {
+        /// Requests the host to rescan the plugin's triggers, according to the given flags.
+        #[inline]
+        pub fn rescan(&self, host: &mut HostMainThreadHandle, flags: TriggerRescanFlags) {
+            if let Some(rescan) = host.use_extension(&self.0).rescan {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { rescan(host.as_raw(), flags.bits()) }
+            }
+        }
+
+        /// Requests the host to clear its references to the given trigger, according to the
+        /// given flags.
+        #[inline]
+        pub fn clear(
+            &self,
+            host: &mut HostMainThreadHandle,
+            trigger_id: ClapId,
+            flags: TriggerClearFlags,
+        ) {
+            if let Some(clear) = host.use_extension(&self.0).clear {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { clear(host.as_raw(), trigger_id.get(), flags.bits()) }
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Triggers extension.
+    pub trait PluginTriggersImpl {
+        /// Returns the number of triggers the plugin exposes.
+        fn count(&mut self) -> u32;
+        /// Writes the information of the trigger at the given index into the given writer.
+        ///
+        /// If nothing is written, the host will consider the call to have failed.
+        fn get_info(&mut self, trigger_index: u32, info: &mut TriggerInfoWriter);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginTriggers
+    where
+        for<'a> P::MainThread<'a>: PluginTriggersImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_triggers {
+                count: Some(count::<P>),
+                get_info: Some(get_info::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn count<P: Plugin>(plugin: *const clap_plugin) -> u32
+    where
+        for<'a> P::MainThread<'a>: PluginTriggersImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| Ok(p.main_thread().as_mut().count())).unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_info<P: Plugin>(
+        plugin: *const clap_plugin,
+        trigger_index: u32,
+        trigger_info: *mut clap_trigger_info,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginTriggersImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if trigger_info.is_null() {
+                return Err(PluginWrapperError::NulPtr("clap_trigger_info"));
+            }
+
+            let mut info = TriggerInfoWriter::new(trigger_info);
+            p.main_thread().as_mut().get_info(trigger_index, &mut info);
+            Ok(info.is_set)
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,420 @@
+#![deny(missing_docs)]
+
+//! Allows hosts to provide plugins with microtonal tunings.
+//!
+//! The host exposes a list of tunings (see [`TuningInfo`]), and selects which one applies to a
+//! given port and channel by sending [`RelativeTuningEvent`]s. Those events belong to the
+//! [`TuningEventSpace`] rather than the core event space, so plugins must query that space's ID
+//! using the [Event Registry](crate::event_registry) extension to recognize them.
+//!
+//! The actual tuning is then retrieved from the audio thread using [`HostTuning::get_relative`],
+//! which returns the tuning offset of a given key, in semitones, relative to equal temperament.
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clack_common::utils::ClapId;
+use clap_sys::ext::draft::tuning::*;
+use std::ffi::CStr;
+
+pub use clack_common::events::event_types::RelativeTuningEvent;
+pub use clack_common::events::spaces::TuningEventSpace;
+
+/// The Plugin-side of the Tuning extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginTuning(RawExtension<PluginExtensionSide, clap_plugin_tuning_t>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginTuning {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_TUNING;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Tuning extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostTuning(RawExtension<HostExtensionSide, clap_host_tuning>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostTuning {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_TUNING;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// Information about one of the host's tunings.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TuningInfo<'a> {
+    /// The identifier of the tuning.
+    pub tuning_id: ClapId,
+    /// The user-facing name of the tuning.
+    pub name: &'a [u8],
+    /// Whether the tuning may change over time, in which case the plugin should keep querying
+    /// it while notes are playing.
+    pub is_dynamic: bool,
+}
+
+impl<'a> TuningInfo<'a> {
+    /// Reads the tuning information from the raw, C-FFI compatible struct.
+    ///
+    /// This returns [`None`] if the tuning's ID is invalid.
+    pub fn from_raw(raw: &'a clap_tuning_info) -> Option<Self> {
+        Some(Self {
+            tuning_id: ClapId::from_raw(raw.tuning_id)?,
+            name: crate::utils::data_from_array_buf(&raw.name),
+            is_dynamic: raw.is_dynamic,
+        })
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use crate::utils::write_to_array_buf;
+    use clack_host::extensions::prelude::*;
+    use clap_sys::id::clap_id;
+    use std::mem::MaybeUninit;
+
+    impl PluginTuning {
+        /// Informs the plugin that the list of tunings has changed.
+        #[inline]
+        pub fn changed(&self, plugin: &mut PluginMainThreadHandle) {
+            if let Some(changed) = plugin.use_extension(&self.0).changed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { changed(plugin.as_raw()) }
+            }
+        }
+    }
+
+    /// A writer the host uses to provide a [`TuningInfo`] to the plugin.
+    pub struct TuningInfoWriter<'a> {
+        buf: &'a mut MaybeUninit<clap_tuning_info>,
+        is_set: bool,
+    }
+
+    impl TuningInfoWriter<'_> {
+        /// # Safety
+        ///
+        /// The user must ensure the provided pointer is aligned and points to a valid allocation.
+        /// However, it doesn't have to be initialized.
+        unsafe fn new(raw: *mut clap_tuning_info) -> Self {
+            Self {
+                // SAFETY: MaybeUninit<T> and T have same memory representation
+                buf: unsafe { &mut *raw.cast() },
+                is_set: false,
+            }
+        }
+
+        /// Writes the given tuning information.
+        #[inline]
+        pub fn set(&mut self, info: &TuningInfo) {
+            let buf = self.buf.as_mut_ptr();
+
+            // SAFETY: all pointers come from `buf`, which is valid for writes and well-aligned
+            unsafe {
+                core::ptr::addr_of_mut!((*buf).tuning_id).write(info.tuning_id.get());
+                core::ptr::addr_of_mut!((*buf).is_dynamic).write(info.is_dynamic);
+
+                write_to_array_buf(core::ptr::addr_of_mut!((*buf).name), info.name);
+            }
+
+            self.is_set = true;
+        }
+    }
+
+    /// Implementation of the main-thread part of the Host-side of the Tuning extension.
+    pub trait HostTuningImpl {
+        /// Returns the number of tunings the host provides.
+        fn get_tuning_count(&mut self) -> u32;
+        /// Writes the information of the tuning at the given index into the given writer.
+        ///
+        /// If nothing is written, the plugin will consider the call to have failed.
+        fn get_info(&mut self, tuning_index: u32, info: &mut TuningInfoWriter);
+    }
+
+    /// Implementation of the audio-thread part of the Host-side of the Tuning extension.
+    pub trait HostTuningAudioProcessorImpl {
+        /// Returns the tuning offset of the given key and channel at the given sample offset, in
+        /// semitones, relative to equal temperament.
+        ///
+        /// If the tuning is [`None`] or unknown, this should return a sensible value, such as `0.0`.
+        fn get_relative(
+            &mut self,
+            tuning_id: Option<ClapId>,
+            channel: u16,
+            key: u16,
+            sample_offset: u32,
+        ) -> f64;
+
+        /// Returns whether the given key and channel should be played at all using the given
+        /// tuning.
+        fn should_play(&mut self, tuning_id: Option<ClapId>, channel: u16, key: u16) -> bool;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostTuning
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTuningImpl,
+        for<'a> <H as HostHandlers>::AudioProcessor<'a>: HostTuningAudioProcessorImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_tuning {
+                get_relative: Some(get_relative::<H>),
+                should_play: Some(should_play::<H>),
+                get_tuning_count: Some(get_tuning_count::<H>),
+                get_info: Some(get_info::<H>),
+            });
+    }
+
+    /// Converts the given raw channel or key to a `u16`, if it is in range.
+    fn to_u16(value: i32, error: &'static str) -> Result<u16, HostWrapperError> {
+        u16::try_from(value).map_err(|_| HostWrapperError::InvalidParameter(error))
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_relative<H: HostHandlers>(
+        host: *const clap_host,
+        tuning_id: clap_id,
+        channel: i32,
+        key: i32,
+        sample_offset: u32,
+    ) -> f64
+    where
+        for<'a> <H as HostHandlers>::AudioProcessor<'a>: HostTuningAudioProcessorImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            let channel = to_u16(channel, "Invalid channel")?;
+            let key = to_u16(key, "Invalid key")?;
+
+            Ok(host.audio_processor()?.as_mut().get_relative(
+                ClapId::from_raw(tuning_id),
+                channel,
+                key,
+                sample_offset,
+            ))
+        })
+        .unwrap_or(0.0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn should_play<H: HostHandlers>(
+        host: *const clap_host,
+        tuning_id: clap_id,
+        channel: i32,
+        key: i32,
+    ) -> bool
+    where
+        for<'a> <H as HostHandlers>::AudioProcessor<'a>: HostTuningAudioProcessorImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            let channel = to_u16(channel, "Invalid channel")?;
+            let key = to_u16(key, "Invalid key")?;
+
+            Ok(host.audio_processor()?.as_mut().should_play(
+                ClapId::from_raw(tuning_id),
+                channel,
+                key,
+            ))
+        })
+        .unwrap_or(true)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_tuning_count<H: HostHandlers>(host: *const clap_host) -> u32
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTuningImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            Ok(host.main_thread().as_mut().get_tuning_count())
+        })
+        .unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_info<H: HostHandlers>(
+        host: *const clap_host,
+        tuning_index: u32,
+        info: *mut clap_tuning_info,
+    ) -> bool
+    where
+        for<'a> <H as HostHandlers>::MainThread<'a>: HostTuningImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            if info.is_null() {
+                return Err(HostWrapperError::InvalidParameter("Null clap_tuning_info"));
+            }
+
+            let mut info = TuningInfoWriter::new(info);
+            host.main_thread()
+                .as_mut()
+                .get_info(tuning_index, &mut info);
+            Ok(info.is_set)
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+    use std::mem::MaybeUninit;
+
+    /// A buffer the host writes a [`TuningInfo`] into.
+    #[derive(Clone)]
+    pub struct TuningInfoBuffer {
+        inner: MaybeUninit<clap_tuning_info>,
+    }
+
+    impl Default for TuningInfoBuffer {
+        #[inline]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl TuningInfoBuffer {
+        /// Creates a new, empty tuning info buffer.
+        #[inline]
+        pub fn new() -> Self {
+            Self {
+                inner: MaybeUninit::zeroed(),
+            }
+        }
+    }
+
+    impl HostTuning {
+        /// Returns the tuning offset of the given key and channel at the given sample offset, in
+        /// semitones, relative to equal temperament.
+        ///
+        /// If the given tuning is [`None`], the host will return a sensible default value.
+        #[inline]
+        pub fn get_relative(
+            &self,
+            host: &mut HostAudioProcessorHandle,
+            tuning_id: Option<ClapId>,
+            channel: u16,
+            key: u16,
+            sample_offset: u32,
+        ) -> f64 {
+            match host.use_extension(&self.0).get_relative {
+                None => 0.0,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(get_relative) => unsafe {
+                    get_relative(
+                        host.as_raw(),
+                        ClapId::optional_to_raw(tuning_id),
+                        channel as i32,
+                        key as i32,
+                        sample_offset,
+                    )
+                },
+            }
+        }
+
+        /// Returns whether the given key and channel should be played at all using the given
+        /// tuning.
+        #[inline]
+        pub fn should_play(
+            &self,
+            host: &mut HostAudioProcessorHandle,
+            tuning_id: Option<ClapId>,
+            channel: u16,
+            key: u16,
+        ) -> bool {
+            match host.use_extension(&self.0).should_play {
+                None => true,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(should_play) => unsafe {
+                    should_play(
+                        host.as_raw(),
+                        ClapId::optional_to_raw(tuning_id),
+                        channel as i32,
+                        key as i32,
+                    )
+                },
+            }
+        }
+
+        /// Returns the number of tunings the host provides.
+        #[inline]
+        pub fn get_tuning_count(&self, host: &mut HostMainThreadHandle) -> u32 {
+            match host.use_extension(&self.0).get_tuning_count {
+                None => 0,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(get_tuning_count) => unsafe { get_tuning_count(host.as_raw()) },
+            }
+        }
+
+        /// Retrieves the information about the tuning at the given index, using the given
+        /// buffer.
+        ///
+        /// This returns [`None`] if the host failed to provide valid information.
+        pub fn get_info<'b>(
+            &self,
+            host: &mut HostMainThreadHandle,
+            tuning_index: u32,
+            buffer: &'b mut TuningInfoBuffer,
+        ) -> Option<TuningInfo<'b>> {
+            // SAFETY: This type ensures the function pointer is valid.
+            let success = unsafe {
+                host.use_extension(&self.0).get_info?(
+                    host.as_raw(),
+                    tuning_index,
+                    buffer.inner.as_mut_ptr(),
+                )
+            };
+
+            if success {
+                // SAFETY: we just checked the buffer was successfully written to.
+                TuningInfo::from_raw(unsafe { buffer.inner.assume_init_ref() })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Tuning extension.
+    pub trait PluginTuningImpl {
+        /// Called when the host's list of tunings has changed.
+        fn changed(&mut self);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginTuning
+    where
+        for<'a> P::MainThread<'a>: PluginTuningImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_tuning_t {
+                changed: Some(changed::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn changed<P: Plugin>(plugin: *const clap_plugin)
+    where
+        for<'a> P::MainThread<'a>: PluginTuningImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            plugin.main_thread().as_mut().changed();
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,282 @@
+#![deny(missing_docs)]
+
+//! Allows plugins to take part in the host's undo history.
+//!
+//! This is made of three extensions:
+//!
+//! * [`HostUndo`], which plugins use to notify the host of the changes they made, and to request
+//!   the host to undo or redo them;
+//! * [`PluginUndoDelta`], which hosts use to apply undo or redo operations through opaque
+//!   deltas provided by the plugin;
+//! * [`PluginUndoContext`], which hosts use to keep the plugin informed about the current state
+//!   of the undo history (e.g. to display it in the plugin's GUI).
+//!
+//! Deltas are opaque byte buffers, whose format is defined by the plugin. The host stores them
+//! in its undo history as owned [`UndoDelta`]s, and sends them back to the plugin to undo or
+//! redo a change.
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clack_common::utils::ClapId;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+
+/// The C-FFI definitions of the undo extensions.
+///
+/// These are not part of the version of `clap-sys` currently in use, and are therefore defined
+/// here, following the CLAP headers.
+#[allow(non_camel_case_types)]
+mod sys {
+    use crate::utils::extension_id;
+    use clap_sys::host::clap_host;
+    use clap_sys::id::clap_id;
+    use clap_sys::plugin::clap_plugin;
+    use std::ffi::{c_char, c_void, CStr};
+
+    pub const CLAP_EXT_UNDO: &CStr = extension_id(b"clap.undo/4\0");
+    pub const CLAP_EXT_UNDO_CONTEXT: &CStr = extension_id(b"clap.undo_context/4\0");
+    pub const CLAP_EXT_UNDO_DELTA: &CStr = extension_id(b"clap.undo_delta/4\0");
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_undo_delta_properties {
+        pub has_delta: bool,
+        pub are_deltas_persistent: bool,
+        pub format_version: clap_id,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_undo_delta {
+        pub get_delta_properties: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                properties: *mut clap_undo_delta_properties,
+            ),
+        >,
+        pub can_use_delta_format_version: Option<
+            unsafe extern "C" fn(plugin: *const clap_plugin, format_version: clap_id) -> bool,
+        >,
+        pub undo: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                format_version: clap_id,
+                delta: *const c_void,
+                delta_size: usize,
+            ) -> bool,
+        >,
+        pub redo: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                format_version: clap_id,
+                delta: *const c_void,
+                delta_size: usize,
+            ) -> bool,
+        >,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_undo_context {
+        pub set_can_undo: Option<unsafe extern "C" fn(plugin: *const clap_plugin, can_undo: bool)>,
+        pub set_can_redo: Option<unsafe extern "C" fn(plugin: *const clap_plugin, can_redo: bool)>,
+        pub set_undo_name:
+            Option<unsafe extern "C" fn(plugin: *const clap_plugin, name: *const c_char)>,
+        pub set_redo_name:
+            Option<unsafe extern "C" fn(plugin: *const clap_plugin, name: *const c_char)>,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_host_undo {
+        pub begin_change: Option<unsafe extern "C" fn(host: *const clap_host)>,
+        pub cancel_change: Option<unsafe extern "C" fn(host: *const clap_host)>,
+        pub change_made: Option<
+            unsafe extern "C" fn(
+                host: *const clap_host,
+                name: *const c_char,
+                delta: *const c_void,
+                delta_size: usize,
+                delta_can_undo: bool,
+            ),
+        >,
+        pub request_undo: Option<unsafe extern "C" fn(host: *const clap_host)>,
+        pub request_redo: Option<unsafe extern "C" fn(host: *const clap_host)>,
+        pub set_wants_context_updates:
+            Option<unsafe extern "C" fn(host: *const clap_host, is_subscribed: bool)>,
+    }
+}
+
+use sys::*;
+
+/// The Plugin-side of the Undo Delta extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginUndoDelta(RawExtension<PluginExtensionSide, clap_plugin_undo_delta>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginUndoDelta {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_UNDO_DELTA;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Plugin-side of the Undo Context extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginUndoContext(RawExtension<PluginExtensionSide, clap_plugin_undo_context>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginUndoContext {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_UNDO_CONTEXT;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Undo extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostUndo(RawExtension<HostExtensionSide, clap_host_undo>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostUndo {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_UNDO;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// Describes how a plugin provides undo deltas.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct UndoDeltaProperties {
+    /// Whether the plugin provides a delta with each change it reports.
+    pub has_delta: bool,
+    /// Whether the deltas can be saved with the project, and used after the plugin is reloaded.
+    pub are_deltas_persistent: bool,
+    /// The version of the delta format the plugin currently uses, if any.
+    pub format_version: Option<ClapId>,
+}
+
+impl UndoDeltaProperties {
+    #[cfg(feature = "clack-host")]
+    #[inline]
+    fn from_raw(raw: &clap_undo_delta_properties) -> Self {
+        Self {
+            has_delta: raw.has_delta,
+            are_deltas_persistent: raw.are_deltas_persistent,
+            format_version: ClapId::from_raw(raw.format_version),
+        }
+    }
+
+    #[cfg(feature = "clack-plugin")]
+    #[inline]
+    fn to_raw(self) -> clap_undo_delta_properties {
+        clap_undo_delta_properties {
+            has_delta: self.has_delta,
+            are_deltas_persistent: self.are_deltas_persistent,
+            format_version: ClapId::optional_to_raw(self.format_version),
+        }
+    }
+}
+
+/// An opaque undo delta, as provided by a plugin.
+///
+/// This owns the delta's bytes, which allows hosts to store it in their undo history.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct UndoDelta(Box<[u8]>);
+
+impl UndoDelta {
+    /// Creates a new delta from a copy of the given bytes.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self(bytes.into())
+    }
+
+    /// Returns the bytes of this delta.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the bytes of this delta, consuming it.
+    #[inline]
+    pub fn into_bytes(self) -> Box<[u8]> {
+        self.0
+    }
+}
+
+impl From<Vec<u8>> for UndoDelta {
+    #[inline]
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes.into_boxed_slice())
+    }
+}
+
+impl From<Box<[u8]>> for UndoDelta {
+    #[inline]
+    fn from(bytes: Box<[u8]>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for UndoDelta {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Errors that can occur when asking a plugin to apply an undo delta.
+#[derive(Copy, Clone, Debug)]
+pub struct UndoError {
+    redoing: bool,
+}
+
+impl UndoError {
+    /// Returns an [`UndoError`] that was triggered while undoing a change.
+    ///
+    /// This information is used in the error's message.
+    pub const fn undoing() -> Self {
+        Self { redoing: false }
+    }
+
+    /// Returns an [`UndoError`] that was triggered while redoing a change.
+    ///
+    /// This information is used in the error's message.
+    pub const fn redoing() -> Self {
+        Self { redoing: true }
+    }
+}
+
+impl Display for UndoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.redoing {
+            f.write_str("Failed to redo plugin change")
+        } else {
+            f.write_str("Failed to undo plugin change")
+        }
+    }
+}
+
+impl Error for UndoError {}
+
+#[cfg(feature = "clack-plugin")]
+mod plugin;
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
+
+#[cfg(feature = "clack-host")]
+mod host;
+#[cfg(feature = "clack-host")]
+pub use host::*;
@@ -0,0 +1,275 @@
+use super::*;
+use crate::utils::slice_from_external_parts;
+use clack_host::extensions::prelude::*;
+use std::ffi::{c_char, c_void};
+use std::mem::MaybeUninit;
+
+impl PluginUndoDelta {
+    /// Returns how the plugin provides undo deltas.
+    ///
+    /// If the plugin doesn't implement this function, it is considered to not provide any delta.
+    pub fn get_delta_properties(&self, plugin: &mut PluginMainThreadHandle) -> UndoDeltaProperties {
+        let Some(get_delta_properties) = plugin.use_extension(&self.0).get_delta_properties else {
+            return UndoDeltaProperties::default();
+        };
+
+        let mut properties = MaybeUninit::<clap_undo_delta_properties>::zeroed();
+
+        // SAFETY: This type ensures the function pointer is valid.
+        unsafe { get_delta_properties(plugin.as_raw(), properties.as_mut_ptr()) };
+
+        // SAFETY: the properties buffer was zero-initialized, which is a valid value.
+        UndoDeltaProperties::from_raw(unsafe { properties.assume_init_ref() })
+    }
+
+    /// Returns whether the plugin is able to apply deltas using the given format version.
+    #[inline]
+    pub fn can_use_delta_format_version(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        format_version: ClapId,
+    ) -> bool {
+        match plugin.use_extension(&self.0).can_use_delta_format_version {
+            None => false,
+            // SAFETY: This type ensures the function pointer is valid.
+            Some(can_use) => unsafe { can_use(plugin.as_raw(), format_version.get()) },
+        }
+    }
+
+    /// Asks the plugin to undo the change described by the given delta, which uses the given
+    /// format version.
+    ///
+    /// # Errors
+    ///
+    /// This returns an [`UndoError`] if the plugin failed to undo the change.
+    pub fn undo(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        format_version: ClapId,
+        delta: &[u8],
+    ) -> Result<(), UndoError> {
+        let undo = plugin
+            .use_extension(&self.0)
+            .undo
+            .ok_or(UndoError::undoing())?;
+
+        // SAFETY: This type ensures the function pointer is valid.
+        if unsafe {
+            undo(
+                plugin.as_raw(),
+                format_version.get(),
+                delta.as_ptr().cast(),
+                delta.len(),
+            )
+        } {
+            Ok(())
+        } else {
+            Err(UndoError::undoing())
+        }
+    }
+
+    /// Asks the plugin to redo the change described by the given delta, which uses the given
+    /// format version.
+    ///
+    /// # Errors
+    ///
+    /// This returns an [`UndoError`] if the plugin failed to redo the change.
+    pub fn redo(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        format_version: ClapId,
+        delta: &[u8],
+    ) -> Result<(), UndoError> {
+        let redo = plugin
+            .use_extension(&self.0)
+            .redo
+            .ok_or(UndoError::redoing())?;
+
+        // SAFETY: This type ensures the function pointer is valid.
+        if unsafe {
+            redo(
+                plugin.as_raw(),
+                format_version.get(),
+                delta.as_ptr().cast(),
+                delta.len(),
+            )
+        } {
+            Ok(())
+        } else {
+            Err(UndoError::redoing())
+        }
+    }
+}
+
+impl PluginUndoContext {
+    /// Informs the plugin whether the host is able to undo.
+    #[inline]
+    pub fn set_can_undo(&self, plugin: &mut PluginMainThreadHandle, can_undo: bool) {
+        if let Some(set_can_undo) = plugin.use_extension(&self.0).set_can_undo {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { set_can_undo(plugin.as_raw(), can_undo) }
+        }
+    }
+
+    /// Informs the plugin whether the host is able to redo.
+    #[inline]
+    pub fn set_can_redo(&self, plugin: &mut PluginMainThreadHandle, can_redo: bool) {
+        if let Some(set_can_redo) = plugin.use_extension(&self.0).set_can_redo {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { set_can_redo(plugin.as_raw(), can_redo) }
+        }
+    }
+
+    /// Informs the plugin of the name of the next undo step, or [`None`] if there is none.
+    #[inline]
+    pub fn set_undo_name(&self, plugin: &mut PluginMainThreadHandle, name: Option<&CStr>) {
+        if let Some(set_undo_name) = plugin.use_extension(&self.0).set_undo_name {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe {
+                set_undo_name(
+                    plugin.as_raw(),
+                    name.map_or(core::ptr::null(), CStr::as_ptr),
+                )
+            }
+        }
+    }
+
+    /// Informs the plugin of the name of the next redo step, or [`None`] if there is none.
+    #[inline]
+    pub fn set_redo_name(&self, plugin: &mut PluginMainThreadHandle, name: Option<&CStr>) {
+        if let Some(set_redo_name) = plugin.use_extension(&self.0).set_redo_name {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe {
+                set_redo_name(
+                    plugin.as_raw(),
+                    name.map_or(core::ptr::null(), CStr::as_ptr),
+                )
+            }
+        }
+    }
+}
+
+/// Implementation of the Host-side of the Undo extension.
+pub trait HostUndoImpl {
+    /// Called when the plugin begins a long-running change (e.g. a knob drag).
+    fn begin_change(&mut self);
+    /// Called when the plugin aborted the change started with
+    /// [`begin_change`](HostUndoImpl::begin_change).
+    fn cancel_change(&mut self);
+    /// Called when the plugin made a change, which should be added to the undo history.
+    ///
+    /// If the plugin provided a delta, it is given as an owned [`UndoDelta`], which can be
+    /// stored in the undo history. `delta_can_undo` indicates whether that delta can be used to
+    /// undo this change.
+    fn change_made(&mut self, name: &CStr, delta: Option<UndoDelta>, delta_can_undo: bool);
+    /// Called when the plugin requests the host to perform the next undo step.
+    fn request_undo(&mut self);
+    /// Called when the plugin requests the host to perform the next redo step.
+    fn request_redo(&mut self);
+    /// Called when the plugin subscribes to or unsubscribes from undo context updates, sent
+    /// through the [`PluginUndoContext`] extension.
+    fn set_wants_context_updates(&mut self, is_subscribed: bool);
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostUndo
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostUndoImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_host_undo {
+            begin_change: Some(begin_change::<H>),
+            cancel_change: Some(cancel_change::<H>),
+            change_made: Some(change_made::<H>),
+            request_undo: Some(request_undo::<H>),
+            request_redo: Some(request_redo::<H>),
+            set_wants_context_updates: Some(set_wants_context_updates::<H>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn begin_change<H: HostHandlers>(host: *const clap_host)
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostUndoImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        host.main_thread().as_mut().begin_change();
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn cancel_change<H: HostHandlers>(host: *const clap_host)
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostUndoImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        host.main_thread().as_mut().cancel_change();
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn change_made<H: HostHandlers>(
+    host: *const clap_host,
+    name: *const c_char,
+    delta: *const c_void,
+    delta_size: usize,
+    delta_can_undo: bool,
+) where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostUndoImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        if name.is_null() {
+            return Err(HostWrapperError::InvalidParameter("Null change name"));
+        }
+
+        let name = CStr::from_ptr(name);
+        let delta = (!delta.is_null() && delta_size > 0).then(|| {
+            UndoDelta::from_bytes(slice_from_external_parts(delta.cast::<u8>(), delta_size))
+        });
+
+        host.main_thread()
+            .as_mut()
+            .change_made(name, delta, delta_can_undo);
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn request_undo<H: HostHandlers>(host: *const clap_host)
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostUndoImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        host.main_thread().as_mut().request_undo();
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn request_redo<H: HostHandlers>(host: *const clap_host)
+where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostUndoImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        host.main_thread().as_mut().request_redo();
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn set_wants_context_updates<H: HostHandlers>(
+    host: *const clap_host,
+    is_subscribed: bool,
+) where
+    for<'a> <H as HostHandlers>::MainThread<'a>: HostUndoImpl,
+{
+    HostWrapper::<H>::handle(host, |host| {
+        host.main_thread()
+            .as_mut()
+            .set_wants_context_updates(is_subscribed);
+        Ok(())
+    });
+}
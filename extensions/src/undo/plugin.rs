@@ -0,0 +1,298 @@
+use super::*;
+use crate::utils::slice_from_external_parts;
+use clack_common::utils::cstr_from_nullable_ptr;
+use clack_plugin::extensions::prelude::*;
+use clap_sys::id::clap_id;
+use std::ffi::{c_char, c_void};
+
+impl HostUndo {
+    /// Informs the host that a long-running change (e.g. a knob drag) has begun.
+    ///
+    /// The change must then be either completed with [`change_made`](HostUndo::change_made), or
+    /// aborted with [`cancel_change`](HostUndo::cancel_change).
+    #[inline]
+    pub fn begin_change(&self, host: &mut HostMainThreadHandle) {
+        if let Some(begin_change) = host.use_extension(&self.0).begin_change {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { begin_change(host.as_raw()) }
+        }
+    }
+
+    /// Informs the host that the change started with [`begin_change`](HostUndo::begin_change)
+    /// was aborted.
+    #[inline]
+    pub fn cancel_change(&self, host: &mut HostMainThreadHandle) {
+        if let Some(cancel_change) = host.use_extension(&self.0).cancel_change {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { cancel_change(host.as_raw()) }
+        }
+    }
+
+    /// Informs the host that a change was made, and should be added to its undo history.
+    ///
+    /// The `name` describes the change to the user. If the plugin supports undo deltas, the
+    /// opaque `delta` can be used by the host to undo or redo this change through the
+    /// [`PluginUndoDelta`] extension. `delta_can_undo` indicates whether the given delta is
+    /// able to undo this change.
+    ///
+    /// The delta is copied by the host, and doesn't need to outlive this call.
+    pub fn change_made(
+        &self,
+        host: &mut HostMainThreadHandle,
+        name: &CStr,
+        delta: Option<&[u8]>,
+        delta_can_undo: bool,
+    ) {
+        let Some(change_made) = host.use_extension(&self.0).change_made else {
+            return;
+        };
+
+        let (delta_ptr, delta_size) = match delta {
+            Some(delta) if !delta.is_empty() => (delta.as_ptr().cast::<c_void>(), delta.len()),
+            _ => (core::ptr::null(), 0),
+        };
+
+        // SAFETY: This type ensures the function pointer is valid.
+        unsafe {
+            change_made(
+                host.as_raw(),
+                name.as_ptr(),
+                delta_ptr,
+                delta_size,
+                delta_can_undo,
+            )
+        }
+    }
+
+    /// Asks the host to perform the next undo step.
+    ///
+    /// The host may decline this request, or perform it asynchronously.
+    #[inline]
+    pub fn request_undo(&self, host: &mut HostMainThreadHandle) {
+        if let Some(request_undo) = host.use_extension(&self.0).request_undo {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { request_undo(host.as_raw()) }
+        }
+    }
+
+    /// Asks the host to perform the next redo step.
+    ///
+    /// The host may decline this request, or perform it asynchronously.
+    #[inline]
+    pub fn request_redo(&self, host: &mut HostMainThreadHandle) {
+        if let Some(request_redo) = host.use_extension(&self.0).request_redo {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { request_redo(host.as_raw()) }
+        }
+    }
+
+    /// Subscribes to or unsubscribes from undo context updates.
+    ///
+    /// While subscribed, the host keeps the plugin informed about the undo history through the
+    /// [`PluginUndoContext`] extension.
+    #[inline]
+    pub fn set_wants_context_updates(&self, host: &mut HostMainThreadHandle, is_subscribed: bool) {
+        if let Some(set_wants_context_updates) =
+            host.use_extension(&self.0).set_wants_context_updates
+        {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { set_wants_context_updates(host.as_raw(), is_subscribed) }
+        }
+    }
+}
+
+/// Implementation of the Plugin-side of the Undo Delta extension.
+pub trait PluginUndoDeltaImpl {
+    /// Returns how this plugin provides undo deltas.
+    fn get_delta_properties(&mut self) -> UndoDeltaProperties;
+
+    /// Returns whether this plugin is able to apply deltas using the given format version.
+    fn can_use_delta_format_version(&mut self, format_version: ClapId) -> bool;
+
+    /// Undoes the change described by the given delta, using the given format version.
+    fn undo(&mut self, format_version: ClapId, delta: &[u8]) -> Result<(), PluginError>;
+
+    /// Redoes the change described by the given delta, using the given format version.
+    fn redo(&mut self, format_version: ClapId, delta: &[u8]) -> Result<(), PluginError>;
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginUndoDelta
+where
+    for<'a> P::MainThread<'a>: PluginUndoDeltaImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_plugin_undo_delta {
+            get_delta_properties: Some(get_delta_properties::<P>),
+            can_use_delta_format_version: Some(can_use_delta_format_version::<P>),
+            undo: Some(undo::<P>),
+            redo: Some(redo::<P>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn get_delta_properties<P: Plugin>(
+    plugin: *const clap_plugin,
+    properties: *mut clap_undo_delta_properties,
+) where
+    for<'a> P::MainThread<'a>: PluginUndoDeltaImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        if properties.is_null() {
+            return Err(PluginWrapperError::NulPtr("clap_undo_delta_properties"));
+        }
+
+        let props = p.main_thread().as_mut().get_delta_properties();
+        properties.write(props.to_raw());
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn can_use_delta_format_version<P: Plugin>(
+    plugin: *const clap_plugin,
+    format_version: clap_id,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginUndoDeltaImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        let format_version = ClapId::from_raw(format_version).ok_or(
+            PluginWrapperError::InvalidParameter("Invalid format_version"),
+        )?;
+
+        Ok(p.main_thread()
+            .as_mut()
+            .can_use_delta_format_version(format_version))
+    })
+    .unwrap_or(false)
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn undo<P: Plugin>(
+    plugin: *const clap_plugin,
+    format_version: clap_id,
+    delta: *const c_void,
+    delta_size: usize,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginUndoDeltaImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        let format_version = ClapId::from_raw(format_version).ok_or(
+            PluginWrapperError::InvalidParameter("Invalid format_version"),
+        )?;
+        if delta.is_null() && delta_size > 0 {
+            return Err(PluginWrapperError::NulPtr("delta"));
+        }
+
+        let delta = slice_from_external_parts(delta.cast::<u8>(), delta_size);
+
+        p.main_thread().as_mut().undo(format_version, delta)?;
+        Ok(())
+    })
+    .is_some()
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn redo<P: Plugin>(
+    plugin: *const clap_plugin,
+    format_version: clap_id,
+    delta: *const c_void,
+    delta_size: usize,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginUndoDeltaImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        let format_version = ClapId::from_raw(format_version).ok_or(
+            PluginWrapperError::InvalidParameter("Invalid format_version"),
+        )?;
+        if delta.is_null() && delta_size > 0 {
+            return Err(PluginWrapperError::NulPtr("delta"));
+        }
+
+        let delta = slice_from_external_parts(delta.cast::<u8>(), delta_size);
+
+        p.main_thread().as_mut().redo(format_version, delta)?;
+        Ok(())
+    })
+    .is_some()
+}
+
+/// Implementation of the Plugin-side of the Undo Context extension.
+///
+/// The host only calls these methods after the plugin subscribed to context updates using
+/// [`HostUndo::set_wants_context_updates`].
+pub trait PluginUndoContextImpl {
+    /// Called when the host's ability to undo changes.
+    fn set_can_undo(&mut self, can_undo: bool);
+    /// Called when the host's ability to redo changes.
+    fn set_can_redo(&mut self, can_redo: bool);
+    /// Called when the name of the next undo step changes, or [`None`] if there is none.
+    fn set_undo_name(&mut self, name: Option<&CStr>);
+    /// Called when the name of the next redo step changes, or [`None`] if there is none.
+    fn set_redo_name(&mut self, name: Option<&CStr>);
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginUndoContext
+where
+    for<'a> P::MainThread<'a>: PluginUndoContextImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_plugin_undo_context {
+            set_can_undo: Some(set_can_undo::<P>),
+            set_can_redo: Some(set_can_redo::<P>),
+            set_undo_name: Some(set_undo_name::<P>),
+            set_redo_name: Some(set_redo_name::<P>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn set_can_undo<P: Plugin>(plugin: *const clap_plugin, can_undo: bool)
+where
+    for<'a> P::MainThread<'a>: PluginUndoContextImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        p.main_thread().as_mut().set_can_undo(can_undo);
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn set_can_redo<P: Plugin>(plugin: *const clap_plugin, can_redo: bool)
+where
+    for<'a> P::MainThread<'a>: PluginUndoContextImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        p.main_thread().as_mut().set_can_redo(can_redo);
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn set_undo_name<P: Plugin>(plugin: *const clap_plugin, name: *const c_char)
+where
+    for<'a> P::MainThread<'a>: PluginUndoContextImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        let name = cstr_from_nullable_ptr(name);
+        p.main_thread().as_mut().set_undo_name(name);
+        Ok(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn set_redo_name<P: Plugin>(plugin: *const clap_plugin, name: *const c_char)
+where
+    for<'a> P::MainThread<'a>: PluginUndoContextImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        let name = cstr_from_nullable_ptr(name);
+        p.main_thread().as_mut().set_redo_name(name);
+        Ok(())
+    });
+}
@@ -2,6 +2,9 @@
 
 use core::ffi::c_char;
 
+#[allow(unused)]
+pub(crate) use std::panic::catch_unwind as handle_panic;
+
 pub(crate) fn data_from_array_buf<const N: usize>(data: &[c_char; N]) -> &[u8] {
     // SAFETY: casting from i8 to u8 is safe
     let data = unsafe { core::slice::from_raw_parts(data.as_ptr() as *const _, data.len()) };
@@ -26,6 +29,24 @@ pub(crate) unsafe fn write_to_array_buf<const N: usize>(dst: *mut [c_char; N], v
     dst.add(max_len).write(0)
 }
 
+/// A safer form of [`core::slice::from_raw_parts`] that returns a properly aligned slice in case
+/// the length is 0.
+///
+/// See [`slice_from_external_parts_mut`] for more information.
+///
+/// # Safety
+///
+/// Same requirements as [`core::slice::from_raw_parts`], except the pointer *can* be null or
+/// dangling if `len == 0`.
+#[inline]
+pub(crate) unsafe fn slice_from_external_parts<'a, T>(data: *const T, len: usize) -> &'a [T] {
+    if len == 0 {
+        return &[];
+    }
+
+    core::slice::from_raw_parts(data, len)
+}
+
 /// A safer form of [`core::slice::from_raw_parts_mut`] that returns a properly aligned slice in case
 /// the length is 0.
 ///
@@ -46,3 +67,26 @@ pub(crate) unsafe fn slice_from_external_parts_mut<'a, T>(data: *mut T, len: usi
 
     core::slice::from_raw_parts_mut(data, len)
 }
+
+/// Builds an extension identifier from a nul-terminated byte string.
+///
+/// This is used for the extensions that are not part of the version of `clap-sys` currently in
+/// use. When used in a `const`, the given string is checked at compile time.
+pub(crate) const fn extension_id(id: &'static [u8]) -> &'static core::ffi::CStr {
+    assert!(
+        !id.is_empty() && id[id.len() - 1] == 0,
+        "Extension ID must be nul-terminated"
+    );
+
+    let mut i = 0;
+    while i < id.len() - 1 {
+        assert!(
+            id[i] != 0,
+            "Extension ID must not contain interior nul bytes"
+        );
+        i += 1;
+    }
+
+    // SAFETY: We just checked the string is nul-terminated and has no interior nul bytes.
+    unsafe { core::ffi::CStr::from_bytes_with_nul_unchecked(id) }
+}
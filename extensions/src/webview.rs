@@ -0,0 +1,497 @@
+//! Allows plugins to provide an HTML-based GUI, displayed by the host in a webview.
+//!
+//! The plugin's GUI is created through the [GUI](crate::gui) extension, using the
+//! [`WEBVIEW`](crate::gui::GuiApiType::WEBVIEW) API type. The host then opens the page at the URI
+//! given by the plugin, serving any resources the page requests from the plugin itself, as
+//! [`WebviewResource`]s.
+//!
+//! Once the page is loaded, the plugin and its webview can exchange messages: messages sent by
+//! the page are forwarded by the host to the plugin's `receive` callback, and the plugin can send
+//! messages to the page using [`HostWebview::send`].
+//!
+//! The contents of these messages are entirely defined by the plugin.
+
+#![deny(missing_docs)]
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use std::borrow::Cow;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+
+/// The C-FFI definitions of the Webview extension.
+///
+/// This extension is not part of the version of `clap-sys` currently in use, and is therefore
+/// defined here, following the CLAP headers.
+#[allow(non_camel_case_types)]
+mod sys {
+    use crate::utils::extension_id;
+    use clap_sys::host::clap_host;
+    use clap_sys::plugin::clap_plugin;
+    use clap_sys::stream::clap_ostream;
+    use std::ffi::{c_char, c_void, CStr};
+
+    pub const CLAP_EXT_WEBVIEW: &CStr = extension_id(b"clap.webview/3\0");
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_plugin_webview {
+        pub get_uri: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                uri: *mut c_char,
+                uri_capacity: u32,
+            ) -> i32,
+        >,
+        pub get_resource: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                path: *const c_char,
+                mime: *mut c_char,
+                mime_capacity: u32,
+                target: *const clap_ostream,
+            ) -> bool,
+        >,
+        pub receive: Option<
+            unsafe extern "C" fn(
+                plugin: *const clap_plugin,
+                buffer: *const c_void,
+                size: u32,
+            ) -> bool,
+        >,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct clap_host_webview {
+        pub send: Option<
+            unsafe extern "C" fn(host: *const clap_host, buffer: *const c_void, size: u32) -> bool,
+        >,
+    }
+}
+
+use sys::*;
+
+/// The Plugin-side of the Webview extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct PluginWebview(RawExtension<PluginExtensionSide, clap_plugin_webview>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginWebview {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_WEBVIEW;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The Host-side of the Webview extension.
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub struct HostWebview(RawExtension<HostExtensionSide, clap_host_webview>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostWebview {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_WEBVIEW;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// A resource served by the plugin to its webview, such as a page, a script, or an image.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct WebviewResource<'a> {
+    /// The MIME type of the resource, e.g. `text/html` or `image/png`.
+    pub mime_type: Cow<'a, str>,
+    /// The contents of the resource.
+    pub content: Cow<'a, [u8]>,
+}
+
+impl<'a> WebviewResource<'a> {
+    /// Creates a new resource from its MIME type and contents.
+    #[inline]
+    pub fn new(mime_type: impl Into<Cow<'a, str>>, content: impl Into<Cow<'a, [u8]>>) -> Self {
+        Self {
+            mime_type: mime_type.into(),
+            content: content.into(),
+        }
+    }
+}
+
+/// Errors that can occur while exchanging messages with a webview.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum WebviewError {
+    /// The host denied or failed to send a message to the webview.
+    SendError,
+    /// The plugin denied or failed to handle a message from the webview.
+    ReceiveError,
+}
+
+impl Display for WebviewError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebviewError::SendError => f.write_str("Failed to send message to the webview"),
+            WebviewError::ReceiveError => f.write_str("Plugin failed to receive webview message"),
+        }
+    }
+}
+
+impl Error for WebviewError {}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use crate::utils::slice_from_external_parts;
+    use clack_common::stream::OutputStream;
+    use clack_host::extensions::prelude::*;
+    use std::ffi::{c_char, c_void, CString};
+
+    /// The size of the buffer first given to the plugin to write its URI into.
+    const INITIAL_URI_BUFFER_SIZE: usize = 1024;
+
+    /// The size of the buffer given to the plugin to write a resource's MIME type into.
+    const MIME_TYPE_BUFFER_SIZE: usize = 256;
+
+    impl PluginWebview {
+        /// Returns the URI of the page to open in the webview.
+        ///
+        /// This returns [`None`] if the plugin failed to provide it, or if it isn't valid UTF-8.
+        pub fn get_uri(&self, plugin: &mut PluginMainThreadHandle) -> Option<String> {
+            let get_uri = plugin.use_extension(&self.0).get_uri?;
+            let mut buffer = vec![0u8; INITIAL_URI_BUFFER_SIZE];
+
+            loop {
+                let buffer_size = u32::try_from(buffer.len()).ok()?;
+
+                // SAFETY: This type ensures the function pointer is valid.
+                let length = unsafe {
+                    get_uri(
+                        plugin.as_raw(),
+                        buffer.as_mut_ptr().cast::<c_char>(),
+                        buffer_size,
+                    )
+                };
+                let length = usize::try_from(length).ok()?;
+
+                // The URI was truncated, try again with a large enough buffer.
+                if length >= buffer.len() {
+                    buffer.resize(length + 1, 0);
+                    continue;
+                }
+
+                let uri = CStr::from_bytes_until_nul(&buffer).ok()?;
+                return uri.to_str().ok().map(String::from);
+            }
+        }
+
+        /// Retrieves the resource at the given path from the plugin.
+        ///
+        /// This returns [`None`] if the plugin doesn't have a resource at that path, failed to
+        /// provide it, or if its MIME type isn't valid UTF-8.
+        pub fn get_resource(
+            &self,
+            plugin: &PluginSharedHandle,
+            path: &str,
+        ) -> Option<WebviewResource<'static>> {
+            let get_resource = plugin.use_extension(&self.0).get_resource?;
+            let path = CString::new(path).ok()?;
+
+            let mut mime_type = [0u8; MIME_TYPE_BUFFER_SIZE];
+            let mut content = Vec::new();
+            let mut stream = OutputStream::from_writer(&mut content);
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let success = unsafe {
+                get_resource(
+                    plugin.as_raw(),
+                    path.as_ptr(),
+                    mime_type.as_mut_ptr().cast::<c_char>(),
+                    MIME_TYPE_BUFFER_SIZE as u32,
+                    stream.as_raw_mut(),
+                )
+            };
+
+            if !success {
+                return None;
+            }
+
+            let mime_type = CStr::from_bytes_until_nul(&mime_type).ok()?.to_str().ok()?;
+            Some(WebviewResource::new(mime_type.to_owned(), content))
+        }
+
+        /// Forwards a message sent by the webview to the plugin.
+        ///
+        /// # Errors
+        ///
+        /// This may return a [`WebviewError::ReceiveError`] if the plugin denied or failed to
+        /// handle the message.
+        pub fn receive(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            message: &[u8],
+        ) -> Result<(), WebviewError> {
+            let receive = plugin
+                .use_extension(&self.0)
+                .receive
+                .ok_or(WebviewError::ReceiveError)?;
+            let size = u32::try_from(message.len()).map_err(|_| WebviewError::ReceiveError)?;
+
+            // SAFETY: This type ensures the function pointer is valid. The message buffer is valid
+            // for reads of its whole length for the duration of the call.
+            let success = unsafe { receive(plugin.as_raw(), message.as_ptr().cast(), size) };
+
+            match success {
+                true => Ok(()),
+                false => Err(WebviewError::ReceiveError),
+            }
+        }
+    }
+
+    /// Implementation of the Host-side of the Webview extension.
+    pub trait HostWebviewImpl {
+        /// Sends a message from the plugin to its webview.
+        ///
+        /// This may be called from any thread.
+        ///
+        /// # Errors
+        ///
+        /// This may return an error if the webview isn't open, or if the message could not be
+        /// sent.
+        fn send(&self, message: &[u8]) -> Result<(), HostError>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostWebview
+    where
+        for<'a> <H as HostHandlers>::Shared<'a>: HostWebviewImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_webview {
+                send: Some(send::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn send<H: HostHandlers>(
+        host: *const clap_host,
+        buffer: *const c_void,
+        size: u32,
+    ) -> bool
+    where
+        for<'a> <H as HostHandlers>::Shared<'a>: HostWebviewImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            if buffer.is_null() && size > 0 {
+                return Err(HostWrapperError::InvalidParameter(
+                    "Null webview message buffer",
+                ));
+            }
+
+            let message = slice_from_external_parts(buffer.cast::<u8>(), size as usize);
+            Ok(host.shared().send(message).is_ok())
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use crate::utils::{slice_from_external_parts, slice_from_external_parts_mut};
+    use clack_common::stream::OutputStream;
+    use clack_plugin::extensions::prelude::*;
+    use clap_sys::stream::clap_ostream;
+    use std::ffi::{c_char, c_void};
+    use std::io::Write;
+
+    impl HostWebview {
+        /// Sends a message to the plugin's webview.
+        ///
+        /// # Errors
+        ///
+        /// This may return a [`WebviewError::SendError`] if the host denied or failed to send the
+        /// message, e.g. because the webview isn't open.
+        pub fn send(&self, host: &HostSharedHandle, message: &[u8]) -> Result<(), WebviewError> {
+            let send = host
+                .use_extension(&self.0)
+                .send
+                .ok_or(WebviewError::SendError)?;
+            let size = u32::try_from(message.len()).map_err(|_| WebviewError::SendError)?;
+
+            // SAFETY: This type ensures the function pointer is valid. The message buffer is valid
+            // for reads of its whole length for the duration of the call.
+            let success = unsafe { send(host.as_raw(), message.as_ptr().cast(), size) };
+
+            match success {
+                true => Ok(()),
+                false => Err(WebviewError::SendError),
+            }
+        }
+    }
+
+    /// Implementation of the main-thread Plugin-side of the Webview extension.
+    pub trait PluginWebviewImpl {
+        /// Returns the URI of the page to open in the webview, or [`None`] if it is unavailable.
+        ///
+        /// This can be a remote URL, or a path to be served through
+        /// [`get_resource`](PluginWebviewResourcesImpl::get_resource).
+        fn get_uri(&mut self) -> Option<&str>;
+
+        /// Receives a message sent by the webview.
+        ///
+        /// # Errors
+        ///
+        /// This may return an error if the plugin failed to handle the message.
+        fn receive(&mut self, message: &[u8]) -> Result<(), PluginError>;
+    }
+
+    /// Implementation of the thread-safe Plugin-side of the Webview extension.
+    ///
+    /// This is implemented by the plugin's shared data, as the host may request resources from
+    /// any thread.
+    pub trait PluginWebviewResourcesImpl {
+        /// Returns the resource at the given path, or [`None`] if there is no such resource.
+        fn get_resource(&self, path: &str) -> Option<WebviewResource<'_>>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginWebview
+    where
+        for<'a> P::MainThread<'a>: PluginWebviewImpl,
+        for<'a> P::Shared<'a>: PluginWebviewResourcesImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_webview {
+                get_uri: Some(get_uri::<P>),
+                get_resource: Some(get_resource::<P>),
+                receive: Some(receive::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_uri<P: Plugin>(
+        plugin: *const clap_plugin,
+        uri: *mut c_char,
+        uri_capacity: u32,
+    ) -> i32
+    where
+        for<'a> P::MainThread<'a>: PluginWebviewImpl,
+        for<'a> P::Shared<'a>: PluginWebviewResourcesImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if uri.is_null() && uri_capacity > 0 {
+                return Err(PluginWrapperError::NulPtr("clap_plugin_webview.get_uri"));
+            }
+
+            let mut main_thread = p.main_thread();
+            let Some(plugin_uri) = main_thread.as_mut().get_uri() else {
+                return Ok(-1);
+            };
+            let plugin_uri = plugin_uri.as_bytes();
+
+            if plugin_uri.contains(&0) {
+                return Err(PluginWrapperError::InvalidParameter("Invalid webview URI"));
+            }
+
+            // The full length is returned even if the URI gets truncated, so that the host can
+            // retry with a large enough buffer.
+            let length = i32::try_from(plugin_uri.len())
+                .map_err(|_| PluginWrapperError::InvalidParameter("Webview URI is too long"))?;
+
+            let buffer = slice_from_external_parts_mut(uri.cast::<u8>(), uri_capacity as usize);
+            if let Some((nul, available)) = buffer.split_last_mut() {
+                let written = plugin_uri.len().min(available.len());
+                available[..written].copy_from_slice(&plugin_uri[..written]);
+                *available.get_mut(written).unwrap_or(nul) = 0;
+            }
+
+            Ok(length)
+        })
+        .unwrap_or(-1)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_resource<P: Plugin>(
+        plugin: *const clap_plugin,
+        path: *const c_char,
+        mime: *mut c_char,
+        mime_capacity: u32,
+        target: *const clap_ostream,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginWebviewImpl,
+        for<'a> P::Shared<'a>: PluginWebviewResourcesImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if path.is_null() || mime.is_null() || target.is_null() {
+                return Err(PluginWrapperError::NulPtr(
+                    "clap_plugin_webview.get_resource",
+                ));
+            }
+
+            let path = CStr::from_ptr(path)
+                .to_str()
+                .map_err(|_| PluginWrapperError::InvalidParameter("Invalid resource path"))?;
+
+            let Some(resource) = p.shared().get_resource(path) else {
+                return Ok(false);
+            };
+
+            // The MIME type must not be truncated, as it would then be invalid.
+            let mime_type = resource.mime_type.as_bytes();
+            if mime_type.len() >= mime_capacity as usize || mime_type.contains(&0) {
+                return Err(PluginWrapperError::InvalidParameter(
+                    "Resource MIME type is too long",
+                ));
+            }
+
+            let mime = slice_from_external_parts_mut(mime.cast::<u8>(), mime_capacity as usize);
+            mime[..mime_type.len()].copy_from_slice(mime_type);
+            mime[mime_type.len()] = 0;
+
+            let output = OutputStream::from_raw_mut(&mut *(target as *mut _));
+            output
+                .write_all(&resource.content)
+                .map_err(|_| PluginError::Message("Failed to write webview resource"))?;
+
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn receive<P: Plugin>(
+        plugin: *const clap_plugin,
+        buffer: *const c_void,
+        size: u32,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginWebviewImpl,
+        for<'a> P::Shared<'a>: PluginWebviewResourcesImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if buffer.is_null() && size > 0 {
+                return Err(PluginWrapperError::NulPtr("webview message buffer"));
+            }
+
+            let message = slice_from_external_parts(buffer.cast::<u8>(), size as usize);
+            p.main_thread().as_mut().receive(message)?;
+            Ok(true)
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
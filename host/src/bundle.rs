@@ -51,8 +51,8 @@
 //!
 //! # Plugin bundle discovery
 //!
-//! As of now, Clack does not implement any utilities to aid host implementations with discovering
-//! which CLAP bundle files are available to be loaded on the filesystem.
+//! The [`scan`](crate::scan) module provides utilities to find the CLAP bundle files that are
+//! installed in the standard search paths, and to list the plugins they contain.
 //!
 //! Refer to the
 //! [CLAP specification](https://github.com/free-audio/clap/blob/main/include/clap/entry.h) for more
@@ -19,6 +19,15 @@
 //!
 //! See the [`PluginFactory`]'s type documentation for more detail and examples on how to
 //! list plugins.
+//!
+//! Clack also supports the following draft factory types:
+//!
+//! * [`PresetDiscoveryFactory`](preset_discovery::PresetDiscoveryFactory), which enables hosts to
+//!   index the presets provided by the plugins in a bundle.
+//! * [`PluginInvalidationFactory`](plugin_invalidation::PluginInvalidationFactory), which enables
+//!   hosts to know when the list of plugins exposed by a bundle may have changed.
+//! * [`PluginStateConverterFactory`](plugin_state_converter::PluginStateConverterFactory), which
+//!   enables hosts to migrate the state of a plugin to another one.
 
 use crate::plugin::PluginInstanceError;
 use clap_sys::factory::plugin_factory::{clap_plugin_factory, CLAP_PLUGIN_FACTORY_ID};
@@ -29,6 +38,9 @@ use std::marker::PhantomData;
 use std::ptr::NonNull;
 
 mod plugin_descriptor;
+pub mod plugin_invalidation;
+pub mod plugin_state_converter;
+pub mod preset_discovery;
 pub use plugin_descriptor::*;
 
 /// A custom factory pointer type.
@@ -1,3 +1,4 @@
+use clack_common::utils::non_empty_cstr;
 use clap_sys::plugin::clap_plugin_descriptor;
 use std::ffi::CStr;
 use std::marker::PhantomData;
@@ -22,23 +23,6 @@ pub struct PluginDescriptor<'a> {
     descriptor: &'a clap_plugin_descriptor,
 }
 
-/// # Safety
-///
-/// Same as [`CStr::from_ptr`], except the given pointer *can* be null.
-unsafe fn cstr_to_str<'a>(ptr: *const std::os::raw::c_char) -> Option<&'a CStr> {
-    if ptr.is_null() {
-        return None;
-    }
-
-    let string = CStr::from_ptr(ptr);
-
-    if string.is_empty() {
-        None
-    } else {
-        Some(string)
-    }
-}
-
 impl<'a> PluginDescriptor<'a> {
     /// # Safety
     /// The user must ensure the provided descriptor is valid, including all of its pointers.
@@ -70,7 +54,7 @@ impl<'a> PluginDescriptor<'a> {
     /// ```
     pub fn id(&self) -> Option<&'a CStr> {
         // SAFETY: this type ensures the string pointer is valid
-        unsafe { cstr_to_str(self.descriptor.id) }
+        unsafe { non_empty_cstr(self.descriptor.id) }
     }
 
     /// The user-facing display name of this plugin.
@@ -90,7 +74,7 @@ impl<'a> PluginDescriptor<'a> {
     /// ```
     pub fn name(&self) -> Option<&'a CStr> {
         // SAFETY: this type ensures the string pointer is valid
-        unsafe { cstr_to_str(self.descriptor.name) }
+        unsafe { non_empty_cstr(self.descriptor.name) }
     }
 
     /// The vendor of this plugin.
@@ -107,7 +91,7 @@ impl<'a> PluginDescriptor<'a> {
     /// ```
     pub fn vendor(&self) -> Option<&'a CStr> {
         // SAFETY: this type ensures the string pointer is valid
-        unsafe { cstr_to_str(self.descriptor.vendor) }
+        unsafe { non_empty_cstr(self.descriptor.vendor) }
     }
 
     /// The URL of this plugin's homepage.
@@ -124,7 +108,7 @@ impl<'a> PluginDescriptor<'a> {
     /// ```
     pub fn url(&self) -> Option<&'a CStr> {
         // SAFETY: this type ensures the string pointer is valid
-        unsafe { cstr_to_str(self.descriptor.url) }
+        unsafe { non_empty_cstr(self.descriptor.url) }
     }
 
     /// The URL of this plugin's user's manual.
@@ -144,7 +128,7 @@ impl<'a> PluginDescriptor<'a> {
     /// ```
     pub fn manual_url(&self) -> Option<&'a CStr> {
         // SAFETY: this type ensures the string pointer is valid
-        unsafe { cstr_to_str(self.descriptor.manual_url) }
+        unsafe { non_empty_cstr(self.descriptor.manual_url) }
     }
 
     /// The URL of this plugin's support page.
@@ -161,7 +145,7 @@ impl<'a> PluginDescriptor<'a> {
     /// ```
     pub fn support_url(&self) -> Option<&'a CStr> {
         // SAFETY: this type ensures the string pointer is valid
-        unsafe { cstr_to_str(self.descriptor.support_url) }
+        unsafe { non_empty_cstr(self.descriptor.support_url) }
     }
 
     /// The version of this plugin.
@@ -181,7 +165,7 @@ impl<'a> PluginDescriptor<'a> {
     /// ```
     pub fn version(&self) -> Option<&'a CStr> {
         // SAFETY: this type ensures the string pointer is valid
-        unsafe { cstr_to_str(self.descriptor.version) }
+        unsafe { non_empty_cstr(self.descriptor.version) }
     }
 
     /// A short description of this plugin.
@@ -198,7 +182,7 @@ impl<'a> PluginDescriptor<'a> {
     /// ```
     pub fn description(&self) -> Option<&'a CStr> {
         // SAFETY: this type ensures the string pointer is valid
-        unsafe { cstr_to_str(self.descriptor.description) }
+        unsafe { non_empty_cstr(self.descriptor.description) }
     }
 
     /// An iterator over an arbitrary list of tags, that can be used by hosts to classify this plugin.
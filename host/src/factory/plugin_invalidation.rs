@@ -0,0 +1,193 @@
+//! The plugin invalidation factory type.
+//!
+//! The Plugin Invalidation Factory is exposed by plugin bundles that act as a "shell" for many
+//! sub-plugins, whose list can change without the bundle itself being modified.
+//!
+//! This factory exposes a list of [`InvalidationSource`s](InvalidationSource), which describe the
+//! files the host should watch. When any of them changes, the host should call
+//! [`PluginInvalidationFactory::refresh`], and then scan the bundle's
+//! [`PluginFactory`](crate::factory::PluginFactory) again.
+//!
+//! Note that the plugin invalidation factory is still a draft in the CLAP specification, and is
+//! therefore subject to change.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::factory::plugin_invalidation::PluginInvalidationFactory;
+//! use clack_host::prelude::*;
+//!
+//! fn print_invalidation_sources(bundle: &PluginBundle) {
+//!     let Some(factory) = bundle.get_factory::<PluginInvalidationFactory>() else {
+//!         return;
+//!     };
+//!
+//!     for source in factory.sources() {
+//!         println!(
+//!             "Watching {:?} in {:?} (recursive: {})",
+//!             source.filename_glob(),
+//!             source.directory(),
+//!             source.recursive_scan()
+//!         );
+//!     }
+//! }
+//! ```
+
+use crate::factory::FactoryPointer;
+use clack_common::utils::non_empty_cstr;
+use clap_sys::factory::draft::plugin_invalidation::{
+    clap_plugin_invalidation_factory, clap_plugin_invalidation_source,
+    CLAP_PLUGIN_INVALIDATION_FACTORY_ID,
+};
+use std::ffi::{c_void, CStr};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+/// A factory pointer that exposes a list of [`InvalidationSource`s](InvalidationSource).
+///
+/// See the [module documentation](self) for an example of how to use this factory.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct PluginInvalidationFactory<'a> {
+    inner: *const clap_plugin_invalidation_factory,
+    _lifetime: PhantomData<&'a clap_plugin_invalidation_factory>,
+}
+
+// SAFETY: This takes a clap_plugin_invalidation_factory pointer, which matches
+// CLAP_PLUGIN_INVALIDATION_FACTORY_ID
+unsafe impl<'a> FactoryPointer<'a> for PluginInvalidationFactory<'a> {
+    const IDENTIFIER: &'static CStr = CLAP_PLUGIN_INVALIDATION_FACTORY_ID;
+
+    #[inline]
+    unsafe fn from_raw(raw: NonNull<c_void>) -> Self {
+        Self {
+            inner: raw.as_ptr() as *const _,
+            _lifetime: PhantomData,
+        }
+    }
+}
+
+impl<'a> PluginInvalidationFactory<'a> {
+    /// Returns the number of invalidation sources exposed by this factory.
+    #[inline]
+    pub fn source_count(&self) -> u32 {
+        // SAFETY: no special safety considerations
+        match unsafe { (*self.inner).count } {
+            None => 0,
+            // SAFETY: this type ensures the function pointer is valid
+            Some(count) => unsafe { count(self.inner) },
+        }
+    }
+
+    /// Returns the [`InvalidationSource`] exposed by this factory at a given index, or `None` if
+    /// there is no source at the given index.
+    ///
+    /// See also the [`sources`](PluginInvalidationFactory::sources) method for a convenient
+    /// iterator of all the sources exposed by this factory.
+    #[inline]
+    pub fn source(&self, index: u32) -> Option<InvalidationSource<'a>> {
+        // SAFETY: the source is guaranteed not to outlive the entry
+        unsafe { (*self.inner).get?(self.inner, index).as_ref() }
+            // SAFETY: this source is guaranteed to be valid by the spec
+            .map(|s| unsafe { InvalidationSource::from_raw(s) })
+    }
+
+    /// Returns an iterator of all the [`InvalidationSource`s](InvalidationSource) exposed by this
+    /// factory.
+    #[inline]
+    pub fn sources(&self) -> InvalidationSourcesIter<'a> {
+        InvalidationSourcesIter {
+            factory: *self,
+            count: self.source_count(),
+            current_index: 0,
+        }
+    }
+
+    /// Asks the bundle to update its list of plugins, after a change was detected in one of the
+    /// invalidation sources.
+    ///
+    /// If this returns `true`, the bundle's [`PluginFactory`](crate::factory::PluginFactory) was
+    /// updated in place, and only has to be scanned again. Otherwise, the whole bundle has to be
+    /// unloaded and reloaded.
+    #[inline]
+    pub fn refresh(&self) -> bool {
+        // SAFETY: no special safety considerations
+        match unsafe { (*self.inner).refresh } {
+            None => false,
+            // SAFETY: this type ensures the function pointer is valid
+            Some(refresh) => unsafe { refresh(self.inner) },
+        }
+    }
+}
+
+/// An [`Iterator`] over all the [`InvalidationSource`s](InvalidationSource) exposed by a
+/// plugin invalidation factory.
+///
+/// See the [`PluginInvalidationFactory::sources`] method that produces this iterator.
+pub struct InvalidationSourcesIter<'a> {
+    factory: PluginInvalidationFactory<'a>,
+    current_index: u32,
+    count: u32,
+}
+
+impl<'a> Iterator for InvalidationSourcesIter<'a> {
+    type Item = InvalidationSource<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.current_index >= self.count {
+                return None;
+            }
+
+            let source = self.factory.source(self.current_index);
+            self.current_index += 1;
+
+            // Skip all none-returning indexes
+            if let Some(s) = source {
+                return Some(s);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count as usize, Some(self.count as usize))
+    }
+}
+
+/// A set of files that can change the list of plugins exposed by a bundle.
+#[derive(Copy, Clone)]
+pub struct InvalidationSource<'a> {
+    source: &'a clap_plugin_invalidation_source,
+}
+
+impl<'a> InvalidationSource<'a> {
+    /// # Safety
+    /// The user must ensure the provided source is valid, including all of its pointers.
+    #[inline]
+    pub(crate) unsafe fn from_raw(source: &'a clap_plugin_invalidation_source) -> Self {
+        Self { source }
+    }
+
+    /// The directory to watch.
+    #[inline]
+    pub fn directory(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.source.directory) }
+    }
+
+    /// The glob matching the names of the files to watch in the
+    /// [`directory`](InvalidationSource::directory).
+    #[inline]
+    pub fn filename_glob(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.source.filename_glob) }
+    }
+
+    /// Whether the subdirectories of the [`directory`](InvalidationSource::directory) should also
+    /// be watched.
+    #[inline]
+    pub fn recursive_scan(&self) -> bool {
+        self.source.recursive_scan
+    }
+}
@@ -0,0 +1,423 @@
+//! The plugin state converter factory type.
+//!
+//! The Plugin State Converter Factory allows hosts to convert the saved state of a plugin into
+//! the state of another plugin, e.g. when loading a project that uses an older version of a plugin
+//! whose ID changed, or a version of the plugin using another ABI.
+//!
+//! This factory exposes a list of converters, described by their
+//! [`PluginStateConverterDescriptor`], which advertise the ID of the plugin they convert from
+//! ([`src_plugin_id`](PluginStateConverterDescriptor::src_plugin_id)) and to
+//! ([`dst_plugin_id`](PluginStateConverterDescriptor::dst_plugin_id)). Once created using
+//! [`PluginStateConverterFactory::create_converter`], a [`PluginStateConverter`] can convert both
+//! the plugin's state and its parameter values (e.g. for automation).
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::factory::plugin_state_converter::*;
+//! use clack_host::prelude::*;
+//! use std::ffi::CStr;
+//!
+//! fn convert_state(bundle: &PluginBundle, src_plugin_id: PluginId, state: &[u8]) -> Option<Vec<u8>> {
+//!     let factory = bundle.get_factory::<PluginStateConverterFactory>()?;
+//!
+//!     let descriptor = factory
+//!         .converter_descriptors()
+//!         .find(|d| d.src_plugin_id() == Some(src_plugin_id))?;
+//!
+//!     let mut converter = factory.create_converter(descriptor.id()?).ok()?;
+//!
+//!     let mut converted = vec![];
+//!     converter.convert_state(&mut &state[..], &mut converted).ok()?;
+//!
+//!     Some(converted)
+//! }
+//! ```
+
+use crate::factory::FactoryPointer;
+use clack_common::factory::plugin_state_converter::sys::{
+    clap_plugin_state_converter, clap_plugin_state_converter_descriptor,
+    clap_plugin_state_converter_factory, CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID,
+};
+use clack_common::stream::{InputStream, OutputStream};
+use clack_common::utils::non_empty_cstr;
+use clack_common::utils::{ClapId, ClapVersion};
+use std::error::Error;
+use std::ffi::{c_void, CStr};
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+pub use clack_common::factory::plugin_state_converter::*;
+
+/// A factory pointer that exposes a list of plugin state converters.
+///
+/// See the [module documentation](self) for an example of how to use this factory.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct PluginStateConverterFactory<'a> {
+    inner: *const clap_plugin_state_converter_factory,
+    _lifetime: PhantomData<&'a clap_plugin_state_converter_factory>,
+}
+
+// SAFETY: This takes a clap_plugin_state_converter_factory pointer, which matches
+// CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID
+unsafe impl<'a> FactoryPointer<'a> for PluginStateConverterFactory<'a> {
+    const IDENTIFIER: &'static CStr = CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID;
+
+    #[inline]
+    unsafe fn from_raw(raw: NonNull<c_void>) -> Self {
+        Self {
+            inner: raw.as_ptr() as *const _,
+            _lifetime: PhantomData,
+        }
+    }
+}
+
+impl<'a> PluginStateConverterFactory<'a> {
+    /// Returns the number of converters exposed by this factory.
+    #[inline]
+    pub fn converter_count(&self) -> u32 {
+        // SAFETY: no special safety considerations
+        match unsafe { (*self.inner).count } {
+            None => 0,
+            // SAFETY: this type ensures the function pointer is valid
+            Some(count) => unsafe { count(self.inner) },
+        }
+    }
+
+    /// Returns the [`PluginStateConverterDescriptor`] exposed by this factory at a given index, or
+    /// `None` if there is no converter descriptor at the given index.
+    ///
+    /// See also the [`converter_descriptors`](PluginStateConverterFactory::converter_descriptors)
+    /// method for a convenient iterator of all the converter descriptors exposed by this factory.
+    #[inline]
+    pub fn converter_descriptor(&self, index: u32) -> Option<PluginStateConverterDescriptor<'a>> {
+        // SAFETY: descriptor is guaranteed not to outlive the entry
+        unsafe { (*self.inner).get_descriptor?(self.inner, index).as_ref() }
+            // SAFETY: this descriptor is guaranteed to be valid by the spec
+            .map(|d| unsafe { PluginStateConverterDescriptor::from_raw(d) })
+    }
+
+    /// Returns an iterator of all the
+    /// [`PluginStateConverterDescriptor`s](PluginStateConverterDescriptor) exposed by this factory.
+    #[inline]
+    pub fn converter_descriptors(&self) -> PluginStateConverterDescriptorsIter<'a> {
+        PluginStateConverterDescriptorsIter {
+            factory: *self,
+            count: self.converter_count(),
+            current_index: 0,
+        }
+    }
+
+    /// Creates the converter matching the given `converter_id`.
+    ///
+    /// # Errors
+    ///
+    /// This returns a [`ConverterInstanceError`] if the converter could not be found.
+    pub fn create_converter(
+        &self,
+        converter_id: &CStr,
+    ) -> Result<PluginStateConverter<'a>, ConverterInstanceError> {
+        // SAFETY: this type ensures the factory pointer is valid.
+        let create = unsafe { (*self.inner).create }
+            .ok_or(ConverterInstanceError::NullFactoryCreateFunction)?;
+
+        // SAFETY: this type ensures the function pointer is valid.
+        let raw = unsafe { create(self.inner, converter_id.as_ptr()) };
+
+        Ok(PluginStateConverter {
+            raw: NonNull::new(raw).ok_or(ConverterInstanceError::ConverterNotFound)?,
+            _lifetime: PhantomData,
+        })
+    }
+}
+
+/// An [`Iterator`] over all the [`PluginStateConverterDescriptor`s](PluginStateConverterDescriptor)
+/// exposed by a plugin state converter factory.
+///
+/// See the [`PluginStateConverterFactory::converter_descriptors`] method that produces this
+/// iterator.
+pub struct PluginStateConverterDescriptorsIter<'a> {
+    factory: PluginStateConverterFactory<'a>,
+    current_index: u32,
+    count: u32,
+}
+
+impl<'a> Iterator for PluginStateConverterDescriptorsIter<'a> {
+    type Item = PluginStateConverterDescriptor<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.current_index >= self.count {
+                return None;
+            }
+
+            let descriptor = self.factory.converter_descriptor(self.current_index);
+            self.current_index += 1;
+
+            // Skip all none-returning indexes
+            if let Some(d) = descriptor {
+                return Some(d);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count as usize, Some(self.count as usize))
+    }
+}
+
+/// Various information about a plugin state converter.
+///
+/// All fields of this type as exposed as optional, however the CLAP specification requires the
+/// [`id`](PluginStateConverterDescriptor::id), [`name`](PluginStateConverterDescriptor::name), and
+/// the source and destination plugin IDs to be present.
+#[derive(Copy, Clone)]
+pub struct PluginStateConverterDescriptor<'a> {
+    descriptor: &'a clap_plugin_state_converter_descriptor,
+}
+
+impl<'a> PluginStateConverterDescriptor<'a> {
+    /// # Safety
+    /// The user must ensure the provided descriptor is valid, including all of its pointers.
+    #[inline]
+    pub(crate) unsafe fn from_raw(descriptor: &'a clap_plugin_state_converter_descriptor) -> Self {
+        Self { descriptor }
+    }
+
+    /// The CLAP version the converter implements.
+    #[inline]
+    pub fn clap_version(&self) -> ClapVersion {
+        ClapVersion::from_raw(self.descriptor.clap_version)
+    }
+
+    /// The identifier of the plugin this converter converts the state from.
+    #[inline]
+    pub fn src_plugin_id(&self) -> Option<PluginId<'a>> {
+        // SAFETY: this type ensures the string pointers are valid
+        unsafe { PluginId::from_raw(&self.descriptor.src_plugin_id) }
+    }
+
+    /// The identifier of the plugin this converter converts the state to.
+    #[inline]
+    pub fn dst_plugin_id(&self) -> Option<PluginId<'a>> {
+        // SAFETY: this type ensures the string pointers are valid
+        unsafe { PluginId::from_raw(&self.descriptor.dst_plugin_id) }
+    }
+
+    /// An arbitrary string identifier that is unique to this converter.
+    ///
+    /// This is the identifier that must be passed to
+    /// [`create_converter`](PluginStateConverterFactory::create_converter).
+    #[inline]
+    pub fn id(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.descriptor.id) }
+    }
+
+    /// The user-facing display name of this converter.
+    #[inline]
+    pub fn name(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.descriptor.name) }
+    }
+
+    /// The vendor of this converter.
+    #[inline]
+    pub fn vendor(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.descriptor.vendor) }
+    }
+
+    /// The version of this converter.
+    #[inline]
+    pub fn version(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.descriptor.version) }
+    }
+
+    /// A short description of this converter.
+    #[inline]
+    pub fn description(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.descriptor.description) }
+    }
+}
+
+/// A plugin state converter instance.
+///
+/// Converters are created by [`PluginStateConverterFactory::create_converter`], and are destroyed
+/// when this type is dropped.
+pub struct PluginStateConverter<'a> {
+    raw: NonNull<clap_plugin_state_converter>,
+    _lifetime: PhantomData<&'a clap_plugin_state_converter_factory>,
+}
+
+impl<'a> PluginStateConverter<'a> {
+    /// The maximum length of the error messages the converter can report.
+    const ERROR_BUFFER_SIZE: usize = 1024;
+
+    #[inline]
+    fn raw(&self) -> &clap_plugin_state_converter {
+        // SAFETY: this type ensures the converter pointer is valid until it is dropped.
+        unsafe { self.raw.as_ref() }
+    }
+
+    /// Returns the descriptor of this converter, if the converter exposes it.
+    #[inline]
+    pub fn descriptor(&self) -> Option<PluginStateConverterDescriptor<'_>> {
+        // SAFETY: the descriptor pointer is guaranteed to be valid by the spec, if not null.
+        unsafe {
+            self.raw()
+                .desc
+                .as_ref()
+                .map(|d| PluginStateConverterDescriptor::from_raw(d))
+        }
+    }
+
+    /// Reads the state of the source plugin from the given `src` reader, and writes the converted
+    /// state of the destination plugin into the given `dst` writer.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`ConverterInstanceError::ConvertStateFailed`] if the conversion failed,
+    /// alongside the error message reported by the converter, if any.
+    pub fn convert_state<R: Read, W: Write>(
+        &mut self,
+        src: &mut R,
+        dst: &mut W,
+    ) -> Result<(), ConverterInstanceError> {
+        let Some(convert_state) = self.raw().convert_state else {
+            return Err(ConverterInstanceError::ConvertStateFailed(None));
+        };
+
+        let mut input = InputStream::from_reader(src);
+        let mut output = OutputStream::from_writer(dst);
+        let mut error_buffer = [0u8; Self::ERROR_BUFFER_SIZE];
+
+        // SAFETY: this type ensures the function pointer is valid. The streams and the error
+        // buffer are valid for the duration of the call.
+        let success = unsafe {
+            convert_state(
+                self.raw.as_ptr(),
+                input.as_raw_mut(),
+                output.as_raw_mut(),
+                error_buffer.as_mut_ptr().cast(),
+                error_buffer.len(),
+            )
+        };
+
+        if success {
+            return Ok(());
+        }
+
+        let message = CStr::from_bytes_until_nul(&error_buffer)
+            .ok()
+            .filter(|m| !m.is_empty())
+            .map(|m| m.to_string_lossy().into_owned());
+
+        Err(ConverterInstanceError::ConvertStateFailed(message))
+    }
+
+    /// Converts the normalized value of a source plugin parameter, returning the ID and normalized
+    /// value of the matching destination plugin parameter.
+    ///
+    /// This returns [`None`] if the converter could not convert the value.
+    pub fn convert_normalized_value(
+        &mut self,
+        src_param_id: ClapId,
+        src_normalized_value: f64,
+    ) -> Option<(ClapId, f64)> {
+        let convert = self.raw().convert_normalized_value?;
+        let mut dst_param_id = u32::MAX;
+        let mut dst_value = 0.0;
+
+        // SAFETY: this type ensures the function pointer is valid. The destination pointers are
+        // valid for the duration of the call.
+        let success = unsafe {
+            convert(
+                self.raw.as_ptr(),
+                src_param_id.get(),
+                src_normalized_value,
+                &mut dst_param_id,
+                &mut dst_value,
+            )
+        };
+
+        success.then_some((ClapId::from_raw(dst_param_id)?, dst_value))
+    }
+
+    /// Converts the plain value of a source plugin parameter, returning the ID and plain value of
+    /// the matching destination plugin parameter.
+    ///
+    /// This returns [`None`] if the converter could not convert the value.
+    pub fn convert_plain_value(
+        &mut self,
+        src_param_id: ClapId,
+        src_plain_value: f64,
+    ) -> Option<(ClapId, f64)> {
+        let convert = self.raw().convert_plain_value?;
+        let mut dst_param_id = u32::MAX;
+        let mut dst_value = 0.0;
+
+        // SAFETY: this type ensures the function pointer is valid. The destination pointers are
+        // valid for the duration of the call.
+        let success = unsafe {
+            convert(
+                self.raw.as_ptr(),
+                src_param_id.get(),
+                src_plain_value,
+                &mut dst_param_id,
+                &mut dst_value,
+            )
+        };
+
+        success.then_some((ClapId::from_raw(dst_param_id)?, dst_value))
+    }
+}
+
+impl Drop for PluginStateConverter<'_> {
+    fn drop(&mut self) {
+        // SAFETY: this type ensures the converter pointer is valid, and it is never used again.
+        unsafe {
+            if let Some(destroy) = self.raw().destroy {
+                destroy(self.raw.as_ptr());
+            }
+        }
+    }
+}
+
+/// Errors that can occur while creating or using a [`PluginStateConverter`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConverterInstanceError {
+    /// The underlying factory's `create` C function was a null pointer.
+    ///
+    /// This is a sign of a misbehaving plugin implementation.
+    NullFactoryCreateFunction,
+    /// No converter with a matching ID was found.
+    ConverterNotFound,
+    /// The converter failed to convert the plugin's state.
+    ///
+    /// This contains the error message reported by the converter, if any.
+    ConvertStateFailed(Option<String>),
+}
+
+impl Display for ConverterInstanceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NullFactoryCreateFunction => {
+                f.write_str("Plugin state converter factory create function is a null pointer")
+            }
+            Self::ConverterNotFound => f.write_str("Specified converter was not found"),
+            Self::ConvertStateFailed(None) => f.write_str("Plugin state conversion failed"),
+            Self::ConvertStateFailed(Some(message)) => {
+                write!(f, "Plugin state conversion failed: {message}")
+            }
+        }
+    }
+}
+
+impl Error for ConverterInstanceError {}
@@ -0,0 +1,290 @@
+//! The preset discovery factory type.
+//!
+//! The Preset Discovery Factory allows hosts to index the presets provided by the plugins in a
+//! bundle (e.g. to display them in a preset browser), without having to instantiate the plugins
+//! themselves.
+//!
+//! This factory exposes a list of preset providers, described by their [`ProviderDescriptor`].
+//! Providers are created by the host using [`PresetDiscoveryFactory::create_provider`], alongside
+//! a [`PresetIndexer`] implementation which receives the [file types](FileType),
+//! [locations](LocationInfo) and [soundpacks](Soundpack) the provider declares. The host can then
+//! crawl the declared locations, and ask the provider to describe the presets they contain to a
+//! [`MetadataReceiver`], such as the provided [`PresetMetadataCollector`].
+//!
+//! Note that the preset discovery factory is still a draft in the CLAP specification, and is
+//! therefore subject to change.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::factory::preset_discovery::*;
+//! use clack_host::prelude::*;
+//! use std::ffi::CStr;
+//!
+//! #[derive(Default)]
+//! struct MyIndexer {
+//!     locations: Vec<String>,
+//! }
+//!
+//! impl PresetIndexer for MyIndexer {
+//!     fn declare_filetype(&mut self, _file_type: FileType) -> Result<(), HostError> {
+//!         Ok(())
+//!     }
+//!
+//!     fn declare_location(&mut self, location: LocationInfo) -> Result<(), HostError> {
+//!         self.locations.push(location.name.to_string_lossy().into_owned());
+//!         Ok(())
+//!     }
+//!
+//!     fn declare_soundpack(&mut self, _soundpack: Soundpack) -> Result<(), HostError> {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! fn index_presets(bundle: &PluginBundle, host_info: &HostInfo) -> Vec<PresetMetadata> {
+//!     let Some(factory) = bundle.get_factory::<PresetDiscoveryFactory>() else {
+//!         return vec![];
+//!     };
+//!
+//!     let mut collector = PresetMetadataCollector::new();
+//!
+//!     for descriptor in factory.provider_descriptors() {
+//!         let Some(id) = descriptor.id() else { continue };
+//!         let Ok(mut provider) =
+//!             factory.create_provider(id, MyIndexer::default(), host_info)
+//!         else {
+//!             continue;
+//!         };
+//!
+//!         // A real host would also crawl the file locations the provider declared.
+//!         let _ = provider.get_metadata(Location::Plugin, &mut collector);
+//!     }
+//!
+//!     collector.into_presets()
+//! }
+//! ```
+
+use crate::factory::FactoryPointer;
+use crate::host::HostInfo;
+use clack_common::utils::non_empty_cstr;
+use clack_common::utils::ClapVersion;
+use clap_sys::factory::draft::preset_discovery::{
+    clap_preset_discovery_factory, clap_preset_discovery_provider_descriptor,
+    CLAP_PRESET_DISCOVERY_FACTORY_ID,
+};
+use std::error::Error;
+use std::ffi::{c_void, CStr};
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+pub use clack_common::factory::preset_discovery::*;
+
+mod indexer;
+mod metadata;
+mod provider;
+
+pub use indexer::PresetIndexer;
+pub use metadata::{
+    MetadataError, MetadataReceiver, PresetMetadata, PresetMetadataCollector, PresetPluginId,
+};
+pub use provider::Provider;
+
+/// A factory pointer that exposes a list of preset providers.
+///
+/// See the [module documentation](self) for an example of how to use this factory to index the
+/// presets of a bundle.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct PresetDiscoveryFactory<'a> {
+    inner: *const clap_preset_discovery_factory,
+    _lifetime: PhantomData<&'a clap_preset_discovery_factory>,
+}
+
+// SAFETY: This takes a clap_preset_discovery_factory pointer, which matches
+// CLAP_PRESET_DISCOVERY_FACTORY_ID
+unsafe impl<'a> FactoryPointer<'a> for PresetDiscoveryFactory<'a> {
+    const IDENTIFIER: &'static CStr = CLAP_PRESET_DISCOVERY_FACTORY_ID;
+
+    #[inline]
+    unsafe fn from_raw(raw: NonNull<c_void>) -> Self {
+        Self {
+            inner: raw.as_ptr() as *const _,
+            _lifetime: PhantomData,
+        }
+    }
+}
+
+impl<'a> PresetDiscoveryFactory<'a> {
+    /// Returns the number of preset providers exposed by this factory.
+    #[inline]
+    pub fn provider_count(&self) -> u32 {
+        // SAFETY: no special safety considerations
+        match unsafe { (*self.inner).count } {
+            None => 0,
+            // SAFETY: this type ensures the function pointer is valid
+            Some(count) => unsafe { count(self.inner) },
+        }
+    }
+
+    /// Returns the [`ProviderDescriptor`] exposed by this factory at a given index, or `None` if
+    /// there is no provider descriptor at the given index.
+    ///
+    /// See also the [`provider_descriptors`](PresetDiscoveryFactory::provider_descriptors) method
+    /// for a convenient iterator of all the provider descriptors exposed by this factory.
+    #[inline]
+    pub fn provider_descriptor(&self, index: u32) -> Option<ProviderDescriptor<'a>> {
+        // SAFETY: descriptor is guaranteed not to outlive the entry
+        unsafe { (*self.inner).get_descriptor?(self.inner, index).as_ref() }
+            // SAFETY: this descriptor is guaranteed to be valid by the spec
+            .map(|d| unsafe { ProviderDescriptor::from_raw(d) })
+    }
+
+    /// Returns an iterator of all the [`ProviderDescriptor`s](ProviderDescriptor) exposed by this
+    /// factory.
+    #[inline]
+    pub fn provider_descriptors(&self) -> ProviderDescriptorsIter<'a> {
+        ProviderDescriptorsIter {
+            factory: *self,
+            count: self.provider_count(),
+            current_index: 0,
+        }
+    }
+
+    /// Creates and initializes the preset provider matching the given `provider_id`.
+    ///
+    /// The given `indexer` receives all the declarations the provider makes during its
+    /// initialization, and can be accessed afterward using [`Provider::indexer`]. The name,
+    /// vendor, URL and version of the indexer are taken from the given [`HostInfo`].
+    ///
+    /// # Errors
+    ///
+    /// This returns a [`ProviderInstanceError`] if the provider could not be found, or if its
+    /// initialization failed.
+    pub fn create_provider<I: PresetIndexer>(
+        &self,
+        provider_id: &CStr,
+        indexer: I,
+        host_info: &HostInfo,
+    ) -> Result<Provider<'a, I>, ProviderInstanceError> {
+        // SAFETY: this type ensures the factory pointer is valid for 'a.
+        unsafe { Provider::new(self.inner, provider_id, indexer, host_info) }
+    }
+}
+
+/// An [`Iterator`] over all the [`ProviderDescriptor`s](ProviderDescriptor) exposed by a
+/// preset discovery factory.
+///
+/// See the [`PresetDiscoveryFactory::provider_descriptors`] method that produces this iterator.
+pub struct ProviderDescriptorsIter<'a> {
+    factory: PresetDiscoveryFactory<'a>,
+    current_index: u32,
+    count: u32,
+}
+
+impl<'a> Iterator for ProviderDescriptorsIter<'a> {
+    type Item = ProviderDescriptor<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.current_index >= self.count {
+                return None;
+            }
+
+            let descriptor = self.factory.provider_descriptor(self.current_index);
+            self.current_index += 1;
+
+            // Skip all none-returning indexes
+            if let Some(d) = descriptor {
+                return Some(d);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count as usize, Some(self.count as usize))
+    }
+}
+
+/// Various textual information about a preset provider.
+///
+/// All fields of this type as exposed as optional, however the CLAP specification requires the
+/// [`id`](ProviderDescriptor::id) and [`name`](ProviderDescriptor::name) fields to be present.
+#[derive(Copy, Clone)]
+pub struct ProviderDescriptor<'a> {
+    descriptor: &'a clap_preset_discovery_provider_descriptor,
+}
+
+impl<'a> ProviderDescriptor<'a> {
+    /// # Safety
+    /// The user must ensure the provided descriptor is valid, including all of its pointers.
+    #[inline]
+    pub(crate) unsafe fn from_raw(
+        descriptor: &'a clap_preset_discovery_provider_descriptor,
+    ) -> Self {
+        Self { descriptor }
+    }
+
+    /// The CLAP version the provider implements.
+    #[inline]
+    pub fn clap_version(&self) -> ClapVersion {
+        ClapVersion::from_raw(self.descriptor.clap_version)
+    }
+
+    /// An arbitrary string identifier that is unique to this provider.
+    ///
+    /// This is the identifier that must be passed to
+    /// [`create_provider`](PresetDiscoveryFactory::create_provider).
+    #[inline]
+    pub fn id(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.descriptor.id) }
+    }
+
+    /// The user-facing display name of this provider.
+    #[inline]
+    pub fn name(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.descriptor.name) }
+    }
+
+    /// The vendor of this provider.
+    #[inline]
+    pub fn vendor(&self) -> Option<&'a CStr> {
+        // SAFETY: this type ensures the string pointer is valid
+        unsafe { non_empty_cstr(self.descriptor.vendor) }
+    }
+}
+
+/// Errors that can occur while creating or using a preset [`Provider`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProviderInstanceError {
+    /// The underlying factory's `create` C function was a null pointer.
+    ///
+    /// This is a sign of a misbehaving plugin implementation.
+    NullFactoryCreateFunction,
+    /// No provider with a matching ID was found.
+    ProviderNotFound,
+    /// The provider's initialization failed.
+    InitializationFailed,
+    /// The provider failed to read the metadata of the given location.
+    GetMetadataFailed,
+}
+
+impl Display for ProviderInstanceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            Self::NullFactoryCreateFunction => {
+                "Preset discovery factory create function is a null pointer"
+            }
+            Self::ProviderNotFound => "Specified provider was not found",
+            Self::InitializationFailed => "Preset provider initialization failed",
+            Self::GetMetadataFailed => "Preset provider failed to read the location's metadata",
+        };
+
+        f.write_str(msg)
+    }
+}
+
+impl Error for ProviderInstanceError {}
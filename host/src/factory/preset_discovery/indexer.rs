@@ -0,0 +1,172 @@
+use crate::factory::preset_discovery::{FileType, LocationInfo, Soundpack};
+use crate::host::{HostError, HostInfo};
+use clap_sys::factory::draft::preset_discovery::{
+    clap_preset_discovery_filetype, clap_preset_discovery_indexer, clap_preset_discovery_location,
+    clap_preset_discovery_soundpack,
+};
+use clap_sys::version::CLAP_VERSION;
+use std::ffi::{c_char, c_void};
+use std::panic::AssertUnwindSafe;
+use std::ptr::NonNull;
+
+/// Implementation of a host's preset indexer.
+///
+/// An indexer receives everything a preset provider declares as it is being initialized: the
+/// [file types](FileType) it supports, the [locations](LocationInfo) the host should crawl to find
+/// presets, and the [soundpacks](Soundpack) these presets may belong to.
+///
+/// All the given data is only borrowed for the duration of the call, and has to be copied if the
+/// indexer wants to keep it.
+pub trait PresetIndexer {
+    /// Receives a preset file type declared by the provider.
+    ///
+    /// # Errors
+    ///
+    /// This may return an error if the indexer rejects the file type.
+    fn declare_filetype(&mut self, file_type: FileType) -> Result<(), HostError>;
+
+    /// Receives a preset location declared by the provider.
+    ///
+    /// # Errors
+    ///
+    /// This may return an error if the indexer rejects the location.
+    fn declare_location(&mut self, location: LocationInfo) -> Result<(), HostError>;
+
+    /// Receives a soundpack declared by the provider.
+    ///
+    /// # Errors
+    ///
+    /// This may return an error if the indexer rejects the soundpack.
+    fn declare_soundpack(&mut self, soundpack: Soundpack) -> Result<(), HostError>;
+}
+
+/// The raw, C-FFI compatible indexer given to a preset provider, alongside its implementation.
+pub(crate) struct IndexerWrapper<I> {
+    raw: clap_preset_discovery_indexer,
+    indexer: I,
+    _host_info: HostInfo,
+}
+
+impl<I: PresetIndexer> IndexerWrapper<I> {
+    /// Allocates a new indexer wrapper. It must be freed using [`Self::free`].
+    pub(crate) fn new(indexer: I, host_info: &HostInfo) -> NonNull<Self> {
+        let mut raw = clap_preset_discovery_indexer {
+            clap_version: CLAP_VERSION,
+            name: core::ptr::null(),
+            vendor: core::ptr::null(),
+            url: core::ptr::null(),
+            version: core::ptr::null(),
+            indexer_data: core::ptr::null_mut(),
+            declare_filetype: Some(Self::declare_filetype),
+            declare_location: Some(Self::declare_location),
+            declare_soundpack: Some(Self::declare_soundpack),
+            get_extension: Some(Self::get_extension),
+        };
+
+        host_info.write_to_raw_indexer(&mut raw);
+
+        let wrapper = Box::into_raw(Box::new(Self {
+            raw,
+            indexer,
+            _host_info: host_info.clone(),
+        }));
+
+        // SAFETY: the pointer comes from Box::into_raw, it is valid and non-null.
+        unsafe {
+            (*wrapper).raw.indexer_data = wrapper.cast();
+            NonNull::new_unchecked(wrapper)
+        }
+    }
+
+    /// # Safety
+    /// The given pointer must come from [`Self::new`], and must not be used afterward.
+    pub(crate) unsafe fn free(wrapper: NonNull<Self>) -> I {
+        Box::from_raw(wrapper.as_ptr()).indexer
+    }
+
+    /// # Safety
+    /// The given pointer must come from [`Self::new`], and must not have been freed.
+    #[inline]
+    pub(crate) unsafe fn as_raw(wrapper: NonNull<Self>) -> *const clap_preset_discovery_indexer {
+        core::ptr::addr_of!((*wrapper.as_ptr()).raw)
+    }
+
+    /// # Safety
+    /// The given pointer must come from [`Self::new`], and must not have been freed.
+    #[inline]
+    pub(crate) unsafe fn indexer<'a>(wrapper: NonNull<Self>) -> &'a I {
+        &(*wrapper.as_ptr()).indexer
+    }
+
+    /// # Safety
+    /// The given pointer must come from [`Self::new`], and must not have been freed.
+    #[inline]
+    pub(crate) unsafe fn indexer_mut<'a>(wrapper: NonNull<Self>) -> &'a mut I {
+        &mut (*wrapper.as_ptr()).indexer
+    }
+
+    /// # Safety
+    /// The given indexer pointer must be valid (but it can be null).
+    unsafe fn handle(
+        indexer: *const clap_preset_discovery_indexer,
+        handler: impl FnOnce(&mut I) -> Result<(), HostError>,
+    ) -> bool {
+        let Some(raw) = indexer.as_ref() else {
+            return false;
+        };
+
+        let Some(wrapper) = raw.indexer_data.cast::<Self>().as_mut() else {
+            return false;
+        };
+
+        match std::panic::catch_unwind(AssertUnwindSafe(|| handler(&mut wrapper.indexer))) {
+            Ok(result) => result.is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn declare_filetype(
+        indexer: *const clap_preset_discovery_indexer,
+        filetype: *const clap_preset_discovery_filetype,
+    ) -> bool {
+        let Some(file_type) = filetype.as_ref().and_then(|f| FileType::from_raw(f)) else {
+            return false;
+        };
+
+        Self::handle(indexer, |i| i.declare_filetype(file_type))
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn declare_location(
+        indexer: *const clap_preset_discovery_indexer,
+        location: *const clap_preset_discovery_location,
+    ) -> bool {
+        let Some(location) = location.as_ref().and_then(|l| LocationInfo::from_raw(l)) else {
+            return false;
+        };
+
+        Self::handle(indexer, |i| i.declare_location(location))
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn declare_soundpack(
+        indexer: *const clap_preset_discovery_indexer,
+        soundpack: *const clap_preset_discovery_soundpack,
+    ) -> bool {
+        let Some(soundpack) = soundpack.as_ref().and_then(|s| Soundpack::from_raw(s)) else {
+            return false;
+        };
+
+        Self::handle(indexer, |i| i.declare_soundpack(soundpack))
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_extension(
+        _indexer: *const clap_preset_discovery_indexer,
+        _extension_id: *const c_char,
+    ) -> *const c_void {
+        // There are no standard indexer extensions yet.
+        core::ptr::null()
+    }
+}
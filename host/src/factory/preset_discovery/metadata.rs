@@ -0,0 +1,411 @@
+use crate::factory::preset_discovery::{Flags, PluginId, Timestamp};
+use crate::host::HostError;
+use clack_common::utils::non_empty_cstr;
+use clap_sys::factory::draft::preset_discovery::{
+    clap_plugin_id, clap_preset_discovery_metadata_receiver, clap_timestamp,
+};
+use std::error::Error;
+use std::ffi::{c_char, CStr};
+use std::fmt::{Display, Formatter};
+use std::panic::AssertUnwindSafe;
+
+/// Implementation of a host's preset metadata receiver.
+///
+/// A metadata receiver is given to a [`Provider`](super::Provider) when requesting the metadata of
+/// a location. The provider then calls [`begin_preset`](MetadataReceiver::begin_preset) for each
+/// preset it finds in the location, followed by any number of calls to the other methods of this
+/// trait, which all apply to the preset that was last begun.
+///
+/// See [`PresetMetadataCollector`] for an implementation which collects all the received presets
+/// into owned [`PresetMetadata`] records.
+pub trait MetadataReceiver {
+    /// Receives an error reported by the provider, e.g. if a preset file could not be read.
+    ///
+    /// The `os_error` is the operating system error code, if applicable, or `0` otherwise.
+    fn on_error(&mut self, os_error: i32, error_message: Option<&CStr>);
+
+    /// Starts receiving the metadata of a new preset.
+    ///
+    /// The `name` is [`None`] if the location contains a single preset, in which case the host
+    /// should derive the name from the location itself (e.g. from the file name). The `load_key`
+    /// is only present if the location contains multiple presets.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error signals the provider that no more presets are wanted.
+    fn begin_preset(
+        &mut self,
+        name: Option<&CStr>,
+        load_key: Option<&CStr>,
+    ) -> Result<(), HostError>;
+
+    /// Adds the identifier of a plugin the current preset can be loaded into.
+    fn add_plugin_id(&mut self, plugin_id: PluginId);
+
+    /// Sets the identifier of the soundpack the current preset belongs to.
+    fn set_soundpack_id(&mut self, soundpack_id: &CStr);
+
+    /// Sets the flags of the current preset.
+    fn set_flags(&mut self, flags: Flags);
+
+    /// Adds a creator name to the current preset.
+    fn add_creator(&mut self, creator: &CStr);
+
+    /// Sets the description of the current preset.
+    fn set_description(&mut self, description: &CStr);
+
+    /// Sets the creation and last modification times of the current preset, if known.
+    fn set_timestamps(
+        &mut self,
+        creation_time: Option<Timestamp>,
+        modification_time: Option<Timestamp>,
+    );
+
+    /// Adds a feature to the current preset.
+    fn add_feature(&mut self, feature: &CStr);
+
+    /// Adds extra, free-form information to the current preset, as a key-value pair.
+    fn add_extra_info(&mut self, key: &CStr, value: &CStr);
+}
+
+/// The owned identifier of a plugin a preset can be loaded into.
+///
+/// This is the owned counterpart of [`PluginId`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PresetPluginId {
+    /// The plugin ABI the identifier refers to, e.g. `clap`, `vst3`, etc.
+    pub abi: String,
+    /// The identifier of the plugin, in the given ABI.
+    pub id: String,
+}
+
+/// The owned metadata of a single preset, as collected by a [`PresetMetadataCollector`].
+///
+/// All strings are converted lossily to UTF-8.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PresetMetadata {
+    /// The name of the preset, or [`None`] if it must be derived from its location.
+    pub name: Option<String>,
+    /// The key to give back to the plugin to load this preset, if its location contains multiple
+    /// presets.
+    pub load_key: Option<String>,
+    /// The identifiers of all the plugins this preset can be loaded into.
+    pub plugin_ids: Vec<PresetPluginId>,
+    /// The identifier of the soundpack this preset belongs to, if any.
+    pub soundpack_id: Option<String>,
+    /// The flags of this preset, or [`None`] if they are inherited from its location.
+    pub flags: Option<Flags>,
+    /// The names of the creators of this preset.
+    pub creators: Vec<String>,
+    /// The description of this preset, if any.
+    pub description: Option<String>,
+    /// The time this preset was created, if known.
+    pub creation_time: Option<Timestamp>,
+    /// The time this preset was last modified, if known.
+    pub modification_time: Option<Timestamp>,
+    /// The features of this preset.
+    pub features: Vec<String>,
+    /// Extra, free-form information about this preset, as key-value pairs.
+    pub extra_info: Vec<(String, String)>,
+}
+
+/// An error reported by a preset provider through [`MetadataReceiver::on_error`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MetadataError {
+    /// The operating system error code, or `0` if not applicable.
+    pub os_error: i32,
+    /// The error message given by the provider.
+    pub message: String,
+}
+
+impl Display for MetadataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.os_error == 0 {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{} (OS error {})", self.message, self.os_error)
+        }
+    }
+}
+
+impl Error for MetadataError {}
+
+/// A [`MetadataReceiver`] implementation which collects all the received presets into owned
+/// [`PresetMetadata`] records.
+///
+/// The same collector can be given to multiple metadata requests, in which case the presets of
+/// all the requested locations are accumulated.
+#[derive(Debug, Clone, Default)]
+pub struct PresetMetadataCollector {
+    presets: Vec<PresetMetadata>,
+    errors: Vec<MetadataError>,
+}
+
+impl PresetMetadataCollector {
+    /// Creates a new, empty collector.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all the presets that have been collected so far.
+    #[inline]
+    pub fn presets(&self) -> &[PresetMetadata] {
+        &self.presets
+    }
+
+    /// Returns all the errors that have been reported by the provider so far.
+    #[inline]
+    pub fn errors(&self) -> &[MetadataError] {
+        &self.errors
+    }
+
+    /// Consumes this collector, returning all the collected presets.
+    #[inline]
+    pub fn into_presets(self) -> Vec<PresetMetadata> {
+        self.presets
+    }
+
+    #[inline]
+    fn current(&mut self) -> Option<&mut PresetMetadata> {
+        self.presets.last_mut()
+    }
+}
+
+fn to_owned_string(string: &CStr) -> String {
+    string.to_string_lossy().into_owned()
+}
+
+impl MetadataReceiver for PresetMetadataCollector {
+    fn on_error(&mut self, os_error: i32, error_message: Option<&CStr>) {
+        self.errors.push(MetadataError {
+            os_error,
+            message: error_message.map(to_owned_string).unwrap_or_default(),
+        })
+    }
+
+    fn begin_preset(
+        &mut self,
+        name: Option<&CStr>,
+        load_key: Option<&CStr>,
+    ) -> Result<(), HostError> {
+        self.presets.push(PresetMetadata {
+            name: name.map(to_owned_string),
+            load_key: load_key.map(to_owned_string),
+            ..PresetMetadata::default()
+        });
+
+        Ok(())
+    }
+
+    fn add_plugin_id(&mut self, plugin_id: PluginId) {
+        if let Some(preset) = self.current() {
+            preset.plugin_ids.push(PresetPluginId {
+                abi: to_owned_string(plugin_id.abi),
+                id: to_owned_string(plugin_id.id),
+            });
+        }
+    }
+
+    fn set_soundpack_id(&mut self, soundpack_id: &CStr) {
+        if let Some(preset) = self.current() {
+            preset.soundpack_id = Some(to_owned_string(soundpack_id));
+        }
+    }
+
+    fn set_flags(&mut self, flags: Flags) {
+        if let Some(preset) = self.current() {
+            preset.flags = Some(flags);
+        }
+    }
+
+    fn add_creator(&mut self, creator: &CStr) {
+        if let Some(preset) = self.current() {
+            preset.creators.push(to_owned_string(creator));
+        }
+    }
+
+    fn set_description(&mut self, description: &CStr) {
+        if let Some(preset) = self.current() {
+            preset.description = Some(to_owned_string(description));
+        }
+    }
+
+    fn set_timestamps(
+        &mut self,
+        creation_time: Option<Timestamp>,
+        modification_time: Option<Timestamp>,
+    ) {
+        if let Some(preset) = self.current() {
+            preset.creation_time = creation_time;
+            preset.modification_time = modification_time;
+        }
+    }
+
+    fn add_feature(&mut self, feature: &CStr) {
+        if let Some(preset) = self.current() {
+            preset.features.push(to_owned_string(feature));
+        }
+    }
+
+    fn add_extra_info(&mut self, key: &CStr, value: &CStr) {
+        if let Some(preset) = self.current() {
+            preset
+                .extra_info
+                .push((to_owned_string(key), to_owned_string(value)));
+        }
+    }
+}
+
+/// Creates a raw, C-FFI compatible metadata receiver from a given receiver implementation.
+///
+/// The returned struct points to the given receiver, and must not outlive it.
+pub(crate) fn raw_receiver<R: MetadataReceiver>(
+    receiver: &mut R,
+) -> clap_preset_discovery_metadata_receiver {
+    clap_preset_discovery_metadata_receiver {
+        receiver_data: (receiver as *mut R).cast(),
+        on_error: Some(on_error::<R>),
+        begin_preset: Some(begin_preset::<R>),
+        add_plugin_id: Some(add_plugin_id::<R>),
+        set_soundpack_id: Some(set_soundpack_id::<R>),
+        set_flags: Some(set_flags::<R>),
+        add_creator: Some(add_creator::<R>),
+        set_description: Some(set_description::<R>),
+        set_timestamps: Some(set_timestamps::<R>),
+        add_feature: Some(add_feature::<R>),
+        add_extra_info: Some(add_extra_info::<R>),
+    }
+}
+
+/// # Safety
+/// The given receiver pointer must have been created by [`raw_receiver`], and still be valid.
+unsafe fn handle<R: MetadataReceiver, T>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    handler: impl FnOnce(&mut R) -> Option<T>,
+) -> Option<T> {
+    let receiver = receiver.as_ref()?.receiver_data.cast::<R>().as_mut()?;
+
+    std::panic::catch_unwind(AssertUnwindSafe(|| handler(receiver)))
+        .ok()
+        .flatten()
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn on_error<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    os_error: i32,
+    error_message: *const c_char,
+) {
+    handle(receiver, |r: &mut R| {
+        r.on_error(os_error, non_empty_cstr(error_message));
+        Some(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn begin_preset<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    name: *const c_char,
+    load_key: *const c_char,
+) -> bool {
+    handle(receiver, |r: &mut R| {
+        let name = non_empty_cstr(name);
+        let load_key = non_empty_cstr(load_key);
+        Some(r.begin_preset(name, load_key).is_ok())
+    })
+    .unwrap_or(false)
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn add_plugin_id<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    plugin_id: *const clap_plugin_id,
+) {
+    handle(receiver, |r: &mut R| {
+        r.add_plugin_id(PluginId::from_raw(plugin_id.as_ref()?)?);
+        Some(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn set_soundpack_id<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    soundpack_id: *const c_char,
+) {
+    handle(receiver, |r: &mut R| {
+        r.set_soundpack_id(non_empty_cstr(soundpack_id)?);
+        Some(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn set_flags<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    flags: u32,
+) {
+    handle(receiver, |r: &mut R| {
+        r.set_flags(Flags::from_bits_truncate(flags));
+        Some(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn add_creator<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    creator: *const c_char,
+) {
+    handle(receiver, |r: &mut R| {
+        r.add_creator(non_empty_cstr(creator)?);
+        Some(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn set_description<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    description: *const c_char,
+) {
+    handle(receiver, |r: &mut R| {
+        r.set_description(non_empty_cstr(description)?);
+        Some(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn set_timestamps<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    creation_time: clap_timestamp,
+    modification_time: clap_timestamp,
+) {
+    handle(receiver, |r: &mut R| {
+        r.set_timestamps(
+            Timestamp::from_raw(creation_time),
+            Timestamp::from_raw(modification_time),
+        );
+        Some(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn add_feature<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    feature: *const c_char,
+) {
+    handle(receiver, |r: &mut R| {
+        r.add_feature(non_empty_cstr(feature)?);
+        Some(())
+    });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn add_extra_info<R: MetadataReceiver>(
+    receiver: *const clap_preset_discovery_metadata_receiver,
+    key: *const c_char,
+    value: *const c_char,
+) {
+    handle(receiver, |r: &mut R| {
+        r.add_extra_info(non_empty_cstr(key)?, non_empty_cstr(value)?);
+        Some(())
+    });
+}
@@ -0,0 +1,146 @@
+use crate::factory::preset_discovery::indexer::IndexerWrapper;
+use crate::factory::preset_discovery::metadata::raw_receiver;
+use crate::factory::preset_discovery::{
+    Location, MetadataReceiver, PresetIndexer, ProviderDescriptor, ProviderInstanceError,
+};
+use crate::host::HostInfo;
+use clap_sys::factory::draft::preset_discovery::{
+    clap_preset_discovery_factory, clap_preset_discovery_provider,
+};
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+/// An initialized preset provider instance.
+///
+/// Providers are created by [`PresetDiscoveryFactory::create_provider`](super::PresetDiscoveryFactory::create_provider).
+/// Once created, the [`PresetIndexer`] holds all the file types, locations and soundpacks the
+/// provider declared, and the metadata of the presets in these locations can be requested using
+/// [`get_metadata`](Provider::get_metadata).
+///
+/// The provider is destroyed when this type is dropped.
+pub struct Provider<'a, I: PresetIndexer> {
+    raw: NonNull<clap_preset_discovery_provider>,
+    indexer: NonNull<IndexerWrapper<I>>,
+    _lifetime: PhantomData<&'a clap_preset_discovery_factory>,
+}
+
+impl<'a, I: PresetIndexer> Provider<'a, I> {
+    /// # Safety
+    /// The given factory pointer must be valid for 'a.
+    pub(crate) unsafe fn new(
+        factory: *const clap_preset_discovery_factory,
+        provider_id: &CStr,
+        indexer: I,
+        host_info: &HostInfo,
+    ) -> Result<Self, ProviderInstanceError> {
+        let create = (*factory)
+            .create
+            .ok_or(ProviderInstanceError::NullFactoryCreateFunction)?;
+
+        let indexer = IndexerWrapper::new(indexer, host_info);
+
+        let raw = create(
+            factory,
+            IndexerWrapper::as_raw(indexer),
+            provider_id.as_ptr(),
+        );
+
+        let Some(raw) = NonNull::new(raw as *mut clap_preset_discovery_provider) else {
+            IndexerWrapper::free(indexer);
+            return Err(ProviderInstanceError::ProviderNotFound);
+        };
+
+        // Constructing this now ensures the provider is destroyed if its initialization fails.
+        let provider = Self {
+            raw,
+            indexer,
+            _lifetime: PhantomData,
+        };
+
+        match provider.raw().init {
+            Some(init) if init(raw.as_ptr()) => Ok(provider),
+            _ => Err(ProviderInstanceError::InitializationFailed),
+        }
+    }
+
+    #[inline]
+    fn raw(&self) -> &clap_preset_discovery_provider {
+        // SAFETY: this type ensures the provider pointer is valid until it is dropped.
+        unsafe { self.raw.as_ref() }
+    }
+
+    /// Returns the descriptor of this provider, if the provider exposes it.
+    #[inline]
+    pub fn descriptor(&self) -> Option<ProviderDescriptor<'_>> {
+        // SAFETY: the descriptor pointer is guaranteed to be valid by the spec, if not null.
+        unsafe {
+            self.raw()
+                .desc
+                .as_ref()
+                .map(|d| ProviderDescriptor::from_raw(d))
+        }
+    }
+
+    /// Returns a shared reference to the indexer of this provider.
+    #[inline]
+    pub fn indexer(&self) -> &I {
+        // SAFETY: the indexer is valid until this provider is dropped.
+        unsafe { IndexerWrapper::indexer(self.indexer) }
+    }
+
+    /// Returns a mutable reference to the indexer of this provider.
+    #[inline]
+    pub fn indexer_mut(&mut self) -> &mut I {
+        // SAFETY: the indexer is valid until this provider is dropped.
+        unsafe { IndexerWrapper::indexer_mut(self.indexer) }
+    }
+
+    /// Requests the metadata of all the presets at the given location, which are then sent to
+    /// the given `receiver`.
+    ///
+    /// The location should be one that was declared by the provider to its [`PresetIndexer`],
+    /// or a file within a declared directory.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`ProviderInstanceError::GetMetadataFailed`] if the provider failed to read
+    /// the location. More details may have been reported through [`MetadataReceiver::on_error`].
+    pub fn get_metadata<R: MetadataReceiver>(
+        &mut self,
+        location: Location,
+        receiver: &mut R,
+    ) -> Result<(), ProviderInstanceError> {
+        let get_metadata = self
+            .raw()
+            .get_metadata
+            .ok_or(ProviderInstanceError::GetMetadataFailed)?;
+
+        let (location_kind, location) = location.to_raw();
+        let raw_receiver = raw_receiver(receiver);
+
+        // SAFETY: this type ensures the function pointer is valid. The receiver and location
+        // pointers are valid for the duration of the call.
+        let success =
+            unsafe { get_metadata(self.raw.as_ptr(), location_kind, location, &raw_receiver) };
+
+        match success {
+            true => Ok(()),
+            false => Err(ProviderInstanceError::GetMetadataFailed),
+        }
+    }
+}
+
+impl<I: PresetIndexer> Drop for Provider<'_, I> {
+    fn drop(&mut self) {
+        // SAFETY: this type ensures the provider pointer is valid, and it is never used again.
+        // The indexer is only freed once the provider has been destroyed.
+        unsafe {
+            if let Some(destroy) = self.raw().destroy {
+                destroy(self.raw.as_ptr());
+            }
+
+            IndexerWrapper::free(self.indexer);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use clap_sys::factory::draft::preset_discovery::clap_preset_discovery_indexer;
 use clap_sys::host::clap_host;
 use std::ffi::{CStr, CString, NulError};
 use std::pin::Pin;
@@ -98,4 +99,11 @@ impl HostInfo {
         host.url = self.inner.url.as_ptr();
         host.version = self.inner.version.as_ptr();
     }
+
+    pub(crate) fn write_to_raw_indexer(&self, indexer: &mut clap_preset_discovery_indexer) {
+        indexer.name = self.inner.name.as_ptr();
+        indexer.vendor = self.inner.vendor.as_ptr();
+        indexer.url = self.inner.url.as_ptr();
+        indexer.version = self.inner.version.as_ptr();
+    }
 }
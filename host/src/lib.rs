@@ -266,6 +266,7 @@ pub mod factory;
 pub mod host;
 pub mod plugin;
 pub mod process;
+pub mod scan;
 mod util;
 
 pub use clack_common::events;
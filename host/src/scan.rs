@@ -0,0 +1,393 @@
+#![deny(missing_docs)]
+
+//! Discovery of the CLAP plugin bundles installed on the system.
+//!
+//! CLAP bundles are files (or, on macOS, bundle directories) with a `.clap` extension, which are
+//! installed in a set of standard directories defined by the CLAP specification, plus any
+//! directory listed in the `CLAP_PATH` environment variable. [`standard_search_paths`] returns all
+//! of those for the current platform, and [`find_bundles`] recursively lists the bundles they
+//! contain.
+//!
+//! The [`scan`] and [`scan_paths`] functions then load all the found bundles, and gather
+//! information about all the plugins they contain into a [`PluginCatalogue`]. The catalogue is
+//! fully owned: no bundle is kept loaded once the scan is complete.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use clack_host::scan;
+//!
+//! // SAFETY: loading bundles is inherently unsafe, see PluginBundle::load.
+//! let catalogue = unsafe { scan::scan() };
+//!
+//! for (bundle_path, plugin) in catalogue.plugins() {
+//!     println!("{} ({}) in {}", plugin.name.as_deref().unwrap_or("?"), plugin.id, bundle_path.display());
+//! }
+//!
+//! for (bundle_path, error) in catalogue.errors() {
+//!     eprintln!("Failed to scan {}: {error}", bundle_path.display());
+//! }
+//! ```
+
+use crate::factory::PluginDescriptor;
+use std::collections::HashSet;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+
+use crate::bundle::{PluginBundle, PluginBundleError};
+
+/// Returns all the directories CLAP bundles should be searched in, for the current platform.
+///
+/// This contains the standard search paths defined by the CLAP specification, followed by all the
+/// paths listed in the `CLAP_PATH` environment variable, if it is set. Directories that do not
+/// exist are not filtered out.
+pub fn standard_search_paths() -> Vec<PathBuf> {
+    let mut paths = vec![];
+
+    #[cfg(not(windows))]
+    if let Some(home_dir) = std::env::var_os("HOME") {
+        let home_dir = PathBuf::from(home_dir);
+
+        #[cfg(target_os = "macos")]
+        paths.push(home_dir.join("Library/Audio/Plug-Ins/CLAP"));
+
+        #[cfg(not(target_os = "macos"))]
+        paths.push(home_dir.join(".clap"));
+    }
+
+    #[cfg(target_os = "macos")]
+    paths.push(PathBuf::from("/Library/Audio/Plug-Ins/CLAP"));
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    paths.push(PathBuf::from("/usr/lib/clap"));
+
+    #[cfg(windows)]
+    {
+        if let Some(common_files) = std::env::var_os("CommonProgramFiles") {
+            paths.push(PathBuf::from(common_files).join("CLAP"));
+        }
+
+        if let Some(local_app_data) = std::env::var_os("LOCALAPPDATA") {
+            paths.push(PathBuf::from(local_app_data).join("Programs\\Common\\CLAP"));
+        }
+    }
+
+    if let Some(clap_path) = std::env::var_os("CLAP_PATH") {
+        paths.extend(std::env::split_paths(&clap_path).filter(|p| !p.as_os_str().is_empty()));
+    }
+
+    paths
+}
+
+/// Recursively searches the given directories, and returns the paths of all the files that could
+/// be CLAP bundles, i.e. files with a `.clap` extension. On macOS, directories with a `.clap`
+/// extension are also considered to be bundles.
+///
+/// Symbolic links are followed, but each directory is only searched once, and each bundle is only
+/// returned once, even if it can be reached through multiple paths (e.g. through symbolic links,
+/// or if it is both in a standard search path and in `CLAP_PATH`). Directories that do not exist
+/// or cannot be read are skipped.
+pub fn find_bundles(search_paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut search = BundleSearch::default();
+
+    for path in search_paths {
+        search.search_directory(path);
+    }
+
+    search.bundles
+}
+
+#[derive(Default)]
+struct BundleSearch {
+    visited_directories: HashSet<PathBuf>,
+    found_bundles: HashSet<PathBuf>,
+    bundles: Vec<PathBuf>,
+}
+
+impl BundleSearch {
+    fn search_directory(&mut self, directory: &Path) {
+        // Canonical paths have all symbolic links resolved, which allows to detect both symbolic
+        // link loops and directories reachable through multiple paths.
+        let Ok(canonical_directory) = directory.canonicalize() else {
+            return;
+        };
+
+        if !self.visited_directories.insert(canonical_directory) {
+            return;
+        }
+
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            return;
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+
+            // This follows symbolic links.
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+
+            if is_clap_bundle(&path, &metadata) {
+                self.add_bundle(path);
+            } else if metadata.is_dir() {
+                self.search_directory(&path);
+            }
+        }
+    }
+
+    fn add_bundle(&mut self, path: PathBuf) {
+        let canonical_path = path.canonicalize().unwrap_or_else(|_| path.clone());
+
+        if self.found_bundles.insert(canonical_path) {
+            self.bundles.push(path);
+        }
+    }
+}
+
+#[inline]
+fn is_clap_bundle(path: &Path, metadata: &Metadata) -> bool {
+    if !path.extension().is_some_and(|ext| ext == "clap") {
+        return false;
+    }
+
+    metadata.is_file() || (cfg!(target_os = "macos") && metadata.is_dir())
+}
+
+/// Scans all the CLAP bundles in the [standard search paths](standard_search_paths).
+///
+/// This is a shorthand for calling [`scan_paths`] with the result of [`standard_search_paths`].
+///
+/// # Safety
+///
+/// This function loads all the bundles it finds, which is inherently unsafe. See the Safety
+/// section of [`PluginBundle::load`] for more information.
+#[cfg(feature = "libloading")]
+pub unsafe fn scan() -> PluginCatalogue {
+    scan_paths(&standard_search_paths())
+}
+
+/// Recursively searches all the given directories for CLAP bundles (using [`find_bundles`]), and
+/// loads all of them to list the plugins they contain.
+///
+/// Bundles that fail to load are still included in the resulting catalogue, alongside the error
+/// that occurred.
+///
+/// # Safety
+///
+/// This function loads all the bundles it finds, which is inherently unsafe. See the Safety
+/// section of [`PluginBundle::load`] for more information.
+#[cfg(feature = "libloading")]
+pub unsafe fn scan_paths(search_paths: &[PathBuf]) -> PluginCatalogue {
+    let bundles = find_bundles(search_paths)
+        .into_iter()
+        .map(|path| scan_bundle(&path))
+        .collect();
+
+    PluginCatalogue { bundles }
+}
+
+/// Loads the CLAP bundle at the given path, and lists the plugins it contains.
+///
+/// # Safety
+///
+/// This function loads the given bundle, which is inherently unsafe. See the Safety section of
+/// [`PluginBundle::load`] for more information.
+#[cfg(feature = "libloading")]
+pub unsafe fn scan_bundle(path: &Path) -> ScannedBundle {
+    ScannedBundle {
+        path: path.to_path_buf(),
+        plugins: load_bundle(path)
+            .map_err(ScanError::LoadError)
+            .and_then(|bundle| PluginInfo::list_from_bundle(&bundle)),
+    }
+}
+
+/// Loads the CLAP bundle at the given path.
+///
+/// This behaves like [`PluginBundle::load`], except it also supports bundle directories on macOS,
+/// in which case the bundle's executable is loaded.
+///
+/// # Safety
+///
+/// This function loads the given bundle, which is inherently unsafe. See the Safety section of
+/// [`PluginBundle::load`] for more information.
+#[cfg(feature = "libloading")]
+pub unsafe fn load_bundle(path: &Path) -> Result<PluginBundle, PluginBundleError> {
+    #[cfg(target_os = "macos")]
+    if path.is_dir() {
+        // SAFETY: there is a null byte in this string.
+        const SYMBOL_NAME: &CStr = unsafe { CStr::from_bytes_with_nul_unchecked(b"clap_entry\0") };
+
+        // The executable of a macOS bundle is in its Contents/MacOS directory, and is named after
+        // the bundle itself.
+        let executable = path
+            .join("Contents/MacOS")
+            .join(path.file_stem().unwrap_or_default());
+
+        let library =
+            libloading::Library::new(executable).map_err(PluginBundleError::LibraryLoadingError)?;
+
+        return PluginBundle::load_from_symbol_in_library(path, library, SYMBOL_NAME);
+    }
+
+    PluginBundle::load(path)
+}
+
+/// The result of a scan: all the bundles that were found, alongside the plugins they contain.
+#[derive(Debug, Default)]
+pub struct PluginCatalogue {
+    /// All the bundles that were found, in the order they were found.
+    pub bundles: Vec<ScannedBundle>,
+}
+
+impl PluginCatalogue {
+    /// Returns an iterator over all the plugins in this catalogue, alongside the path of the
+    /// bundle that contains them.
+    pub fn plugins(&self) -> impl Iterator<Item = (&Path, &PluginInfo)> {
+        self.bundles.iter().flat_map(|bundle| {
+            bundle
+                .plugins()
+                .iter()
+                .map(move |plugin| (bundle.path.as_path(), plugin))
+        })
+    }
+
+    /// Returns an iterator over all the bundles that failed to be scanned, alongside the error
+    /// that occurred.
+    pub fn errors(&self) -> impl Iterator<Item = (&Path, &ScanError)> {
+        self.bundles
+            .iter()
+            .filter_map(|bundle| Some((bundle.path.as_path(), bundle.plugins.as_ref().err()?)))
+    }
+
+    /// Finds the first plugin matching the given ID in this catalogue, alongside the path of the
+    /// bundle that contains it.
+    pub fn find_plugin(&self, id: &str) -> Option<(&Path, &PluginInfo)> {
+        self.plugins().find(|(_, plugin)| plugin.id == id)
+    }
+}
+
+/// A bundle that was found during a scan.
+#[derive(Debug)]
+pub struct ScannedBundle {
+    /// The path of the bundle's file, or of its directory for macOS bundles.
+    pub path: PathBuf,
+    /// The plugins that this bundle contains, or the error that occurred while scanning it.
+    pub plugins: Result<Vec<PluginInfo>, ScanError>,
+}
+
+impl ScannedBundle {
+    /// Returns the plugins that this bundle contains.
+    ///
+    /// This returns an empty slice if the bundle failed to be scanned.
+    #[inline]
+    pub fn plugins(&self) -> &[PluginInfo] {
+        self.plugins.as_deref().unwrap_or(&[])
+    }
+}
+
+/// Information about a plugin found during a scan.
+///
+/// This is an owned version of a [`PluginDescriptor`], where all the strings are converted to
+/// UTF-8 [`String`]s, and empty strings are converted to [`None`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PluginInfo {
+    /// The unique ID of the plugin.
+    pub id: String,
+    /// The user-facing display name of the plugin.
+    pub name: Option<String>,
+    /// The vendor of the plugin.
+    pub vendor: Option<String>,
+    /// The URL of the plugin's homepage.
+    pub url: Option<String>,
+    /// The URL of the plugin's user manual.
+    pub manual_url: Option<String>,
+    /// The URL of the plugin's support page.
+    pub support_url: Option<String>,
+    /// The version of the plugin.
+    pub version: Option<String>,
+    /// A short description of the plugin.
+    pub description: Option<String>,
+    /// The features of the plugin, e.g. `instrument`, `audio-effect`, `stereo`, etc.
+    pub features: Vec<String>,
+}
+
+impl PluginInfo {
+    /// Copies all the information of the given plugin descriptor.
+    ///
+    /// Strings that are not valid UTF-8 are converted lossily. This returns [`None`] if the
+    /// descriptor has no ID.
+    pub fn from_descriptor(descriptor: &PluginDescriptor) -> Option<Self> {
+        Some(Self {
+            id: to_string(descriptor.id())?,
+            name: to_string(descriptor.name()),
+            vendor: to_string(descriptor.vendor()),
+            url: to_string(descriptor.url()),
+            manual_url: to_string(descriptor.manual_url()),
+            support_url: to_string(descriptor.support_url()),
+            version: to_string(descriptor.version()),
+            description: to_string(descriptor.description()),
+            features: descriptor
+                .features()
+                .filter_map(|f| to_string(Some(f)))
+                .collect(),
+        })
+    }
+
+    /// Lists all the plugins exposed by the given bundle's plugin factory.
+    ///
+    /// Plugins that have no ID are skipped.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`ScanError::MissingPluginFactory`] if the bundle does not expose a plugin
+    /// factory.
+    pub fn list_from_bundle(bundle: &PluginBundle) -> Result<Vec<Self>, ScanError> {
+        let factory = bundle
+            .get_plugin_factory()
+            .ok_or(ScanError::MissingPluginFactory)?;
+
+        Ok(factory
+            .plugin_descriptors()
+            .filter_map(|d| Self::from_descriptor(&d))
+            .collect())
+    }
+}
+
+#[inline]
+fn to_string(string: Option<&CStr>) -> Option<String> {
+    string
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string_lossy().into_owned())
+}
+
+/// Errors that can occur while scanning a bundle.
+#[derive(Debug)]
+pub enum ScanError {
+    /// The bundle could not be loaded.
+    LoadError(PluginBundleError),
+    /// The bundle does not expose a plugin factory.
+    MissingPluginFactory,
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::LoadError(e) => write!(f, "Failed to load plugin bundle: {e}"),
+            ScanError::MissingPluginFactory => f.write_str("Bundle has no plugin factory"),
+        }
+    }
+}
+
+impl Error for ScanError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScanError::LoadError(e) => Some(e),
+            ScanError::MissingPluginFactory => None,
+        }
+    }
+}
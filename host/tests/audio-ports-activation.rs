@@ -0,0 +1,209 @@
+use clack_extensions::audio_ports_activation::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+const PORT_COUNT: u32 = 2;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Activation {
+    from_audio_thread: bool,
+    is_input: bool,
+    port_index: u32,
+    is_active: bool,
+    sample_size: SampleSize,
+}
+
+static ACTIVATIONS: Mutex<Vec<Activation>> = Mutex::new(Vec::new());
+
+fn record_activation(
+    from_audio_thread: bool,
+    is_input: bool,
+    port_index: u32,
+    is_active: bool,
+    sample_size: SampleSize,
+) -> Result<(), PluginError> {
+    if port_index >= PORT_COUNT {
+        return Err(PluginError::Message("Unknown port"));
+    }
+
+    ACTIVATIONS.lock().unwrap().push(Activation {
+        from_audio_thread,
+        is_input,
+        port_index,
+        is_active,
+        sample_size,
+    });
+
+    Ok(())
+}
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = MyAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginAudioPortsActivation>();
+    }
+}
+
+struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginAudioPortsActivationImpl for MyPluginMainThread {
+    fn can_activate_while_processing(&mut self) -> bool {
+        true
+    }
+
+    fn set_active(
+        &mut self,
+        is_input: bool,
+        port_index: u32,
+        is_active: bool,
+        sample_size: SampleSize,
+    ) -> Result<(), PluginError> {
+        record_activation(false, is_input, port_index, is_active, sample_size)
+    }
+}
+
+struct MyAudioProcessor;
+
+impl<'a> PluginAudioProcessor<'a, (), MyPluginMainThread> for MyAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut MyPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        Ok(ProcessStatus::Sleep)
+    }
+}
+
+impl PluginAudioPortsActivationAudioProcessorImpl for MyAudioProcessor {
+    fn set_active(
+        &mut self,
+        is_input: bool,
+        port_index: u32,
+        is_active: bool,
+        sample_size: SampleSize,
+    ) -> Result<(), PluginError> {
+        record_activation(true, is_input, port_index, is_active, sample_size)
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn host_activates_ports_from_both_threads() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let activation = plugin
+        .get_extension::<PluginAudioPortsActivation>()
+        .unwrap();
+
+    assert!(activation.can_activate_while_processing(&mut plugin));
+    activation
+        .set_active(&mut plugin, true, 1, false, SampleSize::Unspecified)
+        .unwrap();
+    assert_eq!(
+        activation.set_active(&mut plugin, true, PORT_COUNT, false, SampleSize::Float32),
+        Err(AudioPortActivationError)
+    );
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 5,
+        max_frames_count: 5,
+    };
+
+    let processor = instance.activate(|_, _| (), config).unwrap();
+    let mut processor = processor.start_processing().unwrap();
+
+    let mut plugin = processor.plugin_handle();
+    activation
+        .set_active_while_processing(&mut plugin, true, 1, true, SampleSize::Float64)
+        .unwrap();
+    assert_eq!(
+        activation.set_active_while_processing(
+            &mut plugin,
+            false,
+            PORT_COUNT,
+            true,
+            SampleSize::Float32
+        ),
+        Err(AudioPortActivationError)
+    );
+
+    instance.deactivate(processor.stop_processing());
+
+    assert_eq!(
+        *ACTIVATIONS.lock().unwrap(),
+        [
+            Activation {
+                from_audio_thread: false,
+                is_input: true,
+                port_index: 1,
+                is_active: false,
+                sample_size: SampleSize::Unspecified,
+            },
+            Activation {
+                from_audio_thread: true,
+                is_input: true,
+                port_index: 1,
+                is_active: true,
+                sample_size: SampleSize::Float64,
+            },
+        ]
+    );
+}
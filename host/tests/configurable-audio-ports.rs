@@ -0,0 +1,163 @@
+use clack_extensions::ambisonic::*;
+use clack_extensions::audio_ports::AudioPortType;
+use clack_extensions::configurable_audio_ports::*;
+use clack_extensions::surround::{SurroundChannel, SurroundChannelMap};
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+const SURROUND_5_1: [SurroundChannel; 6] = [
+    SurroundChannel::FrontLeft,
+    SurroundChannel::FrontRight,
+    SurroundChannel::FrontCenter,
+    SurroundChannel::LowFrequency,
+    SurroundChannel::SideLeft,
+    SurroundChannel::SideRight,
+];
+
+fn expected_requests() -> [AudioPortConfigurationRequest<'static>; 3] {
+    [
+        AudioPortConfigurationRequest {
+            is_input: true,
+            port_index: 0,
+            channel_count: 1,
+            port_type: Some(AudioPortType::MONO),
+            details: AudioPortRequestDetails::None,
+        },
+        AudioPortConfigurationRequest {
+            is_input: false,
+            port_index: 0,
+            channel_count: 6,
+            port_type: Some(AudioPortType::SURROUND),
+            details: AudioPortRequestDetails::Surround(SurroundChannelMap::new(&SURROUND_5_1)),
+        },
+        AudioPortConfigurationRequest {
+            is_input: false,
+            port_index: 1,
+            channel_count: 4,
+            port_type: Some(AudioPortType::AMBISONIC),
+            details: AudioPortRequestDetails::Ambisonic(AmbisonicConfig {
+                ordering: AmbisonicOrdering::Acn,
+                normalization: AmbisonicNormalization::Sn3d,
+            }),
+        },
+    ]
+}
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginConfigurableAudioPorts>();
+    }
+}
+
+struct MyPluginMainThread {
+    applied_request_count: usize,
+}
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginConfigurableAudioPortsImpl for MyPluginMainThread {
+    fn can_apply_configuration(&mut self, requests: &AudioPortConfigurationRequests) -> bool {
+        requests.iter().eq(expected_requests())
+    }
+
+    fn apply_configuration(
+        &mut self,
+        requests: &AudioPortConfigurationRequests,
+    ) -> Result<(), PluginError> {
+        if !self.can_apply_configuration(requests) {
+            return Err(PluginError::Message("Unsupported configuration"));
+        }
+
+        assert_eq!(self.applied_request_count, 0);
+        self.applied_request_count = requests.len();
+        Ok(())
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread {
+            applied_request_count: 0,
+        })
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+fn instantiate() -> PluginInstance<()> {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    PluginInstance::<()>::new(
+        |_| (),
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap()
+}
+
+#[test]
+fn requests_round_trip_through_ffi() {
+    let mut instance = instantiate();
+    let mut plugin = instance.plugin_handle();
+
+    let ext = plugin
+        .get_extension::<PluginConfigurableAudioPorts>()
+        .unwrap();
+
+    let requests = expected_requests();
+    assert!(ext.can_apply_configuration(&mut plugin, &requests));
+    assert!(ext.apply_configuration(&mut plugin, &requests).is_ok());
+
+    let mut different_requests = expected_requests();
+    different_requests[2].details = AudioPortRequestDetails::Ambisonic(AmbisonicConfig {
+        ordering: AmbisonicOrdering::FuMa,
+        normalization: AmbisonicNormalization::N3d,
+    });
+
+    assert!(!ext.can_apply_configuration(&mut plugin, &different_requests));
+    assert_eq!(
+        ext.apply_configuration(&mut plugin, &different_requests),
+        Err(AudioPortConfigApplyError)
+    );
+}
+
+#[test]
+fn rejects_mismatched_surround_channel_maps() {
+    let mut instance = instantiate();
+    let mut plugin = instance.plugin_handle();
+
+    let ext = plugin
+        .get_extension::<PluginConfigurableAudioPorts>()
+        .unwrap();
+
+    let mut requests = expected_requests();
+    requests[1].channel_count = 8;
+
+    assert!(!ext.can_apply_configuration(&mut plugin, &requests));
+    assert_eq!(
+        ext.apply_configuration(&mut plugin, &requests),
+        Err(AudioPortConfigApplyError)
+    );
+}
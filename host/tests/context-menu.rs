@@ -0,0 +1,365 @@
+use clack_extensions::context_menu::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::{CStr, CString};
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+enum CollectedItem {
+    Entry(CString, bool, ClapId),
+    CheckEntry(CString, bool, bool, ClapId),
+    Separator,
+    BeginSubmenu(CString, bool),
+    EndSubmenu,
+    Title(CString, bool),
+}
+
+struct MenuCollector {
+    items: Vec<CollectedItem>,
+    supports_titles: bool,
+}
+
+impl MenuCollector {
+    fn new(supports_titles: bool) -> Self {
+        Self {
+            items: Vec::new(),
+            supports_titles,
+        }
+    }
+}
+
+impl ContextMenuBuilderImpl for MenuCollector {
+    fn add_item(&mut self, item: ContextMenuItem) -> Result<(), ContextMenuError> {
+        if !self.supports(item.kind()) {
+            return Err(ContextMenuError::AddItemError);
+        }
+
+        self.items.push(match item {
+            ContextMenuItem::Entry {
+                label,
+                is_enabled,
+                action_id,
+            } => CollectedItem::Entry(label.into(), is_enabled, action_id),
+            ContextMenuItem::CheckEntry {
+                label,
+                is_enabled,
+                is_checked,
+                action_id,
+            } => CollectedItem::CheckEntry(label.into(), is_enabled, is_checked, action_id),
+            ContextMenuItem::Separator => CollectedItem::Separator,
+            ContextMenuItem::BeginSubmenu { label, is_enabled } => {
+                CollectedItem::BeginSubmenu(label.into(), is_enabled)
+            }
+            ContextMenuItem::EndSubmenu => CollectedItem::EndSubmenu,
+            ContextMenuItem::Title { title, is_enabled } => {
+                CollectedItem::Title(title.into(), is_enabled)
+            }
+        });
+
+        Ok(())
+    }
+
+    fn supports(&self, kind: ContextMenuItemKind) -> bool {
+        kind != ContextMenuItemKind::Title || self.supports_titles
+    }
+}
+
+fn label(bytes: &[u8]) -> &CStr {
+    CStr::from_bytes_with_nul(bytes).unwrap()
+}
+
+const RESET_ACTION: ClapId = ClapId::new(0);
+const MODE_A_ACTION: ClapId = ClapId::new(1);
+const MODE_B_ACTION: ClapId = ClapId::new(2);
+const RANDOMIZE_ACTION: ClapId = ClapId::new(10);
+const HOST_ACTION: ClapId = ClapId::new(42);
+const GAIN_PARAM: ClapId = ClapId::new(5);
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginContextMenu>();
+    }
+}
+
+struct MyPluginMainThread {
+    mode_b: bool,
+}
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginContextMenuImpl for MyPluginMainThread {
+    fn populate(
+        &mut self,
+        target: ContextMenuTarget,
+        builder: &mut ContextMenuBuilder,
+    ) -> Result<(), PluginError> {
+        match target {
+            ContextMenuTarget::Global => {
+                if builder.supports(ContextMenuItemKind::Title) {
+                    builder.add_title(label(b"My plugin\0"))?;
+                }
+
+                builder.add_entry(label(b"Reset\0"), RESET_ACTION)?;
+                builder.add_separator()?;
+                builder.begin_submenu(label(b"Mode\0"))?;
+                builder.add_check_entry(label(b"A\0"), MODE_A_ACTION, !self.mode_b)?;
+                builder.add_check_entry(label(b"B\0"), MODE_B_ACTION, self.mode_b)?;
+                builder.end_submenu()?;
+            }
+            ContextMenuTarget::Param(GAIN_PARAM) => builder.add_item(ContextMenuItem::Entry {
+                label: label(b"Randomize\0"),
+                is_enabled: false,
+                action_id: RANDOMIZE_ACTION,
+            })?,
+            ContextMenuTarget::Param(_) => return Err(PluginError::Message("Unknown parameter")),
+        }
+
+        Ok(())
+    }
+
+    fn perform(&mut self, target: ContextMenuTarget, action_id: ClapId) -> Result<(), PluginError> {
+        match (target, action_id) {
+            (ContextMenuTarget::Global, RESET_ACTION | MODE_A_ACTION) => self.mode_b = false,
+            (ContextMenuTarget::Global, MODE_B_ACTION) => self.mode_b = true,
+            _ => return Err(PluginError::Message("Unknown action")),
+        }
+
+        Ok(())
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        mut host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        if let Some(context_menu) = host.get_extension::<HostContextMenu>() {
+            let mut collector = MenuCollector::new(false);
+            context_menu.populate(
+                &mut host,
+                ContextMenuTarget::Param(GAIN_PARAM),
+                &mut collector,
+            )?;
+
+            assert_eq!(
+                collector.items,
+                [CollectedItem::Entry(
+                    label(b"Host entry\0").into(),
+                    true,
+                    HOST_ACTION
+                )]
+            );
+
+            context_menu.perform(&mut host, ContextMenuTarget::Param(GAIN_PARAM), HOST_ACTION)?;
+            assert!(context_menu
+                .perform(&mut host, ContextMenuTarget::Global, HOST_ACTION)
+                .is_err());
+            assert!(!context_menu.can_popup(&mut host));
+        }
+
+        Ok(MyPluginMainThread { mode_b: false })
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostContextMenu>();
+    }
+}
+
+struct MyHostMainThread {
+    performed_actions: Vec<(ContextMenuTarget, ClapId)>,
+}
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostContextMenuImpl for MyHostMainThread {
+    fn populate(
+        &mut self,
+        target: ContextMenuTarget,
+        builder: &mut ContextMenuBuilder,
+    ) -> Result<(), HostError> {
+        if builder.supports(ContextMenuItemKind::Title) {
+            builder.add_title(label(b"Host\0"))?;
+        }
+
+        if target == ContextMenuTarget::Param(GAIN_PARAM) {
+            builder.add_entry(label(b"Host entry\0"), HOST_ACTION)?;
+        }
+
+        Ok(())
+    }
+
+    fn perform(&mut self, target: ContextMenuTarget, action_id: ClapId) -> Result<(), HostError> {
+        if target != ContextMenuTarget::Param(GAIN_PARAM) {
+            return Err(HostError::Message("Unknown target"));
+        }
+
+        self.performed_actions.push((target, action_id));
+        Ok(())
+    }
+
+    fn can_popup(&mut self) -> bool {
+        false
+    }
+
+    fn popup(
+        &mut self,
+        _target: ContextMenuTarget,
+        _screen_index: i32,
+        _x: i32,
+        _y: i32,
+    ) -> Result<(), HostError> {
+        Err(HostError::Message("Unsupported"))
+    }
+}
+
+fn instantiate() -> PluginInstance<MyHost> {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread {
+            performed_actions: Vec::new(),
+        },
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap()
+}
+
+#[test]
+fn host_collects_plugin_menu_entries() {
+    let mut instance = instantiate();
+    let mut plugin = instance.plugin_handle();
+    let ext = plugin.get_extension::<PluginContextMenu>().unwrap();
+
+    let mut collector = MenuCollector::new(true);
+    ext.populate(&mut plugin, ContextMenuTarget::Global, &mut collector)
+        .unwrap();
+
+    assert_eq!(
+        collector.items,
+        [
+            CollectedItem::Title(label(b"My plugin\0").into(), true),
+            CollectedItem::Entry(label(b"Reset\0").into(), true, RESET_ACTION),
+            CollectedItem::Separator,
+            CollectedItem::BeginSubmenu(label(b"Mode\0").into(), true),
+            CollectedItem::CheckEntry(label(b"A\0").into(), true, true, MODE_A_ACTION),
+            CollectedItem::CheckEntry(label(b"B\0").into(), true, false, MODE_B_ACTION),
+            CollectedItem::EndSubmenu,
+        ]
+    );
+
+    let mut collector = MenuCollector::new(true);
+    ext.populate(
+        &mut plugin,
+        ContextMenuTarget::Param(GAIN_PARAM),
+        &mut collector,
+    )
+    .unwrap();
+
+    assert_eq!(
+        collector.items,
+        [CollectedItem::Entry(
+            label(b"Randomize\0").into(),
+            false,
+            RANDOMIZE_ACTION
+        )]
+    );
+
+    let mut collector = MenuCollector::new(true);
+    assert_eq!(
+        ext.populate(
+            &mut plugin,
+            ContextMenuTarget::Param(ClapId::new(6)),
+            &mut collector
+        ),
+        Err(ContextMenuError::PopulateError)
+    );
+}
+
+#[test]
+fn plugin_respects_builder_supported_items() {
+    let mut instance = instantiate();
+    let mut plugin = instance.plugin_handle();
+    let ext = plugin.get_extension::<PluginContextMenu>().unwrap();
+
+    let mut collector = MenuCollector::new(false);
+    ext.populate(&mut plugin, ContextMenuTarget::Global, &mut collector)
+        .unwrap();
+
+    assert_eq!(
+        collector.items.first(),
+        Some(&CollectedItem::Entry(
+            label(b"Reset\0").into(),
+            true,
+            RESET_ACTION
+        ))
+    );
+}
+
+#[test]
+fn plugin_performs_selected_actions() {
+    let mut instance = instantiate();
+    let mut plugin = instance.plugin_handle();
+    let ext = plugin.get_extension::<PluginContextMenu>().unwrap();
+
+    ext.perform(&mut plugin, ContextMenuTarget::Global, MODE_B_ACTION)
+        .unwrap();
+
+    let mut collector = MenuCollector::new(false);
+    ext.populate(&mut plugin, ContextMenuTarget::Global, &mut collector)
+        .unwrap();
+
+    assert!(collector.items.contains(&CollectedItem::CheckEntry(
+        label(b"B\0").into(),
+        true,
+        true,
+        MODE_B_ACTION
+    )));
+
+    assert_eq!(
+        ext.perform(
+            &mut plugin,
+            ContextMenuTarget::Param(GAIN_PARAM),
+            RESET_ACTION
+        ),
+        Err(ContextMenuError::PerformError)
+    );
+}
+
+#[test]
+fn plugin_can_use_host_menus() {
+    let instance = instantiate();
+
+    // The plugin performed a host action during its initialization.
+    assert_eq!(
+        instance.access_handler(|h| h.performed_actions.clone()),
+        [(ContextMenuTarget::Param(GAIN_PARAM), HOST_ACTION)]
+    );
+}
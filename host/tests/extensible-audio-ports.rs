@@ -0,0 +1,219 @@
+use clack_extensions::ambisonic::*;
+use clack_extensions::audio_ports::*;
+use clack_extensions::configurable_audio_ports::AudioPortRequestDetails;
+use clack_extensions::extensible_audio_ports::*;
+use clack_extensions::surround::{SurroundChannel, SurroundChannelMap};
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+const SURROUND_5_1: [SurroundChannel; 6] = [
+    SurroundChannel::FrontLeft,
+    SurroundChannel::FrontRight,
+    SurroundChannel::FrontCenter,
+    SurroundChannel::LowFrequency,
+    SurroundChannel::SideLeft,
+    SurroundChannel::SideRight,
+];
+
+const AMBISONIC_CONFIG: AmbisonicConfig = AmbisonicConfig {
+    ordering: AmbisonicOrdering::Acn,
+    normalization: AmbisonicNormalization::Sn3d,
+};
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder
+            .register::<PluginAudioPorts>()
+            .register::<PluginExtensibleAudioPorts>();
+    }
+}
+
+#[derive(Default)]
+struct MyPluginMainThread {
+    input_channel_counts: Vec<u32>,
+}
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginAudioPortsImpl for MyPluginMainThread {
+    fn count(&mut self, is_input: bool) -> u32 {
+        match is_input {
+            true => self.input_channel_counts.len() as u32,
+            false => 0,
+        }
+    }
+
+    fn get(&mut self, index: u32, is_input: bool, writer: &mut AudioPortInfoWriter) {
+        let Some(&channel_count) = self.input_channel_counts.get(index as usize) else {
+            return;
+        };
+
+        if is_input {
+            writer.set(&AudioPortInfo {
+                id: ClapId::new(index),
+                name: b"input",
+                channel_count,
+                flags: AudioPortFlags::empty(),
+                port_type: None,
+                in_place_pair: None,
+            });
+        }
+    }
+}
+
+impl PluginExtensibleAudioPortsImpl for MyPluginMainThread {
+    fn add_port(
+        &mut self,
+        is_input: bool,
+        channel_count: u32,
+        port_type: Option<AudioPortType>,
+        port_details: AudioPortRequestDetails,
+    ) -> Result<(), PluginError> {
+        if !is_input {
+            return Err(PluginError::Message("Only input ports can be added"));
+        }
+
+        match port_details {
+            AudioPortRequestDetails::None => {}
+            AudioPortRequestDetails::Surround(channel_map) => {
+                assert_eq!(port_type, Some(AudioPortType::SURROUND));
+                assert_eq!(channel_map, SurroundChannelMap::new(&SURROUND_5_1));
+            }
+            AudioPortRequestDetails::Ambisonic(config) => {
+                assert_eq!(port_type, Some(AudioPortType::AMBISONIC));
+                assert_eq!(config, AMBISONIC_CONFIG);
+            }
+        }
+
+        self.input_channel_counts.push(channel_count);
+        Ok(())
+    }
+
+    fn remove_port(&mut self, is_input: bool, index: u32) -> Result<(), PluginError> {
+        if !is_input || index as usize >= self.input_channel_counts.len() {
+            return Err(PluginError::Message("Invalid port"));
+        }
+
+        self.input_channel_counts.remove(index as usize);
+        Ok(())
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread::default())
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+fn input_channel_counts(plugin: &mut PluginMainThreadHandle) -> Vec<u32> {
+    let ports = plugin.get_extension::<PluginAudioPorts>().unwrap();
+    let mut buffer = AudioPortInfoBuffer::new();
+
+    (0..ports.count(plugin, true))
+        .map(|i| {
+            ports
+                .get(plugin, i, true, &mut buffer)
+                .unwrap()
+                .channel_count
+        })
+        .collect()
+}
+
+#[test]
+fn host_adds_and_removes_ports() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let ext = plugin
+        .get_extension::<PluginExtensibleAudioPorts>()
+        .unwrap();
+    assert!(input_channel_counts(&mut plugin).is_empty());
+
+    ext.add_port(&mut plugin, true, 1, None, AudioPortRequestDetails::None)
+        .unwrap();
+
+    let surround = AudioPortRequestDetails::Surround(SurroundChannelMap::new(&SURROUND_5_1));
+    ext.add_port(
+        &mut plugin,
+        true,
+        6,
+        Some(AudioPortType::SURROUND),
+        surround,
+    )
+    .unwrap();
+
+    // The surround channel map doesn't match the channel count.
+    assert_eq!(
+        ext.add_port(
+            &mut plugin,
+            true,
+            2,
+            Some(AudioPortType::SURROUND),
+            surround
+        ),
+        Err(AudioPortChangeError::adding())
+    );
+
+    ext.add_port(
+        &mut plugin,
+        true,
+        4,
+        Some(AudioPortType::AMBISONIC),
+        AudioPortRequestDetails::Ambisonic(AMBISONIC_CONFIG),
+    )
+    .unwrap();
+
+    assert_eq!(
+        ext.add_port(&mut plugin, false, 2, None, AudioPortRequestDetails::None),
+        Err(AudioPortChangeError::adding())
+    );
+
+    assert_eq!(input_channel_counts(&mut plugin), [1, 6, 4]);
+
+    ext.remove_port(&mut plugin, true, 0).unwrap();
+    assert_eq!(
+        ext.remove_port(&mut plugin, true, 5),
+        Err(AudioPortChangeError::removing())
+    );
+
+    assert_eq!(input_channel_counts(&mut plugin), [6, 4]);
+}
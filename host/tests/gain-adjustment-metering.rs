@@ -0,0 +1,106 @@
+use clack_extensions::gain_adjustment_metering::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = MyAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginGainAdjustmentMetering>();
+    }
+}
+
+struct MyAudioProcessor {
+    gain_reduction: f64,
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for MyAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self {
+            gain_reduction: -6.0,
+        })
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        Ok(ProcessStatus::Sleep)
+    }
+}
+
+impl PluginGainAdjustmentMeteringImpl for MyAudioProcessor {
+    fn get_current_gain_adjustment(&mut self) -> f64 {
+        self.gain_reduction
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(_host: HostMainThreadHandle, _shared: &()) -> Result<(), PluginError> {
+        Ok(())
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn host_reads_gain_adjustment() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let metering = instance
+        .plugin_handle()
+        .get_extension::<PluginGainAdjustmentMetering>()
+        .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 5,
+        max_frames_count: 5,
+    };
+
+    let mut processor = instance.activate(|_, _| (), config).unwrap();
+
+    let mut plugin = processor.plugin_handle();
+    assert_eq!(metering.get_current_gain_adjustment(&mut plugin), -6.0);
+
+    instance.deactivate(processor);
+}
@@ -0,0 +1,185 @@
+use clack_extensions::mini_curve_display::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginMiniCurveDisplay>();
+    }
+}
+
+#[derive(Default)]
+struct MyPluginMainThread {
+    is_observed: bool,
+}
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginMiniCurveDisplayImpl for MyPluginMainThread {
+    fn get_curve_count(&mut self) -> u32 {
+        2
+    }
+
+    fn render(&mut self, curves: &mut [MiniCurve]) -> u32 {
+        assert!(self.is_observed);
+
+        let [response, ..] = curves else {
+            return 0;
+        };
+
+        // A simple ramp, from the bottom to the top of the display.
+        response.set_kind(MiniCurveKind::GainResponse);
+        let values = response.values_mut();
+        let step = (u16::MAX - 2) / (values.len() as u16 - 1);
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = 1 + i as u16 * step;
+        }
+
+        1
+    }
+
+    fn set_observed(&mut self, is_observed: bool) {
+        self.is_observed = is_observed;
+    }
+
+    fn get_axis_name(&mut self, curve_index: u32, writer: &mut MiniCurveAxisNamesWriter) {
+        if curve_index == 0 {
+            writer.set(&MiniCurveAxisNames {
+                x_name: b"Frequency",
+                y_name: b"Gain",
+            })
+        }
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        mut host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        let display = host
+            .get_extension::<HostMiniCurveDisplay>()
+            .ok_or(PluginError::Message("No mini curve display"))?;
+
+        let hints = display.get_hints(&mut host, MiniCurveKind::GainResponse);
+        assert_eq!(
+            hints,
+            Some(MiniCurveHints {
+                x_min: 20.0,
+                x_max: 20_000.0,
+                y_min: -24.0,
+                y_max: 24.0,
+            })
+        );
+        assert_eq!(
+            display.get_hints(&mut host, MiniCurveKind::PhaseResponse),
+            None
+        );
+
+        display.set_dynamic(&mut host, false);
+        display.changed(&mut host, MiniCurveChangeFlags::AXIS_NAME_CHANGED);
+
+        Ok(MyPluginMainThread::default())
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostMiniCurveDisplay>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostMainThread {
+    is_dynamic: Option<bool>,
+    changed: Option<MiniCurveChangeFlags>,
+}
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostMiniCurveDisplayImpl for MyHostMainThread {
+    fn get_hints(&mut self, kind: MiniCurveKind) -> Option<MiniCurveHints> {
+        (kind == MiniCurveKind::GainResponse).then_some(MiniCurveHints {
+            x_min: 20.0,
+            x_max: 20_000.0,
+            y_min: -24.0,
+            y_max: 24.0,
+        })
+    }
+
+    fn set_dynamic(&mut self, is_dynamic: bool) {
+        self.is_dynamic = Some(is_dynamic);
+    }
+
+    fn changed(&mut self, flags: MiniCurveChangeFlags) {
+        self.changed = Some(flags);
+    }
+}
+
+#[test]
+fn host_renders_curves() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread::default(),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    instance.access_handler(|h| {
+        assert_eq!(h.is_dynamic, Some(false));
+        assert_eq!(h.changed, Some(MiniCurveChangeFlags::AXIS_NAME_CHANGED));
+    });
+
+    let mut plugin = instance.plugin_handle();
+    let display = plugin.get_extension::<PluginMiniCurveDisplay>().unwrap();
+
+    let curve_count = display.get_curve_count(&mut plugin);
+    assert_eq!(curve_count, 2);
+
+    display.set_observed(&mut plugin, true);
+
+    let mut buffer = MiniCurveRenderBuffer::new(curve_count as usize, 4);
+    let curves = display.render(&mut plugin, &mut buffer);
+
+    assert_eq!(curves.len(), 1);
+    assert_eq!(curves[0].kind(), Some(MiniCurveKind::GainResponse));
+    assert_eq!(curves[0].values(), [1, 21845, 43689, 65533]);
+
+    let mut names = MiniCurveAxisNamesBuffer::new();
+    let axis_names = display.get_axis_name(&mut plugin, 0, &mut names).unwrap();
+    assert_eq!(axis_names.x_name, b"Frequency");
+    assert_eq!(axis_names.y_name, b"Gain");
+
+    assert!(display.get_axis_name(&mut plugin, 1, &mut names).is_none());
+
+    display.set_observed(&mut plugin, false);
+}
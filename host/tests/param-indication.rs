@@ -0,0 +1,181 @@
+use clack_extensions::param_indication::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use clack_plugin::utils::{ClapId, Color};
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+
+#[derive(Debug, Eq, PartialEq)]
+enum Indication {
+    Mapping {
+        param_id: ClapId,
+        has_mapping: bool,
+        color: Option<Color>,
+        label: Option<CString>,
+        description: Option<CString>,
+    },
+    Automation {
+        param_id: ClapId,
+        automation_state: AutomationState,
+        color: Option<Color>,
+    },
+}
+
+static RECEIVED_INDICATIONS: Mutex<Vec<Indication>> = Mutex::new(Vec::new());
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginParamIndication>();
+    }
+}
+
+struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginParamIndicationImpl for MyPluginMainThread {
+    fn set_mapping(
+        &mut self,
+        param_id: ClapId,
+        has_mapping: bool,
+        color: Option<Color>,
+        label: Option<&CStr>,
+        description: Option<&CStr>,
+    ) {
+        RECEIVED_INDICATIONS
+            .lock()
+            .unwrap()
+            .push(Indication::Mapping {
+                param_id,
+                has_mapping,
+                color,
+                label: label.map(CString::from),
+                description: description.map(CString::from),
+            });
+    }
+
+    fn set_automation(
+        &mut self,
+        param_id: ClapId,
+        automation_state: AutomationState,
+        color: Option<Color>,
+    ) {
+        RECEIVED_INDICATIONS
+            .lock()
+            .unwrap()
+            .push(Indication::Automation {
+                param_id,
+                automation_state,
+                color,
+            });
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn plugin_receives_param_indications() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let ext = plugin.get_extension::<PluginParamIndication>().unwrap();
+
+    let color = Color {
+        alpha: 0x80,
+        red: 0x12,
+        green: 0x34,
+        blue: 0x56,
+    };
+    let label = CStr::from_bytes_with_nul(b"CC 7\0").unwrap();
+    let description = CStr::from_bytes_with_nul(b"Mapped to the volume fader\0").unwrap();
+
+    ext.set_mapping(
+        &mut plugin,
+        ClapId::new(1),
+        true,
+        Some(color),
+        Some(label),
+        Some(description),
+    );
+    ext.set_mapping(&mut plugin, ClapId::new(2), false, None, None, None);
+    ext.set_automation(
+        &mut plugin,
+        ClapId::new(1),
+        AutomationState::Recording,
+        Some(color),
+    );
+    ext.set_automation(&mut plugin, ClapId::new(2), AutomationState::None, None);
+
+    let received = RECEIVED_INDICATIONS.lock().unwrap();
+    assert_eq!(
+        *received,
+        [
+            Indication::Mapping {
+                param_id: ClapId::new(1),
+                has_mapping: true,
+                color: Some(color),
+                label: Some(label.into()),
+                description: Some(description.into()),
+            },
+            Indication::Mapping {
+                param_id: ClapId::new(2),
+                has_mapping: false,
+                color: None,
+                label: None,
+                description: None,
+            },
+            Indication::Automation {
+                param_id: ClapId::new(1),
+                automation_state: AutomationState::Recording,
+                color: Some(color),
+            },
+            Indication::Automation {
+                param_id: ClapId::new(2),
+                automation_state: AutomationState::None,
+                color: None,
+            },
+        ]
+    );
+}
@@ -0,0 +1,82 @@
+use clack_host::factory::plugin_invalidation::PluginInvalidationFactory;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::entry::{Entry, EntryDescriptor, EntryFactories, EntryLoadError};
+use clack_plugin::factory::plugin_invalidation::{
+    InvalidationSource, PluginInvalidationFactory as PluginPluginInvalidationFactory,
+    PluginInvalidationFactoryWrapper,
+};
+use std::ffi::CStr;
+
+struct MyPluginInvalidationFactory {
+    sources: Vec<InvalidationSource>,
+}
+
+impl PluginPluginInvalidationFactory for MyPluginInvalidationFactory {
+    fn source_count(&self) -> u32 {
+        self.sources.len() as u32
+    }
+
+    fn source(&self, index: u32) -> Option<&InvalidationSource> {
+        self.sources.get(index as usize)
+    }
+
+    fn refresh(&self) -> bool {
+        true
+    }
+}
+
+struct MyEntry {
+    plugin_invalidation_factory: PluginInvalidationFactoryWrapper<MyPluginInvalidationFactory>,
+}
+
+impl Entry for MyEntry {
+    fn new(_bundle_path: &CStr) -> Result<Self, EntryLoadError> {
+        Ok(Self {
+            plugin_invalidation_factory: PluginInvalidationFactoryWrapper::new(
+                MyPluginInvalidationFactory {
+                    sources: vec![
+                        InvalidationSource::new("/patches", "*.patch", true),
+                        InvalidationSource::new("/samples", "*.wav", false),
+                    ],
+                },
+            ),
+        })
+    }
+
+    fn declare_factories<'a>(&'a self, builder: &mut EntryFactories<'a>) {
+        builder.register_factory(&self.plugin_invalidation_factory);
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(MyEntry);
+
+fn cstr(bytes: &[u8]) -> &CStr {
+    CStr::from_bytes_with_nul(bytes).unwrap()
+}
+
+#[test]
+fn host_lists_invalidation_sources() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    assert!(bundle.get_plugin_factory().is_none());
+    let factory = bundle.get_factory::<PluginInvalidationFactory>().unwrap();
+
+    assert_eq!(factory.source_count(), 2);
+    assert!(factory.source(2).is_none());
+
+    let sources: Vec<_> = factory
+        .sources()
+        .map(|s| (s.directory(), s.filename_glob(), s.recursive_scan()))
+        .collect();
+
+    assert_eq!(
+        sources,
+        [
+            (Some(cstr(b"/patches\0")), Some(cstr(b"*.patch\0")), true),
+            (Some(cstr(b"/samples\0")), Some(cstr(b"*.wav\0")), false),
+        ]
+    );
+
+    assert!(factory.refresh());
+}
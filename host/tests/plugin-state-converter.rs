@@ -0,0 +1,191 @@
+use clack_host::factory::plugin_state_converter::{
+    ConverterInstanceError, PluginStateConverterFactory,
+};
+use clack_host::prelude::*;
+use clack_host::utils::ClapId;
+use clack_plugin::clack_entry;
+use clack_plugin::entry::{Entry, EntryDescriptor, EntryFactories, EntryLoadError};
+use clack_plugin::factory::plugin_state_converter::{
+    PluginId, PluginStateConverter, PluginStateConverterDescriptor,
+    PluginStateConverterFactory as PluginPluginStateConverterFactory,
+    PluginStateConverterFactoryWrapper, PluginStateConverterInstance,
+};
+use clack_plugin::prelude::PluginError;
+use clack_plugin::stream::{InputStream, OutputStream};
+use std::ffi::CStr;
+use std::io::{Read, Write};
+
+fn cstr(bytes: &[u8]) -> &CStr {
+    CStr::from_bytes_with_nul(bytes).unwrap()
+}
+
+struct MyConverterFactory {
+    descriptor: PluginStateConverterDescriptor,
+}
+
+impl PluginPluginStateConverterFactory for MyConverterFactory {
+    fn converter_count(&self) -> u32 {
+        1
+    }
+
+    fn converter_descriptor(&self, index: u32) -> Option<&PluginStateConverterDescriptor> {
+        match index {
+            0 => Some(&self.descriptor),
+            _ => None,
+        }
+    }
+
+    fn create_converter(&self, converter_id: &CStr) -> Option<PluginStateConverterInstance<'_>> {
+        if converter_id != self.descriptor.id() {
+            return None;
+        }
+
+        Some(PluginStateConverterInstance::new(
+            &self.descriptor,
+            MyConverter,
+        ))
+    }
+}
+
+struct MyConverter;
+
+impl PluginStateConverter for MyConverter {
+    fn convert_state(
+        &mut self,
+        src: &mut InputStream,
+        dst: &mut OutputStream,
+    ) -> Result<(), PluginError> {
+        let mut state = vec![];
+        src.read_to_end(&mut state)?;
+
+        if state.is_empty() {
+            return Err(PluginError::Message("Empty state"));
+        }
+
+        dst.write_all(b"v2:")?;
+        dst.write_all(&state)?;
+        Ok(())
+    }
+
+    fn convert_normalized_value(
+        &mut self,
+        src_param_id: ClapId,
+        src_normalized_value: f64,
+    ) -> Option<(ClapId, f64)> {
+        match src_param_id.get() {
+            1 => Some((ClapId::new(10), src_normalized_value)),
+            _ => None,
+        }
+    }
+
+    fn convert_plain_value(
+        &mut self,
+        src_param_id: ClapId,
+        src_plain_value: f64,
+    ) -> Option<(ClapId, f64)> {
+        match src_param_id.get() {
+            1 => Some((ClapId::new(10), src_plain_value * 2.0)),
+            _ => None,
+        }
+    }
+}
+
+struct MyEntry {
+    converter_factory: PluginStateConverterFactoryWrapper<MyConverterFactory>,
+}
+
+impl Entry for MyEntry {
+    fn new(_bundle_path: &CStr) -> Result<Self, EntryLoadError> {
+        let descriptor = PluginStateConverterDescriptor::new(
+            "org.new.gain.converter",
+            "Gain converter",
+            PluginId::clap(cstr(b"org.old.gain\0")),
+            PluginId::clap(cstr(b"org.new.gain\0")),
+        )
+        .with_vendor("Me")
+        .with_version("1.0.0");
+
+        Ok(Self {
+            converter_factory: PluginStateConverterFactoryWrapper::new(MyConverterFactory {
+                descriptor,
+            }),
+        })
+    }
+
+    fn declare_factories<'a>(&'a self, builder: &mut EntryFactories<'a>) {
+        builder.register_factory(&self.converter_factory);
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(MyEntry);
+
+#[test]
+fn host_converts_plugin_state() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    assert!(bundle.get_plugin_factory().is_none());
+    let factory = bundle.get_factory::<PluginStateConverterFactory>().unwrap();
+
+    let descriptors: Vec<_> = factory.converter_descriptors().collect();
+    assert_eq!(descriptors.len(), 1);
+
+    let descriptor = descriptors[0];
+    assert_eq!(descriptor.id(), Some(cstr(b"org.new.gain.converter\0")));
+    assert_eq!(descriptor.name(), Some(cstr(b"Gain converter\0")));
+    assert_eq!(descriptor.vendor(), Some(cstr(b"Me\0")));
+    assert_eq!(descriptor.version(), Some(cstr(b"1.0.0\0")));
+    assert_eq!(descriptor.description(), None);
+    assert_eq!(
+        descriptor.src_plugin_id(),
+        Some(PluginId::clap(cstr(b"org.old.gain\0")))
+    );
+    assert_eq!(
+        descriptor.dst_plugin_id(),
+        Some(PluginId::clap(cstr(b"org.new.gain\0")))
+    );
+
+    let mut converter = factory
+        .create_converter(cstr(b"org.new.gain.converter\0"))
+        .unwrap();
+
+    assert_eq!(
+        converter.descriptor().unwrap().id(),
+        Some(cstr(b"org.new.gain.converter\0"))
+    );
+
+    let mut converted = vec![];
+    converter
+        .convert_state(&mut &b"gain=0.5"[..], &mut converted)
+        .unwrap();
+    assert_eq!(converted, b"v2:gain=0.5");
+
+    let mut converted = vec![];
+    assert_eq!(
+        converter.convert_state(&mut &b""[..], &mut converted),
+        Err(ConverterInstanceError::ConvertStateFailed(Some(
+            "Empty state".to_string()
+        )))
+    );
+    assert!(converted.is_empty());
+
+    assert_eq!(
+        converter.convert_normalized_value(ClapId::new(1), 0.25),
+        Some((ClapId::new(10), 0.25))
+    );
+    assert_eq!(
+        converter.convert_plain_value(ClapId::new(1), 0.25),
+        Some((ClapId::new(10), 0.5))
+    );
+    assert_eq!(converter.convert_plain_value(ClapId::new(2), 0.25), None);
+}
+
+#[test]
+fn unknown_converter_is_not_found() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+    let factory = bundle.get_factory::<PluginStateConverterFactory>().unwrap();
+
+    assert_eq!(
+        factory.create_converter(cstr(b"unknown\0")).err(),
+        Some(ConverterInstanceError::ConverterNotFound)
+    );
+}
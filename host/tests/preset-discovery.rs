@@ -0,0 +1,288 @@
+use clack_host::factory::preset_discovery::{
+    MetadataError, PresetDiscoveryFactory, PresetIndexer, PresetMetadata, PresetMetadataCollector,
+    PresetPluginId, ProviderInstanceError,
+};
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::entry::{Entry, EntryDescriptor, EntryFactories, EntryLoadError};
+use clack_plugin::factory::preset_discovery::{
+    FileType, Flags, IndexerInfo, Location, LocationInfo, MetadataReceiver, PluginId,
+    PresetDiscoveryFactory as PluginPresetDiscoveryFactory, PresetDiscoveryFactoryWrapper,
+    PresetDiscoveryProvider, ProviderDescriptor, ProviderInstance, Soundpack, Timestamp,
+};
+use clack_plugin::prelude::PluginError;
+use std::ffi::CStr;
+
+fn cstr(bytes: &[u8]) -> &CStr {
+    CStr::from_bytes_with_nul(bytes).unwrap()
+}
+
+struct MyPresetDiscoveryFactory {
+    descriptor: ProviderDescriptor,
+}
+
+impl PluginPresetDiscoveryFactory for MyPresetDiscoveryFactory {
+    fn provider_count(&self) -> u32 {
+        1
+    }
+
+    fn provider_descriptor(&self, index: u32) -> Option<&ProviderDescriptor> {
+        match index {
+            0 => Some(&self.descriptor),
+            _ => None,
+        }
+    }
+
+    fn create_provider<'a>(
+        &'a self,
+        indexer: IndexerInfo<'a>,
+        provider_id: &CStr,
+    ) -> Option<ProviderInstance<'a>> {
+        if provider_id != self.descriptor.id() {
+            return None;
+        }
+
+        assert_eq!(indexer.name(), Some(cstr(b"host\0")));
+
+        Some(ProviderInstance::new(
+            indexer,
+            &self.descriptor,
+            |indexer| {
+                indexer.declare_filetype(FileType {
+                    name: cstr(b"My preset\0"),
+                    description: None,
+                    file_extension: Some(cstr(b"mypreset\0")),
+                })?;
+
+                indexer.declare_location(LocationInfo {
+                    flags: Flags::IS_FACTORY_CONTENT,
+                    name: cstr(b"Factory\0"),
+                    location: Location::Plugin,
+                })?;
+
+                indexer.declare_location(LocationInfo {
+                    flags: Flags::IS_USER_CONTENT,
+                    name: cstr(b"User\0"),
+                    location: Location::File {
+                        path: cstr(b"/presets\0"),
+                    },
+                })?;
+
+                indexer.declare_soundpack(Soundpack {
+                    flags: Flags::IS_FACTORY_CONTENT,
+                    id: cstr(b"core\0"),
+                    name: cstr(b"Core sounds\0"),
+                    description: None,
+                    homepage_url: None,
+                    vendor: Some(cstr(b"Me\0")),
+                    image_path: None,
+                    release_timestamp: Timestamp::from_secs_since_epoch(1_700_000_000),
+                })?;
+
+                Ok(MyPresetProvider)
+            },
+        ))
+    }
+}
+
+struct MyPresetProvider;
+
+impl PresetDiscoveryProvider for MyPresetProvider {
+    fn get_metadata(
+        &mut self,
+        location: Location,
+        receiver: &mut MetadataReceiver,
+    ) -> Result<(), PluginError> {
+        if location != Location::Plugin {
+            receiver.on_error(2, cstr(b"No such file\0"));
+            return Err(PluginError::Message("Unknown location"));
+        }
+
+        let plugin_id = PluginId::clap(cstr(b"my.plugin\0"));
+
+        receiver
+            .begin_preset(Some(cstr(b"Init\0")), Some(cstr(b"0\0")))?
+            .add_plugin_id(plugin_id)
+            .set_flags(Flags::IS_FACTORY_CONTENT);
+
+        receiver
+            .begin_preset(Some(cstr(b"Big Lead\0")), Some(cstr(b"1\0")))?
+            .add_plugin_id(plugin_id)
+            .set_soundpack_id(cstr(b"core\0"))
+            .add_creator(cstr(b"Alice\0"))
+            .add_creator(cstr(b"Bob\0"))
+            .set_description(cstr(b"A big lead\0"))
+            .set_timestamps(Timestamp::from_secs_since_epoch(1_700_000_000), None)
+            .add_feature(cstr(b"lead\0"))
+            .add_extra_info(cstr(b"bpm\0"), cstr(b"120\0"));
+
+        Ok(())
+    }
+}
+
+struct MyEntry {
+    preset_discovery_factory: PresetDiscoveryFactoryWrapper<MyPresetDiscoveryFactory>,
+}
+
+impl Entry for MyEntry {
+    fn new(_bundle_path: &CStr) -> Result<Self, EntryLoadError> {
+        Ok(Self {
+            preset_discovery_factory: PresetDiscoveryFactoryWrapper::new(
+                MyPresetDiscoveryFactory {
+                    descriptor: ProviderDescriptor::new("my.presets", "My presets")
+                        .with_vendor("Me"),
+                },
+            ),
+        })
+    }
+
+    fn declare_factories<'a>(&'a self, builder: &mut EntryFactories<'a>) {
+        builder.register_factory(&self.preset_discovery_factory);
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(MyEntry);
+
+#[derive(Default)]
+struct MyIndexer {
+    file_extensions: Vec<String>,
+    locations: Vec<(Flags, String, Option<String>)>,
+    soundpacks: Vec<(String, Option<Timestamp>)>,
+}
+
+impl PresetIndexer for MyIndexer {
+    fn declare_filetype(&mut self, file_type: FileType) -> Result<(), HostError> {
+        let extension = file_type.file_extension.ok_or(HostError::Message("Any"))?;
+        self.file_extensions
+            .push(extension.to_str().unwrap().to_string());
+        Ok(())
+    }
+
+    fn declare_location(&mut self, location: LocationInfo) -> Result<(), HostError> {
+        let path = match location.location {
+            Location::File { path } => Some(path.to_str().unwrap().to_string()),
+            Location::Plugin => None,
+        };
+
+        let name = location.name.to_str().unwrap().to_string();
+        self.locations.push((location.flags, name, path));
+        Ok(())
+    }
+
+    fn declare_soundpack(&mut self, soundpack: Soundpack) -> Result<(), HostError> {
+        let id = soundpack.id.to_str().unwrap().to_string();
+        self.soundpacks.push((id, soundpack.release_timestamp));
+        Ok(())
+    }
+}
+
+#[test]
+fn host_indexes_plugin_presets() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    assert!(bundle.get_plugin_factory().is_none());
+    let factory = bundle.get_factory::<PresetDiscoveryFactory>().unwrap();
+
+    let descriptors: Vec<_> = factory.provider_descriptors().collect();
+    assert_eq!(descriptors.len(), 1);
+    assert_eq!(descriptors[0].id(), Some(cstr(b"my.presets\0")));
+    assert_eq!(descriptors[0].name(), Some(cstr(b"My presets\0")));
+    assert_eq!(descriptors[0].vendor(), Some(cstr(b"Me\0")));
+
+    let mut provider = factory
+        .create_provider(cstr(b"my.presets\0"), MyIndexer::default(), &host)
+        .unwrap();
+
+    assert_eq!(
+        provider.descriptor().unwrap().id(),
+        Some(cstr(b"my.presets\0"))
+    );
+
+    let indexer = provider.indexer();
+    assert_eq!(indexer.file_extensions, ["mypreset"]);
+    assert_eq!(
+        indexer.locations,
+        [
+            (Flags::IS_FACTORY_CONTENT, "Factory".to_string(), None),
+            (
+                Flags::IS_USER_CONTENT,
+                "User".to_string(),
+                Some("/presets".to_string())
+            )
+        ]
+    );
+    assert_eq!(
+        indexer.soundpacks,
+        [(
+            "core".to_string(),
+            Timestamp::from_secs_since_epoch(1_700_000_000)
+        )]
+    );
+
+    let mut collector = PresetMetadataCollector::new();
+    provider
+        .get_metadata(Location::Plugin, &mut collector)
+        .unwrap();
+
+    let plugin_id = PresetPluginId {
+        abi: "clap".to_string(),
+        id: "my.plugin".to_string(),
+    };
+
+    assert!(collector.errors().is_empty());
+    assert_eq!(
+        collector.presets(),
+        [
+            PresetMetadata {
+                name: Some("Init".to_string()),
+                load_key: Some("0".to_string()),
+                plugin_ids: vec![plugin_id.clone()],
+                flags: Some(Flags::IS_FACTORY_CONTENT),
+                ..PresetMetadata::default()
+            },
+            PresetMetadata {
+                name: Some("Big Lead".to_string()),
+                load_key: Some("1".to_string()),
+                plugin_ids: vec![plugin_id],
+                soundpack_id: Some("core".to_string()),
+                flags: None,
+                creators: vec!["Alice".to_string(), "Bob".to_string()],
+                description: Some("A big lead".to_string()),
+                creation_time: Timestamp::from_secs_since_epoch(1_700_000_000),
+                modification_time: None,
+                features: vec!["lead".to_string()],
+                extra_info: vec![("bpm".to_string(), "120".to_string())],
+            }
+        ]
+    );
+
+    let missing = Location::File {
+        path: cstr(b"/presets/missing.mypreset\0"),
+    };
+    assert_eq!(
+        provider.get_metadata(missing, &mut collector),
+        Err(ProviderInstanceError::GetMetadataFailed)
+    );
+    assert_eq!(
+        collector.errors(),
+        [MetadataError {
+            os_error: 2,
+            message: "No such file".to_string()
+        }]
+    );
+    assert_eq!(collector.into_presets().len(), 2);
+}
+
+#[test]
+fn unknown_provider_is_not_found() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+    let factory = bundle.get_factory::<PresetDiscoveryFactory>().unwrap();
+
+    let provider = factory.create_provider(cstr(b"unknown\0"), MyIndexer::default(), &host);
+    assert_eq!(
+        provider.err(),
+        Some(ProviderInstanceError::ProviderNotFound)
+    );
+}
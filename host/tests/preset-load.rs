@@ -0,0 +1,201 @@
+use clack_extensions::preset_load::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::{CStr, CString};
+
+fn cstr(bytes: &[u8]) -> &CStr {
+    CStr::from_bytes_with_nul(bytes).unwrap()
+}
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread<'a>;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginPresetLoad>();
+    }
+}
+
+struct MyPluginMainThread<'a> {
+    host: HostMainThreadHandle<'a>,
+}
+
+impl<'a> PluginMainThread<'a, ()> for MyPluginMainThread<'a> {}
+
+impl PluginPresetLoadImpl for MyPluginMainThread<'_> {
+    fn from_location(
+        &mut self,
+        location: PresetLocation,
+        load_key: Option<&CStr>,
+    ) -> Result<(), PluginError> {
+        let host_preset_load = self
+            .host
+            .get_extension::<HostPresetLoad>()
+            .ok_or(PluginError::Message("No host preset-load extension"))?;
+
+        match location {
+            PresetLocation::File { path } if path == cstr(b"/missing.preset\0") => {
+                host_preset_load.on_error(
+                    &mut self.host,
+                    location,
+                    load_key,
+                    2,
+                    cstr(b"Preset not found\0"),
+                );
+
+                Err(PluginError::Message("Preset not found"))
+            }
+            _ => {
+                host_preset_load.loaded(&mut self.host, location, load_key);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        host: HostMainThreadHandle<'a>,
+        _shared: &'a (),
+    ) -> Result<MyPluginMainThread<'a>, PluginError> {
+        Ok(MyPluginMainThread { host })
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+/// An owned version of a location and load key, as reported back to the host.
+#[derive(Debug, Eq, PartialEq)]
+struct ReportedPreset {
+    path: Option<CString>,
+    load_key: Option<CString>,
+}
+
+impl ReportedPreset {
+    fn new(location: PresetLocation, load_key: Option<&CStr>) -> Self {
+        Self {
+            path: match location {
+                PresetLocation::File { path } => Some(path.into()),
+                PresetLocation::Plugin => None,
+            },
+            load_key: load_key.map(CString::from),
+        }
+    }
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostPresetLoad>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostMainThread {
+    loaded: Vec<ReportedPreset>,
+    errors: Vec<(ReportedPreset, i32, Option<CString>)>,
+}
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostPresetLoadImpl for MyHostMainThread {
+    fn on_error(
+        &mut self,
+        location: PresetLocation,
+        load_key: Option<&CStr>,
+        os_error: i32,
+        message: Option<&CStr>,
+    ) {
+        self.errors.push((
+            ReportedPreset::new(location, load_key),
+            os_error,
+            message.map(CString::from),
+        ));
+    }
+
+    fn loaded(&mut self, location: PresetLocation, load_key: Option<&CStr>) {
+        self.loaded.push(ReportedPreset::new(location, load_key));
+    }
+}
+
+#[test]
+fn presets_load_round_trip() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread::default(),
+        &bundle,
+        cstr(b"my.plugin\0"),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let preset_load = plugin.get_extension::<PluginPresetLoad>().unwrap();
+
+    let file = PresetLocation::File {
+        path: cstr(b"/presets/bank.preset\0"),
+    };
+
+    preset_load
+        .from_location(&mut plugin, file, Some(cstr(b"lead\0")))
+        .unwrap();
+    preset_load
+        .from_location(&mut plugin, PresetLocation::Plugin, None)
+        .unwrap();
+
+    let missing = PresetLocation::File {
+        path: cstr(b"/missing.preset\0"),
+    };
+    assert_eq!(
+        preset_load.from_location(&mut plugin, missing, None),
+        Err(PresetLoadError)
+    );
+
+    instance.access_handler(|h| {
+        assert_eq!(
+            h.loaded,
+            [
+                ReportedPreset {
+                    path: Some(cstr(b"/presets/bank.preset\0").into()),
+                    load_key: Some(cstr(b"lead\0").into()),
+                },
+                ReportedPreset {
+                    path: None,
+                    load_key: None,
+                },
+            ]
+        );
+
+        assert_eq!(
+            h.errors,
+            [(
+                ReportedPreset {
+                    path: Some(cstr(b"/missing.preset\0").into()),
+                    load_key: None,
+                },
+                2,
+                Some(cstr(b"Preset not found\0").into()),
+            )]
+        );
+    });
+}
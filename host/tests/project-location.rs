@@ -0,0 +1,121 @@
+use clack_extensions::project_location::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use clack_plugin::utils::Color;
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+static RECEIVED_PATHS: Mutex<Vec<Vec<ProjectLocationElement>>> = Mutex::new(Vec::new());
+
+fn location() -> Vec<ProjectLocationElement> {
+    vec![
+        ProjectLocationElement {
+            id: "project-1".into(),
+            name: "My Song".into(),
+            ..ProjectLocationElement::new(ProjectLocationKind::Project)
+        },
+        ProjectLocationElement {
+            index: Some(2),
+            name: "Drum Bus".into(),
+            color: Some(Color::from_rgb(0x12, 0x34, 0x56)),
+            ..ProjectLocationElement::new(ProjectLocationKind::TrackGroup)
+        },
+        ProjectLocationElement {
+            track_kind: Some(ProjectLocationTrackKind::Audio),
+            index: Some(0),
+            name: "Kick".into(),
+            ..ProjectLocationElement::new(ProjectLocationKind::Track)
+        },
+        ProjectLocationElement {
+            index: Some(3),
+            name: "Compressor".into(),
+            ..ProjectLocationElement::new(ProjectLocationKind::Device)
+        },
+    ]
+}
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginProjectLocation>();
+    }
+}
+
+struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginProjectLocationImpl for MyPluginMainThread {
+    fn set(&mut self, path: ProjectLocationElements) {
+        RECEIVED_PATHS.lock().unwrap().push(path.collect());
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn plugin_receives_project_location() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let ext = plugin.get_extension::<PluginProjectLocation>().unwrap();
+
+    ext.set(&mut plugin, &location());
+    ext.set(&mut plugin, &[]);
+
+    let long_name = ProjectLocationElement {
+        name: "a".repeat(1000),
+        ..ProjectLocationElement::new(ProjectLocationKind::Device)
+    };
+    ext.set(&mut plugin, &[long_name]);
+
+    let received = RECEIVED_PATHS.lock().unwrap();
+    assert_eq!(received.len(), 3);
+    assert_eq!(received[0], location());
+    assert!(received[1].is_empty());
+
+    // Names are truncated to fit in the CLAP buffers.
+    assert_eq!(received[2].len(), 1);
+    assert_eq!(received[2][0].name.len(), 255);
+}
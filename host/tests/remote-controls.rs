@@ -0,0 +1,155 @@
+use clack_extensions::remote_controls::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+fn pages() -> Vec<RemoteControlsPage> {
+    vec![
+        RemoteControlsPage {
+            section_name: "Oscillators".into(),
+            page_id: ClapId::new(0),
+            page_name: "Osc 1".into(),
+            param_ids: [
+                Some(ClapId::new(1)),
+                Some(ClapId::new(2)),
+                Some(ClapId::new(3)),
+                None,
+                None,
+                None,
+                None,
+                Some(ClapId::new(8)),
+            ],
+            is_for_preset: false,
+        },
+        RemoteControlsPage {
+            section_name: "Macros".into(),
+            page_id: ClapId::new(7),
+            page_name: "Preset macros".into(),
+            param_ids: [Some(ClapId::new(100)); REMOTE_CONTROLS_COUNT],
+            is_for_preset: true,
+        },
+    ]
+}
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginRemoteControls>();
+    }
+}
+
+struct MyPluginMainThread {
+    pages: Vec<RemoteControlsPage>,
+}
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginRemoteControlsImpl for MyPluginMainThread {
+    fn count(&mut self) -> u32 {
+        // Report one more page than we have, to check the host skips pages that failed.
+        self.pages.len() as u32 + 1
+    }
+
+    fn get(&mut self, page_index: u32, writer: &mut RemoteControlsPageWriter) {
+        if let Some(page) = self.pages.get(page_index as usize) {
+            writer.set(page);
+        }
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        mut host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        if let Some(remote_controls) = host.get_extension::<HostRemoteControls>() {
+            remote_controls.changed(&mut host);
+            remote_controls.suggest_page(&mut host, ClapId::new(7));
+        }
+
+        Ok(MyPluginMainThread { pages: pages() })
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostRemoteControls>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostMainThread {
+    changed_count: usize,
+    suggested_page: Option<ClapId>,
+}
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostRemoteControlsImpl for MyHostMainThread {
+    fn changed(&mut self) {
+        self.changed_count += 1;
+    }
+
+    fn suggest_page(&mut self, page_id: ClapId) {
+        self.suggested_page = Some(page_id);
+    }
+}
+
+fn instantiate() -> PluginInstance<MyHost> {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread::default(),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap()
+}
+
+#[test]
+fn host_fetches_all_pages() {
+    let mut instance = instantiate();
+    let mut plugin = instance.plugin_handle();
+    let ext = plugin.get_extension::<PluginRemoteControls>().unwrap();
+
+    assert_eq!(ext.count(&mut plugin), 3);
+    assert_eq!(ext.get(&mut plugin, 1), Some(pages().remove(1)));
+    assert_eq!(ext.get(&mut plugin, 2), None);
+    assert_eq!(ext.get_all_pages(&mut plugin), pages());
+}
+
+#[test]
+fn plugin_notifies_host() {
+    let instance = instantiate();
+
+    assert_eq!(instance.access_handler(|h| h.changed_count), 1);
+    assert_eq!(
+        instance.access_handler(|h| h.suggested_page),
+        Some(ClapId::new(7))
+    );
+}
@@ -0,0 +1,199 @@
+use clack_extensions::resource_directory::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginResourceDirectory>();
+    }
+}
+
+#[derive(Default)]
+struct MyPluginMainThread {
+    directory: Option<PathBuf>,
+    files: Vec<PathBuf>,
+}
+
+impl MyPluginMainThread {
+    fn scan_files(&mut self) {
+        self.files.clear();
+
+        if let Some(directory) = &self.directory {
+            for entry in fs::read_dir(directory).unwrap() {
+                self.files.push(entry.unwrap().path());
+            }
+        }
+
+        self.files.sort();
+    }
+}
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginResourceDirectoryImpl for MyPluginMainThread {
+    fn set_directory(&mut self, path: Option<&Path>, is_shared: bool) {
+        assert!(!is_shared);
+
+        self.directory = path.map(Path::to_path_buf);
+        self.scan_files();
+    }
+
+    fn collect(&mut self, _all: bool) {
+        // Simulates importing a sample into the project.
+        if let Some(directory) = &self.directory {
+            fs::write(directory.join("imported.wav"), b"RIFF").unwrap();
+        }
+
+        self.scan_files();
+    }
+
+    fn get_files_count(&mut self) -> u32 {
+        self.files.len() as u32
+    }
+
+    fn get_file_path(&mut self, index: u32) -> Option<PathBuf> {
+        self.files.get(index as usize).cloned()
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        mut host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        let resource_directory = host
+            .get_extension::<HostResourceDirectory>()
+            .ok_or(PluginError::Message("No resource directory"))?;
+
+        if !resource_directory.request_directory(&mut host, false) {
+            return Err(PluginError::Message("Resource directory was denied"));
+        }
+
+        Ok(MyPluginMainThread::default())
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostResourceDirectory>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostMainThread {
+    requested: Vec<bool>,
+}
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostResourceDirectoryImpl for MyHostMainThread {
+    fn request_directory(&mut self, is_shared: bool) -> bool {
+        self.requested.push(is_shared);
+        true
+    }
+
+    fn release_directory(&mut self, _is_shared: bool) {}
+}
+
+fn list_files(
+    resource_directory: &PluginResourceDirectory,
+    plugin: &mut PluginMainThreadHandle,
+) -> Vec<PathBuf> {
+    (0..resource_directory.get_files_count(plugin))
+        .map(|i| resource_directory.get_file_path(plugin, i).unwrap())
+        .collect()
+}
+
+#[test]
+fn plugin_follows_moved_directory() {
+    let root =
+        std::env::temp_dir().join(format!("clack-resource-directory-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+
+    let old_directory = root.join("old");
+    // Long enough for the host to have to retry with a larger path buffer.
+    let new_directory = (0..6).fold(root.join("moved"), |path, i| {
+        path.join(format!("{i}-{}", "x".repeat(200)))
+    });
+    fs::create_dir_all(&old_directory).unwrap();
+    fs::create_dir_all(new_directory.parent().unwrap()).unwrap();
+    fs::write(old_directory.join("existing.wav"), b"RIFF").unwrap();
+
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread::default(),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    instance.access_handler(|h| assert_eq!(h.requested, [false]));
+
+    let mut plugin = instance.plugin_handle();
+    let resource_directory = plugin.get_extension::<PluginResourceDirectory>().unwrap();
+
+    resource_directory
+        .set_directory(&mut plugin, Some(&old_directory), false)
+        .unwrap();
+    resource_directory.collect(&mut plugin, true);
+
+    assert_eq!(
+        list_files(&resource_directory, &mut plugin),
+        [
+            old_directory.join("existing.wav"),
+            old_directory.join("imported.wav")
+        ]
+    );
+
+    // The host moves the directory, e.g. because the project was saved elsewhere.
+    fs::rename(&old_directory, &new_directory).unwrap();
+    resource_directory
+        .set_directory(&mut plugin, Some(&new_directory), false)
+        .unwrap();
+
+    assert_eq!(
+        list_files(&resource_directory, &mut plugin),
+        [
+            new_directory.join("existing.wav"),
+            new_directory.join("imported.wav")
+        ]
+    );
+    assert!(resource_directory.get_file_path(&mut plugin, 2).is_none());
+
+    resource_directory
+        .set_directory(&mut plugin, None, false)
+        .unwrap();
+    assert_eq!(resource_directory.get_files_count(&mut plugin), 0);
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,159 @@
+use clack_host::prelude::*;
+use clack_host::scan::{self, PluginInfo, ScanError};
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::path::PathBuf;
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        use clack_plugin::plugin::features::*;
+
+        PluginDescriptor::new("my.plugin", "My plugin")
+            .with_vendor("Me")
+            .with_url("https://example.com")
+            .with_version("1.2.3")
+            .with_description("A plugin")
+            .with_features([AUDIO_EFFECT, STEREO])
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+/// Creates an empty, unique temporary directory for the given test.
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "clack-host-scan-{test_name}-{}",
+        std::process::id()
+    ));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn lists_plugins_from_bundle() {
+    let bundle =
+        unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin.clap") }.unwrap();
+
+    let plugins = PluginInfo::list_from_bundle(&bundle).unwrap();
+
+    assert_eq!(
+        plugins,
+        [PluginInfo {
+            id: "my.plugin".to_string(),
+            name: Some("My plugin".to_string()),
+            vendor: Some("Me".to_string()),
+            url: Some("https://example.com".to_string()),
+            manual_url: None,
+            support_url: None,
+            version: Some("1.2.3".to_string()),
+            description: Some("A plugin".to_string()),
+            features: vec!["audio-effect".to_string(), "stereo".to_string()],
+        }]
+    );
+}
+
+#[test]
+fn finds_bundles_recursively() {
+    let dir = temp_dir("find");
+    std::fs::create_dir_all(dir.join("vendor/nested")).unwrap();
+    std::fs::write(dir.join("top.clap"), b"").unwrap();
+    std::fs::write(dir.join("vendor/nested/deep.clap"), b"").unwrap();
+    std::fs::write(dir.join("vendor/readme.txt"), b"").unwrap();
+    std::fs::write(dir.join("vendor/not-a.clap.bak"), b"").unwrap();
+
+    let mut bundles = scan::find_bundles(&[dir.clone(), dir.join("missing")]);
+    bundles.sort();
+
+    assert_eq!(
+        bundles,
+        [dir.join("top.clap"), dir.join("vendor/nested/deep.clap")]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn finds_each_bundle_once() {
+    let dir = temp_dir("dedupe");
+    std::fs::create_dir_all(dir.join("vendor")).unwrap();
+    std::fs::write(dir.join("vendor/plugin.clap"), b"").unwrap();
+
+    // The same directory is reached both directly and through its parent.
+    let bundles = scan::find_bundles(&[dir.clone(), dir.join("vendor"), dir.clone()]);
+    assert_eq!(bundles, [dir.join("vendor/plugin.clap")]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_loops_are_not_followed_forever() {
+    let dir = temp_dir("symlink-loop");
+    std::fs::create_dir_all(dir.join("vendor")).unwrap();
+    std::fs::write(dir.join("vendor/plugin.clap"), b"").unwrap();
+
+    // vendor/loop points back to the search directory itself.
+    std::os::unix::fs::symlink(&dir, dir.join("vendor/loop")).unwrap();
+    // This points to a bundle that's already found through its real path.
+    std::os::unix::fs::symlink(dir.join("vendor/plugin.clap"), dir.join("link.clap")).unwrap();
+
+    let bundles = scan::find_bundles(std::slice::from_ref(&dir));
+    assert_eq!(bundles.len(), 1);
+    assert!(bundles[0].ends_with("plugin.clap") || bundles[0].ends_with("link.clap"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn catalogue_reports_load_errors() {
+    let dir = temp_dir("errors");
+    std::fs::write(dir.join("broken.clap"), b"not a library").unwrap();
+
+    let catalogue = unsafe { scan::scan_paths(std::slice::from_ref(&dir)) };
+
+    assert_eq!(catalogue.bundles.len(), 1);
+    assert_eq!(catalogue.plugins().count(), 0);
+    assert!(catalogue.find_plugin("my.plugin").is_none());
+
+    let errors: Vec<_> = catalogue.errors().collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, dir.join("broken.clap"));
+    assert!(matches!(errors[0].1, ScanError::LoadError(_)));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn search_paths_include_clap_path() {
+    let dir = temp_dir("clap-path");
+    let other = dir.join("other");
+
+    let clap_path = std::env::join_paths([&dir, &other]).unwrap();
+    std::env::set_var("CLAP_PATH", clap_path);
+
+    let paths = scan::standard_search_paths();
+    assert!(paths.ends_with(&[dir.clone(), other]));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
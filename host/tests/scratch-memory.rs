@@ -0,0 +1,188 @@
+use clack_extensions::scratch_memory::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+
+const SCRATCH_SIZE: u32 = 1024;
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = MyAudioProcessor<'a>;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+struct MyAudioProcessor<'a> {
+    host: HostAudioProcessorHandle<'a>,
+    scratch_memory: HostScratchMemory,
+    reservation: ScratchMemoryReservation,
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for MyAudioProcessor<'a> {
+    fn activate(
+        mut host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        let scratch_memory = host
+            .get_extension::<HostScratchMemory>()
+            .ok_or(PluginError::Message("No scratch memory"))?;
+
+        // The host only accepts reasonable sizes.
+        assert!(scratch_memory.reserve(&mut host, u32::MAX, 0).is_none());
+        let reservation = scratch_memory
+            .reserve(&mut host, SCRATCH_SIZE, 1)
+            .ok_or(PluginError::Message("Scratch memory reservation refused"))?;
+        assert_eq!(reservation.size(), SCRATCH_SIZE);
+
+        Ok(Self {
+            host,
+            scratch_memory,
+            reservation,
+        })
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        // SAFETY: This is the only reservation made during this activation.
+        let mut arena = unsafe {
+            self.scratch_memory
+                .access(&mut self.host, &self.reservation)
+        }
+        .ok_or(PluginError::Message("No scratch memory provided"))?;
+
+        let left = arena.alloc_f32(100).unwrap();
+        let bytes = arena.alloc_slice(3, 42u8).unwrap();
+        let right = arena.alloc_f32(100).unwrap();
+
+        assert_eq!(left.as_ptr() as usize % std::mem::align_of::<f32>(), 0);
+        assert_eq!(right.as_ptr() as usize % std::mem::align_of::<f32>(), 0);
+        assert_eq!(bytes, [42, 42, 42]);
+        assert!(right.iter().all(|s| *s == 0.0));
+
+        left.fill(1.0);
+        right.fill(-1.0);
+        assert!(left.iter().all(|s| *s == 1.0));
+
+        // 800 bytes were lent out, plus some padding.
+        assert!(arena.remaining() < 224);
+        assert!(arena.alloc_f32(100).is_none());
+        assert!(arena.alloc_f64(20).is_some());
+
+        Ok(ProcessStatus::Sleep)
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(_host: HostMainThreadHandle, _shared: &()) -> Result<(), PluginError> {
+        Ok(())
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = MyHostAudioProcessor;
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostScratchMemory>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostAudioProcessor {
+    memory: Vec<MaybeUninit<u8>>,
+    reserved: usize,
+}
+
+impl AudioProcessorHandler<'_> for MyHostAudioProcessor {}
+
+impl HostScratchMemoryImpl for MyHostAudioProcessor {
+    fn reserve(&mut self, scratch_size_bytes: u32, _max_concurrency_hint: u32) -> bool {
+        if scratch_size_bytes > 1024 * 1024 {
+            return false;
+        }
+
+        self.reserved = scratch_size_bytes as usize;
+        self.memory = vec![MaybeUninit::uninit(); self.reserved];
+        true
+    }
+
+    fn reserved_size(&self) -> u32 {
+        self.reserved as u32
+    }
+
+    fn access(&mut self) -> Option<&mut [MaybeUninit<u8>]> {
+        Some(&mut self.memory)
+    }
+}
+
+#[test]
+fn plugin_uses_host_scratch_memory() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 5,
+        max_frames_count: 5,
+    };
+
+    let processor = instance
+        .activate(|_, _| MyHostAudioProcessor::default(), config)
+        .unwrap();
+
+    processor.access_handler(|h| assert_eq!(h.reserved, SCRATCH_SIZE as usize));
+
+    let mut processor = processor.start_processing().unwrap();
+    let status = processor.process(
+        &InputAudioBuffers::empty(),
+        &mut OutputAudioBuffers::empty(),
+        &InputEvents::empty(),
+        &mut OutputEvents::void(),
+        None,
+        None,
+    );
+    assert_eq!(status.unwrap(), ProcessStatus::Sleep);
+
+    instance.deactivate(processor.stop_processing());
+}
+
+#[test]
+fn arena_over_plugin_memory() {
+    let mut memory = [MaybeUninit::uninit(); 64];
+    let mut arena = ScratchArena::new(&mut memory);
+
+    assert_eq!(arena.alloc_f32(8).unwrap(), [0.0; 8]);
+    assert!(arena.alloc_f32(16).is_none());
+    assert_eq!(arena.alloc_slice(0, 0u64).unwrap(), []);
+}
@@ -0,0 +1,215 @@
+use clack_extensions::state::*;
+use clack_extensions::state_context::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use clack_plugin::stream::{InputStream, OutputStream};
+use std::ffi::CStr;
+use std::io::{Read, Write};
+
+fn context_name(context_type: Option<StateContextType>) -> &'static [u8] {
+    match context_type {
+        None => b"plain",
+        Some(StateContextType::ForPreset) => b"preset",
+        Some(StateContextType::ForDuplicate) => b"duplicate",
+        Some(StateContextType::ForProject) => b"project",
+    }
+}
+
+#[derive(Default)]
+struct MyPluginMainThread {
+    loaded: Vec<u8>,
+}
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl MyPluginMainThread {
+    fn write_state(
+        &mut self,
+        output: &mut OutputStream,
+        context_type: Option<StateContextType>,
+    ) -> Result<(), PluginError> {
+        output.write_all(context_name(context_type))?;
+        Ok(())
+    }
+
+    fn read_state(
+        &mut self,
+        input: &mut InputStream,
+        context_type: Option<StateContextType>,
+    ) -> Result<(), PluginError> {
+        self.loaded.clear();
+        input.read_to_end(&mut self.loaded)?;
+
+        if self.loaded != context_name(context_type) {
+            return Err(PluginError::Message("Unexpected state context"));
+        }
+
+        Ok(())
+    }
+}
+
+impl PluginStateImpl for MyPluginMainThread {
+    fn save(&mut self, output: &mut OutputStream) -> Result<(), PluginError> {
+        self.write_state(output, None)
+    }
+
+    fn load(&mut self, input: &mut InputStream) -> Result<(), PluginError> {
+        self.read_state(input, None)
+    }
+}
+
+impl PluginStateContextImpl for MyPluginMainThread {
+    fn save(
+        &mut self,
+        output: &mut OutputStream,
+        context_type: StateContextType,
+    ) -> Result<(), PluginError> {
+        self.write_state(output, Some(context_type))
+    }
+
+    fn load(
+        &mut self,
+        input: &mut InputStream,
+        context_type: StateContextType,
+    ) -> Result<(), PluginError> {
+        self.read_state(input, Some(context_type))
+    }
+}
+
+struct ContextPlugin;
+
+impl Plugin for ContextPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder
+            .register::<PluginState>()
+            .register::<PluginStateContext>();
+    }
+}
+
+impl DefaultPluginFactory for ContextPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread::default())
+    }
+}
+
+struct PlainPlugin;
+
+impl Plugin for PlainPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginState>();
+    }
+}
+
+impl DefaultPluginFactory for PlainPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread::default())
+    }
+}
+
+static CONTEXT_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<ContextPlugin>);
+static PLAIN_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<PlainPlugin>);
+
+fn instantiate(entry: &'static EntryDescriptor) -> PluginInstance<()> {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(entry, "/my/plugin") }.unwrap();
+
+    PluginInstance::<()>::new(
+        |_| (),
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap()
+}
+
+#[test]
+fn context_is_passed_to_plugin() {
+    let mut instance = instantiate(&CONTEXT_PLUGIN_ENTRY);
+    let mut plugin = instance.plugin_handle();
+    let state: PluginState = plugin.get_extension().unwrap();
+    let state_context: PluginStateContext = plugin.get_extension().unwrap();
+
+    let mut buffer = Vec::new();
+    state_context
+        .save(&mut plugin, &mut buffer, StateContextType::ForPreset)
+        .unwrap();
+    assert_eq!(buffer, b"preset");
+
+    state_context
+        .load(&mut plugin, &mut &buffer[..], StateContextType::ForPreset)
+        .unwrap();
+    assert!(state_context
+        .load(
+            &mut plugin,
+            &mut &buffer[..],
+            StateContextType::ForDuplicate
+        )
+        .is_err());
+
+    let mut buffer = Vec::new();
+    state
+        .save_with_context(&mut plugin, &mut buffer, StateContextType::ForDuplicate)
+        .unwrap();
+    assert_eq!(buffer, b"duplicate");
+
+    let mut buffer = Vec::new();
+    state
+        .save_with_context(&mut plugin, &mut buffer, StateContextType::ForProject)
+        .unwrap();
+    assert_eq!(buffer, b"project");
+
+    state
+        .load_with_context(&mut plugin, &mut &buffer[..], StateContextType::ForProject)
+        .unwrap();
+}
+
+#[test]
+fn host_falls_back_to_plain_state() {
+    let mut instance = instantiate(&PLAIN_PLUGIN_ENTRY);
+    let mut plugin = instance.plugin_handle();
+    let state: PluginState = plugin.get_extension().unwrap();
+
+    assert!(plugin.get_extension::<PluginStateContext>().is_none());
+
+    let mut buffer = Vec::new();
+    state
+        .save_with_context(&mut plugin, &mut buffer, StateContextType::ForPreset)
+        .unwrap();
+    assert_eq!(buffer, b"plain");
+
+    state
+        .load_with_context(&mut plugin, &mut &buffer[..], StateContextType::ForPreset)
+        .unwrap();
+}
@@ -0,0 +1,147 @@
+use clack_extensions::audio_ports::AudioPortType;
+use clack_extensions::track_info::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use clack_plugin::utils::Color;
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+
+static RECEIVED_INFOS: Mutex<Vec<Option<TrackInfo>>> = Mutex::new(Vec::new());
+
+fn track_info() -> TrackInfo {
+    TrackInfo {
+        name: Some("Drums".into()),
+        color: Some(Color::from_rgb(0x12, 0x34, 0x56)),
+        audio_channel_count: Some(2),
+        audio_port_type: Some(CString::from(AudioPortType::STEREO.0)),
+        flags: TrackInfoFlags::IS_FOR_BUS,
+    }
+}
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread<'a>;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginTrackInfo>();
+    }
+}
+
+struct MyPluginMainThread<'a> {
+    host: HostMainThreadHandle<'a>,
+}
+
+impl<'a> PluginMainThread<'a, ()> for MyPluginMainThread<'a> {}
+
+impl MyPluginMainThread<'_> {
+    fn fetch_track_info(&mut self) {
+        let info = self
+            .host
+            .get_extension::<HostTrackInfo>()
+            .and_then(|ext| ext.get(&mut self.host));
+
+        RECEIVED_INFOS.lock().unwrap().push(info);
+    }
+}
+
+impl PluginTrackInfoImpl for MyPluginMainThread<'_> {
+    fn changed(&mut self) {
+        self.fetch_track_info();
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        host: HostMainThreadHandle<'a>,
+        _shared: &'a (),
+    ) -> Result<MyPluginMainThread<'a>, PluginError> {
+        let mut main_thread = MyPluginMainThread { host };
+        main_thread.fetch_track_info();
+        Ok(main_thread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostTrackInfo>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostMainThread {
+    track_info: Option<TrackInfo>,
+}
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostTrackInfoImpl for MyHostMainThread {
+    fn get(&mut self) -> Option<&TrackInfo> {
+        self.track_info.as_ref()
+    }
+}
+
+#[test]
+fn plugin_receives_track_info() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread::default(),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    instance.access_handler_mut(|h| h.track_info = Some(track_info()));
+
+    let mut plugin = instance.plugin_handle();
+    let ext = plugin.get_extension::<PluginTrackInfo>().unwrap();
+    ext.changed(&mut plugin);
+
+    instance.access_handler_mut(|h| {
+        h.track_info = Some(TrackInfo {
+            flags: TrackInfoFlags::IS_FOR_MASTER,
+            ..TrackInfo::default()
+        })
+    });
+    let mut plugin = instance.plugin_handle();
+    ext.changed(&mut plugin);
+
+    let received = RECEIVED_INFOS.lock().unwrap();
+    assert_eq!(received.len(), 3);
+    assert_eq!(received[0], None);
+    assert_eq!(received[1], Some(track_info()));
+    assert_eq!(
+        received[1].as_ref().unwrap().audio_port_type(),
+        Some(AudioPortType::STEREO)
+    );
+    assert_eq!(
+        received[2],
+        Some(TrackInfo {
+            flags: TrackInfoFlags::IS_FOR_MASTER,
+            ..TrackInfo::default()
+        })
+    );
+}
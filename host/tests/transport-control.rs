@@ -0,0 +1,152 @@
+use clack_extensions::transport_control::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use clack_plugin::utils::BeatTime;
+use std::ffi::CStr;
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(mut host: HostMainThreadHandle, _shared: &()) -> Result<(), PluginError> {
+        let transport = host
+            .get_extension::<HostTransportControl>()
+            .ok_or(PluginError::Message("No transport control"))?;
+
+        transport.request_start(&mut host);
+        transport.request_jump(&mut host, BeatTime::from_int(4));
+        transport.request_loop_region(&mut host, BeatTime::from_int(8), BeatTime::from_float(2.5));
+        transport.request_enable_loop(&mut host, true);
+        transport.request_record(&mut host, false);
+        transport.request_toggle_record(&mut host);
+        transport.request_stop(&mut host);
+
+        Ok(())
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+#[derive(Debug, PartialEq)]
+enum Request {
+    Start,
+    Stop,
+    Continue,
+    Pause,
+    TogglePlay,
+    Jump(BeatTime),
+    LoopRegion(BeatTime, BeatTime),
+    ToggleLoop,
+    EnableLoop(bool),
+    Record(bool),
+    ToggleRecord,
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostTransportControl>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostMainThread {
+    requests: Vec<Request>,
+}
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostTransportControlImpl for MyHostMainThread {
+    fn request_start(&mut self) {
+        self.requests.push(Request::Start);
+    }
+
+    fn request_stop(&mut self) {
+        self.requests.push(Request::Stop);
+    }
+
+    fn request_continue(&mut self) {
+        self.requests.push(Request::Continue);
+    }
+
+    fn request_pause(&mut self) {
+        self.requests.push(Request::Pause);
+    }
+
+    fn request_toggle_play(&mut self) {
+        self.requests.push(Request::TogglePlay);
+    }
+
+    fn request_jump(&mut self, position: BeatTime) {
+        self.requests.push(Request::Jump(position));
+    }
+
+    fn request_loop_region(&mut self, start: BeatTime, duration: BeatTime) {
+        self.requests.push(Request::LoopRegion(start, duration));
+    }
+
+    fn request_toggle_loop(&mut self) {
+        self.requests.push(Request::ToggleLoop);
+    }
+
+    fn request_enable_loop(&mut self, is_enabled: bool) {
+        self.requests.push(Request::EnableLoop(is_enabled));
+    }
+
+    fn request_record(&mut self, is_recording: bool) {
+        self.requests.push(Request::Record(is_recording));
+    }
+
+    fn request_toggle_record(&mut self) {
+        self.requests.push(Request::ToggleRecord);
+    }
+}
+
+#[test]
+fn host_receives_transport_requests() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread::default(),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    instance.access_handler(|h| {
+        assert_eq!(
+            h.requests,
+            [
+                Request::Start,
+                Request::Jump(BeatTime::from_int(4)),
+                Request::LoopRegion(BeatTime::from_int(8), BeatTime::from_float(2.5)),
+                Request::EnableLoop(true),
+                Request::Record(false),
+                Request::ToggleRecord,
+                Request::Stop,
+            ]
+        )
+    });
+}
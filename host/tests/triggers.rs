@@ -0,0 +1,163 @@
+use clack_extensions::triggers::*;
+use clack_host::events::io::EventBuffer;
+use clack_host::events::spaces::EventSpaceId;
+use clack_host::events::{Match, Pckn};
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use clack_plugin::utils::Cookie;
+use std::ffi::CStr;
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginTriggers>();
+    }
+}
+
+struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginTriggersImpl for MyPluginMainThread {
+    fn count(&mut self) -> u32 {
+        2
+    }
+
+    fn get_info(&mut self, trigger_index: u32, info: &mut TriggerInfoWriter) {
+        if trigger_index == 0 {
+            info.set(&TriggerInfo {
+                id: ClapId::new(42),
+                flags: TriggerInfoFlags::IS_AUTOMATABLE_PER_KEY,
+                cookie: Cookie::empty(),
+                name: b"Kick",
+                module: b"drums/kick",
+            })
+        }
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        mut host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        if let Some(triggers) = host.get_extension::<HostTriggers>() {
+            triggers.rescan(&mut host, TriggerRescanFlags::INFO);
+            triggers.clear(&mut host, ClapId::new(42), TriggerClearFlags::AUTOMATIONS);
+        }
+
+        Ok(MyPluginMainThread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostTriggers>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostMainThread {
+    rescanned: Option<TriggerRescanFlags>,
+    cleared: Option<(ClapId, TriggerClearFlags)>,
+}
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostTriggersImpl for MyHostMainThread {
+    fn rescan(&mut self, flags: TriggerRescanFlags) {
+        self.rescanned = Some(flags);
+    }
+
+    fn clear(&mut self, trigger_id: ClapId, flags: TriggerClearFlags) {
+        self.cleared = Some((trigger_id, flags));
+    }
+}
+
+fn instantiate() -> PluginInstance<MyHost> {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread::default(),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap()
+}
+
+#[test]
+fn host_lists_triggers() {
+    let mut instance = instantiate();
+
+    instance.access_handler(|h| {
+        assert_eq!(h.rescanned, Some(TriggerRescanFlags::INFO));
+        assert_eq!(
+            h.cleared,
+            Some((ClapId::new(42), TriggerClearFlags::AUTOMATIONS))
+        );
+    });
+
+    let mut plugin = instance.plugin_handle();
+    let triggers = plugin.get_extension::<PluginTriggers>().unwrap();
+    let mut buffer = TriggerInfoBuffer::new();
+
+    assert_eq!(triggers.count(&mut plugin), 2);
+
+    let info = triggers.get_info(&mut plugin, 0, &mut buffer).unwrap();
+    assert_eq!(info.id, ClapId::new(42));
+    assert_eq!(info.flags, TriggerInfoFlags::IS_AUTOMATABLE_PER_KEY);
+    assert_eq!(info.name, b"Kick");
+    assert_eq!(info.module, b"drums/kick");
+
+    assert!(triggers.get_info(&mut plugin, 1, &mut buffer).is_none());
+}
+
+#[test]
+fn trigger_events_use_their_own_space() {
+    // SAFETY: this ID is only used for triggers in this test.
+    let space_id = unsafe { EventSpaceId::new(1000).unwrap().into_unchecked() };
+
+    let pckn = Pckn::new(0u16, Match::All, 36u16, Match::All);
+    let event = TriggerEvent::new(space_id, 5, ClapId::new(42), pckn, Cookie::empty());
+
+    let mut buffer = EventBuffer::new();
+    buffer.push(&event);
+    let input = InputEvents::from_buffer(&buffer);
+
+    let received = input.iter().next().unwrap();
+    assert!(received.as_core_event().is_none());
+
+    let Some(TriggersEventSpace::Trigger(trigger)) = received.as_event_space(space_id) else {
+        panic!("Expected a trigger event");
+    };
+
+    assert_eq!(trigger, &event);
+    assert_eq!(trigger.trigger_id(), Some(ClapId::new(42)));
+    assert_eq!(trigger.pckn(), pckn);
+    assert_eq!(trigger.time(), 5);
+}
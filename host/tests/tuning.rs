@@ -0,0 +1,169 @@
+use clack_extensions::tuning::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+const JUST_INTONATION: ClapId = ClapId::new(7);
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = MyAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+}
+
+struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+struct MyAudioProcessor;
+
+impl<'a> PluginAudioProcessor<'a, (), MyPluginMainThread> for MyAudioProcessor {
+    fn activate(
+        mut host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut MyPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        let tuning = host
+            .get_extension::<HostTuning>()
+            .ok_or(PluginError::Message("No tuning"))?;
+
+        let relative = tuning.get_relative(&mut host, Some(JUST_INTONATION), 0, 64, 12);
+        assert_eq!(relative, -0.14);
+        assert_eq!(tuning.get_relative(&mut host, None, 0, 64, 12), 0.0);
+
+        assert!(tuning.should_play(&mut host, Some(JUST_INTONATION), 0, 64));
+        assert!(!tuning.should_play(&mut host, Some(JUST_INTONATION), 0, 65));
+
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        Ok(ProcessStatus::Sleep)
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        mut host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        let tuning = host
+            .get_extension::<HostTuning>()
+            .ok_or(PluginError::Message("No tuning"))?;
+
+        assert_eq!(tuning.get_tuning_count(&mut host), 2);
+
+        let mut buffer = TuningInfoBuffer::new();
+        let info = tuning.get_info(&mut host, 0, &mut buffer).unwrap();
+        assert_eq!(info.tuning_id, JUST_INTONATION);
+        assert_eq!(info.name, b"Just intonation");
+        assert!(!info.is_dynamic);
+
+        assert!(tuning.get_info(&mut host, 1, &mut buffer).is_none());
+
+        Ok(MyPluginMainThread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = MyHostAudioProcessor;
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostTuning>();
+    }
+}
+
+struct MyHostMainThread;
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostTuningImpl for MyHostMainThread {
+    fn get_tuning_count(&mut self) -> u32 {
+        // Report one more tuning than we have, to check the plugin handles failures.
+        2
+    }
+
+    fn get_info(&mut self, tuning_index: u32, info: &mut TuningInfoWriter) {
+        if tuning_index == 0 {
+            info.set(&TuningInfo {
+                tuning_id: JUST_INTONATION,
+                name: b"Just intonation",
+                is_dynamic: false,
+            });
+        }
+    }
+}
+
+struct MyHostAudioProcessor;
+
+impl AudioProcessorHandler<'_> for MyHostAudioProcessor {}
+
+impl HostTuningAudioProcessorImpl for MyHostAudioProcessor {
+    fn get_relative(
+        &mut self,
+        tuning_id: Option<ClapId>,
+        _channel: u16,
+        key: u16,
+        sample_offset: u32,
+    ) -> f64 {
+        assert_eq!(sample_offset, 12);
+
+        match (tuning_id, key) {
+            (Some(JUST_INTONATION), 64) => -0.14,
+            _ => 0.0,
+        }
+    }
+
+    fn should_play(&mut self, tuning_id: Option<ClapId>, _channel: u16, key: u16) -> bool {
+        tuning_id != Some(JUST_INTONATION) || key != 65
+    }
+}
+
+#[test]
+fn plugin_queries_host_tunings() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread,
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 5,
+        max_frames_count: 5,
+    };
+
+    let processor = instance
+        .activate(|_, _| MyHostAudioProcessor, config)
+        .unwrap();
+    instance.deactivate(processor);
+}
@@ -0,0 +1,276 @@
+use clack_extensions::undo::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+
+static APPLIED_VALUES: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+static CONTEXT_UPDATES: Mutex<Vec<ContextUpdate>> = Mutex::new(Vec::new());
+
+#[derive(Debug, PartialEq)]
+enum ContextUpdate {
+    CanUndo(bool),
+    CanRedo(bool),
+    UndoName(Option<CString>),
+    RedoName(Option<CString>),
+}
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder
+            .register::<PluginUndoDelta>()
+            .register::<PluginUndoContext>();
+    }
+}
+
+struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl PluginUndoDeltaImpl for MyPluginMainThread {
+    fn get_delta_properties(&mut self) -> UndoDeltaProperties {
+        UndoDeltaProperties {
+            has_delta: true,
+            are_deltas_persistent: false,
+            format_version: Some(ClapId::new(1)),
+        }
+    }
+
+    fn can_use_delta_format_version(&mut self, format_version: ClapId) -> bool {
+        format_version == ClapId::new(1)
+    }
+
+    fn undo(&mut self, _format_version: ClapId, delta: &[u8]) -> Result<(), PluginError> {
+        let [old, _new] = delta else {
+            return Err(PluginError::Message("Invalid delta"));
+        };
+
+        APPLIED_VALUES.lock().unwrap().push(*old);
+        Ok(())
+    }
+
+    fn redo(&mut self, _format_version: ClapId, delta: &[u8]) -> Result<(), PluginError> {
+        let [_old, new] = delta else {
+            return Err(PluginError::Message("Invalid delta"));
+        };
+
+        APPLIED_VALUES.lock().unwrap().push(*new);
+        Ok(())
+    }
+}
+
+impl PluginUndoContextImpl for MyPluginMainThread {
+    fn set_can_undo(&mut self, can_undo: bool) {
+        let update = ContextUpdate::CanUndo(can_undo);
+        CONTEXT_UPDATES.lock().unwrap().push(update);
+    }
+
+    fn set_can_redo(&mut self, can_redo: bool) {
+        let update = ContextUpdate::CanRedo(can_redo);
+        CONTEXT_UPDATES.lock().unwrap().push(update);
+    }
+
+    fn set_undo_name(&mut self, name: Option<&CStr>) {
+        let update = ContextUpdate::UndoName(name.map(CStr::to_owned));
+        CONTEXT_UPDATES.lock().unwrap().push(update);
+    }
+
+    fn set_redo_name(&mut self, name: Option<&CStr>) {
+        let update = ContextUpdate::RedoName(name.map(CStr::to_owned));
+        CONTEXT_UPDATES.lock().unwrap().push(update);
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        mut host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        let undo = host
+            .get_extension::<HostUndo>()
+            .ok_or(PluginError::Message("No undo"))?;
+
+        undo.set_wants_context_updates(&mut host, true);
+        undo.begin_change(&mut host);
+        undo.cancel_change(&mut host);
+        undo.begin_change(&mut host);
+        undo.change_made(
+            &mut host,
+            CStr::from_bytes_with_nul(b"Set value\0").unwrap(),
+            Some(&[0, 5]),
+            true,
+        );
+        undo.change_made(
+            &mut host,
+            CStr::from_bytes_with_nul(b"Reset\0").unwrap(),
+            None,
+            false,
+        );
+        undo.request_undo(&mut host);
+        undo.request_redo(&mut host);
+
+        Ok(MyPluginMainThread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+#[derive(Debug, PartialEq)]
+enum Call {
+    BeginChange,
+    CancelChange,
+    ChangeMade(CString, Option<UndoDelta>, bool),
+    RequestUndo,
+    RequestRedo,
+    SetWantsContextUpdates(bool),
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = ();
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostUndo>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostMainThread {
+    calls: Vec<Call>,
+}
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostUndoImpl for MyHostMainThread {
+    fn begin_change(&mut self) {
+        self.calls.push(Call::BeginChange);
+    }
+
+    fn cancel_change(&mut self) {
+        self.calls.push(Call::CancelChange);
+    }
+
+    fn change_made(&mut self, name: &CStr, delta: Option<UndoDelta>, delta_can_undo: bool) {
+        self.calls
+            .push(Call::ChangeMade(name.to_owned(), delta, delta_can_undo));
+    }
+
+    fn request_undo(&mut self) {
+        self.calls.push(Call::RequestUndo);
+    }
+
+    fn request_redo(&mut self) {
+        self.calls.push(Call::RequestRedo);
+    }
+
+    fn set_wants_context_updates(&mut self, is_subscribed: bool) {
+        self.calls.push(Call::SetWantsContextUpdates(is_subscribed));
+    }
+}
+
+fn instantiate() -> PluginInstance<MyHost> {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    PluginInstance::<MyHost>::new(
+        |_| (),
+        |_| MyHostMainThread::default(),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap()
+}
+
+#[test]
+fn host_receives_undo_calls() {
+    let mut instance = instantiate();
+
+    instance.access_handler(|h| {
+        assert_eq!(
+            h.calls,
+            [
+                Call::SetWantsContextUpdates(true),
+                Call::BeginChange,
+                Call::CancelChange,
+                Call::BeginChange,
+                Call::ChangeMade(
+                    CString::new("Set value").unwrap(),
+                    Some(UndoDelta::from(vec![0, 5])),
+                    true
+                ),
+                Call::ChangeMade(CString::new("Reset").unwrap(), None, false),
+                Call::RequestUndo,
+                Call::RequestRedo,
+            ]
+        )
+    });
+
+    let mut plugin = instance.plugin_handle();
+    let context = plugin.get_extension::<PluginUndoContext>().unwrap();
+
+    context.set_can_undo(&mut plugin, true);
+    context.set_can_redo(&mut plugin, false);
+    context.set_undo_name(
+        &mut plugin,
+        Some(CStr::from_bytes_with_nul(b"Set value\0").unwrap()),
+    );
+    context.set_redo_name(&mut plugin, None);
+
+    assert_eq!(
+        *CONTEXT_UPDATES.lock().unwrap(),
+        [
+            ContextUpdate::CanUndo(true),
+            ContextUpdate::CanRedo(false),
+            ContextUpdate::UndoName(Some(CString::new("Set value").unwrap())),
+            ContextUpdate::RedoName(None),
+        ]
+    );
+}
+
+#[test]
+fn host_applies_undo_deltas() {
+    let mut instance = instantiate();
+
+    let delta = instance.access_handler_mut(|h| match h.calls.remove(4) {
+        Call::ChangeMade(_, Some(delta), true) => delta,
+        call => panic!("Unexpected call: {call:?}"),
+    });
+
+    let mut plugin = instance.plugin_handle();
+    let undo = plugin.get_extension::<PluginUndoDelta>().unwrap();
+
+    let properties = undo.get_delta_properties(&mut plugin);
+    assert!(properties.has_delta);
+    assert!(!properties.are_deltas_persistent);
+
+    let format_version = properties.format_version.unwrap();
+    assert!(undo.can_use_delta_format_version(&mut plugin, format_version));
+    assert!(!undo.can_use_delta_format_version(&mut plugin, ClapId::new(2)));
+
+    undo.undo(&mut plugin, format_version, delta.as_bytes())
+        .unwrap();
+    undo.redo(&mut plugin, format_version, delta.as_bytes())
+        .unwrap();
+    assert!(undo.undo(&mut plugin, format_version, &[]).is_err());
+
+    assert_eq!(*APPLIED_VALUES.lock().unwrap(), [0, 5]);
+}
@@ -0,0 +1,182 @@
+use clack_extensions::gui::GuiApiType;
+use clack_extensions::webview::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+const INDEX_HTML: &[u8] = b"<!DOCTYPE html><html><body>Hello</body></html>";
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = MyPluginShared;
+    type MainThread<'a> = MyPluginMainThread<'a>;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&MyPluginShared>) {
+        builder.register::<PluginWebview>();
+    }
+}
+
+struct MyPluginShared;
+
+impl PluginShared<'_> for MyPluginShared {}
+
+impl PluginWebviewResourcesImpl for MyPluginShared {
+    fn get_resource(&self, path: &str) -> Option<WebviewResource<'_>> {
+        match path {
+            "/index.html" => Some(WebviewResource::new("text/html", INDEX_HTML)),
+            "/data.json" => {
+                let content = format!("{{\"gain\": {}}}", 0.5);
+                Some(WebviewResource::new(
+                    "application/json",
+                    content.into_bytes(),
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+struct MyPluginMainThread<'a> {
+    host: HostMainThreadHandle<'a>,
+    uri: String,
+}
+
+impl<'a> PluginMainThread<'a, MyPluginShared> for MyPluginMainThread<'a> {}
+
+impl PluginWebviewImpl for MyPluginMainThread<'_> {
+    fn get_uri(&mut self) -> Option<&str> {
+        Some(&self.uri)
+    }
+
+    fn receive(&mut self, message: &[u8]) -> Result<(), PluginError> {
+        if message != b"ping" {
+            return Err(PluginError::Message("Unknown message"));
+        }
+
+        let webview = self
+            .host
+            .get_extension::<HostWebview>()
+            .ok_or(PluginError::Message("No host webview"))?;
+
+        webview
+            .send(&self.host, b"pong")
+            .map_err(|_| PluginError::Message("Failed to send reply"))
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<MyPluginShared, PluginError> {
+        Ok(MyPluginShared)
+    }
+
+    fn new_main_thread<'a>(
+        host: HostMainThreadHandle<'a>,
+        _shared: &'a MyPluginShared,
+    ) -> Result<MyPluginMainThread<'a>, PluginError> {
+        // Longer than the host's initial buffer.
+        let uri = format!("file:///{}/index.html", "a".repeat(2000));
+        Ok(MyPluginMainThread { host, uri })
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostWebview>();
+    }
+}
+
+#[derive(Default)]
+struct MyHostShared {
+    sent_messages: Mutex<Vec<Vec<u8>>>,
+}
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {
+        unimplemented!()
+    }
+    fn request_process(&self) {
+        unimplemented!()
+    }
+    fn request_callback(&self) {
+        unimplemented!()
+    }
+}
+
+impl HostWebviewImpl for MyHostShared {
+    fn send(&self, message: &[u8]) -> Result<(), HostError> {
+        self.sent_messages.lock().unwrap().push(message.to_vec());
+        Ok(())
+    }
+}
+
+#[test]
+fn webview_api_type_is_standard() {
+    let api_type = GuiApiType(CStr::from_bytes_with_nul(b"webview\0").unwrap());
+
+    assert_eq!(api_type.to_standard_api(), Some(GuiApiType::WEBVIEW));
+    assert!(GuiApiType::WEBVIEW.uses_logical_size());
+    assert!(!GuiApiType::WEBVIEW.can_provide_raw_window_handle());
+}
+
+#[test]
+fn host_serves_plugin_webview() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared::default(),
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let webview = plugin.get_extension::<PluginWebview>().unwrap();
+
+    let uri = webview.get_uri(&mut plugin).unwrap();
+    assert!(uri.starts_with("file:///aaa"));
+    assert!(uri.ends_with("/index.html"));
+    assert_eq!(uri.len(), 2019);
+
+    let shared = instance.plugin_shared_handle();
+    let index = webview.get_resource(&shared, "/index.html").unwrap();
+    assert_eq!(index.mime_type, "text/html");
+    assert_eq!(index.content, INDEX_HTML);
+
+    let data = webview.get_resource(&shared, "/data.json").unwrap();
+    assert_eq!(
+        data,
+        WebviewResource::new("application/json", &b"{\"gain\": 0.5}"[..])
+    );
+
+    assert_eq!(webview.get_resource(&shared, "/missing.png"), None);
+
+    let mut plugin = instance.plugin_handle();
+    webview.receive(&mut plugin, b"ping").unwrap();
+    assert_eq!(
+        webview.receive(&mut plugin, b"unknown"),
+        Err(WebviewError::ReceiveError)
+    );
+
+    instance.access_shared_handler(|h| {
+        assert_eq!(*h.sent_messages.lock().unwrap(), [b"pong".to_vec()]);
+    });
+}
@@ -4,9 +4,14 @@
 
 use crate::params::{PolySynthParamModulations, PolySynthParams};
 use crate::poly_oscillator::PolyOscillator;
+use clack_extensions::event_registry::HostEventRegistry;
 use clack_extensions::state::PluginState;
+use clack_extensions::track_info::*;
+use clack_extensions::tuning::*;
 use clack_extensions::{audio_ports::*, note_ports::*, params::*};
-use clack_plugin::events::spaces::CoreEventSpace;
+use clack_plugin::events::event_types::NoteOnEvent;
+use clack_plugin::events::spaces::{CoreEventSpace, EventSpaceId};
+use clack_plugin::events::Match;
 use clack_plugin::prelude::*;
 
 mod oscillator;
@@ -31,7 +36,8 @@ impl Plugin for PolySynthPlugin {
             .register::<PluginAudioPorts>()
             .register::<PluginNotePorts>()
             .register::<PluginParams>()
-            .register::<PluginState>();
+            .register::<PluginState>()
+            .register::<PluginTrackInfo>();
     }
 }
 
@@ -50,10 +56,24 @@ impl DefaultPluginFactory for PolySynthPlugin {
     }
 
     fn new_main_thread<'a>(
-        _host: HostMainThreadHandle<'a>,
+        mut host: HostMainThreadHandle<'a>,
         shared: &'a PolySynthPluginShared,
     ) -> Result<PolySynthPluginMainThread<'a>, PluginError> {
-        Ok(PolySynthPluginMainThread { shared })
+        let host_track_info = host.get_extension::<HostTrackInfo>();
+        let track_info = host_track_info.and_then(|ext| ext.get(&mut host));
+
+        // Tuning events don't live in the core event space, so we need to ask the host for its ID.
+        let tuning_space_id = host
+            .get_extension::<HostEventRegistry>()
+            .and_then(|registry| registry.query(&host));
+
+        Ok(PolySynthPluginMainThread {
+            shared,
+            host,
+            host_track_info,
+            track_info,
+            tuning_space_id,
+        })
     }
 }
 
@@ -67,14 +87,20 @@ pub struct PolySynthAudioProcessor<'a> {
     modulation_values: PolySynthParamModulations,
     /// A reference to the plugin's shared data.
     shared: &'a PolySynthPluginShared,
+    /// A handle to the host, to query tunings from the audio thread.
+    host: HostAudioProcessorHandle<'a>,
+    /// The host's Tuning extension, if it supports it.
+    host_tuning: Option<HostTuning>,
+    /// The ID of the tuning event space, if the host supports it.
+    tuning_space_id: Option<EventSpaceId<TuningEventSpace<'static>>>,
 }
 
 impl<'a> PluginAudioProcessor<'a, PolySynthPluginShared, PolySynthPluginMainThread<'a>>
     for PolySynthAudioProcessor<'a>
 {
     fn activate(
-        _host: HostAudioProcessorHandle<'a>,
-        _main_thread: &mut PolySynthPluginMainThread,
+        host: HostAudioProcessorHandle<'a>,
+        main_thread: &mut PolySynthPluginMainThread,
         shared: &'a PolySynthPluginShared,
         audio_config: PluginAudioConfiguration,
     ) -> Result<Self, PluginError> {
@@ -82,6 +108,9 @@ impl<'a> PluginAudioProcessor<'a, PolySynthPluginShared, PolySynthPluginMainThre
             poly_osc: PolyOscillator::new(16, audio_config.sample_rate as f32),
             modulation_values: PolySynthParamModulations::new(),
             shared,
+            host_tuning: host.get_extension(),
+            host,
+            tuning_space_id: main_thread.tuning_space_id,
         })
     }
 
@@ -157,8 +186,19 @@ impl<'a> PluginAudioProcessor<'a, PolySynthPluginShared, PolySynthPluginMainThre
 impl PolySynthAudioProcessor<'_> {
     /// Handles an incoming event.
     fn handle_event(&mut self, event: &UnknownEvent) {
+        if let Some(space_id) = self.tuning_space_id {
+            if let Some(TuningEventSpace::RelativeTuning(event)) = event.as_event_space(space_id) {
+                self.poly_osc.handle_relative_tuning(event);
+                return;
+            }
+        }
+
         match event.as_core_event() {
-            Some(CoreEventSpace::NoteOn(event)) => self.poly_osc.handle_note_on(event),
+            Some(CoreEventSpace::NoteOn(event)) => {
+                if let Some(relative_tuning) = self.note_tuning(event) {
+                    self.poly_osc.handle_note_on(event, relative_tuning)
+                }
+            }
             Some(CoreEventSpace::NoteOff(event)) => self.poly_osc.handle_note_off(event),
             Some(CoreEventSpace::ParamValue(event)) => {
                 // This is a global modulation event
@@ -179,6 +219,30 @@ impl PolySynthAudioProcessor<'_> {
             _ => {}
         }
     }
+
+    /// Returns the tuning offset (in semitones) the host wants the given note to be played at, or
+    /// `None` if the host's tuning says it shouldn't be played at all.
+    fn note_tuning(&mut self, event: &NoteOnEvent) -> Option<f32> {
+        let (Match::Specific(channel), Match::Specific(key)) = (event.channel(), event.key())
+        else {
+            return Some(0.0);
+        };
+
+        let (Some(host_tuning), Some(tuning_id)) =
+            (self.host_tuning, self.poly_osc.channel_tuning(channel))
+        else {
+            return Some(0.0);
+        };
+
+        if !host_tuning.should_play(&mut self.host, Some(tuning_id), channel, key) {
+            return None;
+        }
+
+        let relative_tuning =
+            host_tuning.get_relative(&mut self.host, Some(tuning_id), channel, key, event.time());
+
+        Some(relative_tuning as f32)
+    }
 }
 
 impl PluginAudioPortsImpl for PolySynthPluginMainThread<'_> {
@@ -192,9 +256,16 @@ impl PluginAudioPortsImpl for PolySynthPluginMainThread<'_> {
 
     fn get(&mut self, index: u32, is_input: bool, writer: &mut AudioPortInfoWriter) {
         if !is_input && index == 0 {
+            // Name our output after the track we're on, if the host told us about it.
+            let name = self
+                .track_info
+                .as_ref()
+                .and_then(|info| info.name.as_deref())
+                .unwrap_or("main");
+
             writer.set(&AudioPortInfo {
                 id: ClapId::new(1),
-                name: b"main",
+                name: name.as_bytes(),
                 channel_count: 1,
                 flags: AudioPortFlags::IS_MAIN,
                 port_type: Some(AudioPortType::MONO),
@@ -225,6 +296,23 @@ impl PluginNotePortsImpl for PolySynthPluginMainThread<'_> {
     }
 }
 
+impl PluginTrackInfoImpl for PolySynthPluginMainThread<'_> {
+    fn changed(&mut self) {
+        let Some(host_track_info) = self.host_track_info else {
+            return;
+        };
+
+        self.track_info = host_track_info.get(&mut self.host);
+
+        // Our output port is named after the track, so we ask the host to pick up the new name.
+        if let Some(audio_ports) = self.host.get_extension::<HostAudioPorts>() {
+            if audio_ports.is_rescan_flag_supported(&self.host, RescanType::NAMES) {
+                audio_ports.rescan(&mut self.host, RescanType::NAMES);
+            }
+        }
+    }
+}
+
 /// The plugin data that gets shared between the Main Thread and the Audio Thread.
 pub struct PolySynthPluginShared {
     /// The plugin's parameter values.
@@ -237,6 +325,14 @@ impl PluginShared<'_> for PolySynthPluginShared {}
 pub struct PolySynthPluginMainThread<'a> {
     /// A reference to the plugin's shared data.
     shared: &'a PolySynthPluginShared,
+    /// A handle to the host, to query its extensions from the main thread.
+    host: HostMainThreadHandle<'a>,
+    /// The host's Track Info extension, if it supports it.
+    host_track_info: Option<HostTrackInfo>,
+    /// The information about the track we're on, if the host provided it.
+    track_info: Option<TrackInfo>,
+    /// The ID of the tuning event space, if the host supports it.
+    tuning_space_id: Option<EventSpaceId<TuningEventSpace<'static>>>,
 }
 
 impl<'a> PluginMainThread<'a, PolySynthPluginShared> for PolySynthPluginMainThread<'a> {}
@@ -35,12 +35,14 @@ impl SquareOscillator {
         self.current_phase = 0.;
     }
 
-    /// Sets the current frequency of this oscillator using a MIDI note number.
+    /// Sets the current frequency of this oscillator using a MIDI note number, and a tuning offset
+    /// relative to equal temperament (in semitones).
     ///
     /// For setting the frequency directly, see [`SquareOscillator::set_frequency`].
     #[inline]
-    pub fn set_note_number(&mut self, new_note_number: u8) {
-        self.set_frequency(440.0 * 2.0f32.powf((new_note_number as f32 - 69.0) / 12.0));
+    pub fn set_note_number(&mut self, new_note_number: u8, relative_tuning: f32) {
+        let semitones_from_a4 = new_note_number as f32 + relative_tuning - 69.0;
+        self.set_frequency(440.0 * 2.0f32.powf(semitones_from_a4 / 12.0));
     }
 
     /// Sets the new frequency of this oscillator.
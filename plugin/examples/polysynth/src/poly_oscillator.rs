@@ -3,9 +3,13 @@
 use crate::oscillator::SquareOscillator;
 use crate::params::PARAM_VOLUME_ID;
 use clack_plugin::events::event_types::{
-    NoteOffEvent, NoteOnEvent, ParamModEvent, ParamValueEvent,
+    NoteOffEvent, NoteOnEvent, ParamModEvent, ParamValueEvent, RelativeTuningEvent,
 };
 use clack_plugin::events::Match;
+use clack_plugin::utils::ClapId;
+
+/// The number of MIDI channels we track the tuning of.
+const CHANNEL_COUNT: usize = 16;
 
 /// A voice in the polyphonic oscillator.
 ///
@@ -66,6 +70,9 @@ pub struct PolyOscillator {
     voice_buffer: Box<[Voice]>,
     /// The number of current
     active_voice_count: usize,
+    /// The ID of the tuning selected by the host for each MIDI channel.
+    /// This is None if the channel uses equal temperament.
+    channel_tunings: [Option<ClapId>; CHANNEL_COUNT],
 }
 
 impl PolyOscillator {
@@ -86,20 +93,29 @@ impl PolyOscillator {
             ]
             .into_boxed_slice(),
             active_voice_count: 0,
+            channel_tunings: [None; CHANNEL_COUNT],
         }
     }
 
-    /// Starts a new voice, playing the given MIDI note key.
+    /// Starts a new voice, playing the given MIDI note key with the given relative tuning.
     ///
     /// If there are no more voices available, this does nothing.
-    fn start_new_voice(&mut self, channel: u8, new_note_key: u8, note_id: Option<u32>) {
+    fn start_new_voice(
+        &mut self,
+        channel: u8,
+        new_note_key: u8,
+        note_id: Option<u32>,
+        relative_tuning: f32,
+    ) {
         // Skip the event if we are out of voices
         let Some(available_voice) = self.voice_buffer.get_mut(self.active_voice_count) else {
             return;
         };
 
         available_voice.oscillator.reset();
-        available_voice.oscillator.set_note_number(new_note_key);
+        available_voice
+            .oscillator
+            .set_note_number(new_note_key, relative_tuning);
         available_voice.channel = channel;
         available_voice.key_number = new_note_key;
         available_voice.note_id = note_id;
@@ -131,17 +147,46 @@ impl PolyOscillator {
     }
 
     /// Handles the given Note On input event.
-    pub fn handle_note_on(&mut self, event: &NoteOnEvent) {
+    ///
+    /// The note is played with the given tuning offset relative to equal temperament, in semitones.
+    pub fn handle_note_on(&mut self, event: &NoteOnEvent, relative_tuning: f32) {
         dbg!(event);
         if !event.port_index().matches(0u16) {
             return;
         }
 
         if let (Match::Specific(channel), Match::Specific(key)) = (event.channel(), event.key()) {
-            self.start_new_voice(channel as u8, key as u8, event.note_id().into_specific())
+            self.start_new_voice(
+                channel as u8,
+                key as u8,
+                event.note_id().into_specific(),
+                relative_tuning,
+            )
+        }
+    }
+
+    /// Handles the given Relative Tuning event, selecting the tuning of the matching channels.
+    pub fn handle_relative_tuning(&mut self, event: &RelativeTuningEvent) {
+        if !event.port_index().matches(0u16) {
+            return;
+        }
+
+        for (channel, tuning) in self.channel_tunings.iter_mut().enumerate() {
+            if event.channel().matches(channel as u16) {
+                *tuning = event.tuning_id();
+            }
         }
     }
 
+    /// Returns the ID of the tuning the host selected for the given channel, if any.
+    #[inline]
+    pub fn channel_tuning(&self, channel: u16) -> Option<ClapId> {
+        self.channel_tunings
+            .get(channel as usize)
+            .copied()
+            .flatten()
+    }
+
     /// Handles the given Note Off input event.
     pub fn handle_note_off(&mut self, event: &NoteOffEvent) {
         if !event.port_index().matches(0u16) {
@@ -12,6 +12,15 @@
 //! [`PluginFactory`](plugin::PluginFactory), which enables hosts to list all the plugin
 //! implementations present in a bundle.
 //!
+//! Clack also supports the following draft factory types:
+//!
+//! * [`PresetDiscoveryFactory`](preset_discovery::PresetDiscoveryFactory), which enables hosts to
+//!   index the presets provided by the plugins in a bundle.
+//! * [`PluginInvalidationFactory`](plugin_invalidation::PluginInvalidationFactory), which enables
+//!   hosts to know when the list of plugins exposed by a bundle may have changed.
+//! * [`PluginStateConverterFactory`](plugin_state_converter::PluginStateConverterFactory), which
+//!   enables hosts to migrate the state of a plugin to another one.
+//!
 //! See the [`Entry`](crate::entry::Entry) trait documentation for an example on how to create a
 //! custom entry and plugin factory.
 
@@ -20,6 +29,9 @@ use std::ffi::CStr;
 use std::ptr::NonNull;
 
 pub mod plugin;
+pub mod plugin_invalidation;
+pub mod plugin_state_converter;
+pub mod preset_discovery;
 
 /// A base trait for plugin-side factory implementations.
 ///
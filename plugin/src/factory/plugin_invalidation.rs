@@ -0,0 +1,297 @@
+//! The plugin invalidation factory type.
+//!
+//! The Plugin Invalidation Factory is meant for plugin bundles that act as a "shell" for many
+//! sub-plugins, whose list can change without the bundle itself being modified (e.g. when the
+//! user installs new content).
+//!
+//! This factory exposes a list of [`InvalidationSource`s](InvalidationSource), which are
+//! directories the host should watch. When any file matching a source's filename glob changes,
+//! the host then calls [`refresh`](PluginInvalidationFactory::refresh), so that the bundle can
+//! update the list of plugins exposed by its [`PluginFactory`](crate::factory::plugin::PluginFactory).
+//!
+//! Note that the plugin invalidation factory is still a draft in the CLAP specification, and is
+//! therefore subject to change.
+//!
+//! See the [`factory` module documentation](crate::factory) to learn more about factories.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_plugin::entry::prelude::*;
+//! use clack_plugin::factory::plugin_invalidation::*;
+//! use std::ffi::CStr;
+//!
+//! pub struct MyPluginInvalidationFactory {
+//!     sources: Vec<InvalidationSource>,
+//! }
+//!
+//! impl PluginInvalidationFactory for MyPluginInvalidationFactory {
+//!     fn source_count(&self) -> u32 {
+//!         self.sources.len() as u32
+//!     }
+//!
+//!     fn source(&self, index: u32) -> Option<&InvalidationSource> {
+//!         self.sources.get(index as usize)
+//!     }
+//!
+//!     fn refresh(&self) -> bool {
+//!         // Reload the list of sub-plugins here.
+//!         true
+//!     }
+//! }
+//!
+//! pub struct MyEntry {
+//!     plugin_invalidation_factory: PluginInvalidationFactoryWrapper<MyPluginInvalidationFactory>,
+//! }
+//!
+//! impl Entry for MyEntry {
+//!     fn new(_bundle_path: &CStr) -> Result<Self, EntryLoadError> {
+//!         Ok(Self {
+//!             plugin_invalidation_factory: PluginInvalidationFactoryWrapper::new(
+//!                 MyPluginInvalidationFactory {
+//!                     sources: vec![InvalidationSource::new("/opt/my-shell/patches", "*.patch", true)],
+//!                 },
+//!             ),
+//!         })
+//!     }
+//!
+//!     fn declare_factories<'a>(&'a self, builder: &mut EntryFactories<'a>) {
+//!         builder.register_factory(&self.plugin_invalidation_factory);
+//!     }
+//! }
+//! ```
+
+use crate::extensions::wrapper::handle_panic;
+use crate::factory::Factory;
+use clap_sys::factory::draft::plugin_invalidation::{
+    clap_plugin_invalidation_factory, clap_plugin_invalidation_source,
+    CLAP_PLUGIN_INVALIDATION_FACTORY_ID,
+};
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::fmt::{Display, Formatter};
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+
+/// A source of plugin invalidation, i.e. a set of files that can change the list of plugins
+/// exposed by a bundle.
+///
+/// A source is made of a directory, and of a filename glob matching the files in that directory
+/// (and optionally its subdirectories) that the host should watch for changes.
+pub struct InvalidationSource {
+    directory: Pin<Box<CStr>>,
+    filename_glob: Pin<Box<CStr>>,
+
+    raw: clap_plugin_invalidation_source,
+}
+
+// SAFETY: InvalidationSource is fully self-contained, the pointers refer to data owned by it.
+unsafe impl Send for InvalidationSource {}
+
+// SAFETY: InvalidationSource does not have any interior mutability.
+unsafe impl Sync for InvalidationSource {}
+
+impl InvalidationSource {
+    /// Creates a new invalidation source.
+    ///
+    /// If `recursive_scan` is `true`, the files matching `filename_glob` in any of the
+    /// subdirectories of `directory` are also watched.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if either the directory or the filename glob are empty, or
+    /// contain NULL-byte characters.
+    pub fn new(directory: &str, filename_glob: &str, recursive_scan: bool) -> Self {
+        if directory.is_empty() {
+            panic!("Invalidation source directory must not be blank!");
+        }
+
+        if filename_glob.is_empty() {
+            panic!("Invalidation source filename glob must not be blank!");
+        }
+
+        let directory = Pin::new(
+            CString::new(directory)
+                .expect("Invalid invalidation source directory")
+                .into_boxed_c_str(),
+        );
+
+        let filename_glob = Pin::new(
+            CString::new(filename_glob)
+                .expect("Invalid invalidation source filename glob")
+                .into_boxed_c_str(),
+        );
+
+        Self {
+            raw: clap_plugin_invalidation_source {
+                directory: directory.as_ptr(),
+                filename_glob: filename_glob.as_ptr(),
+                recursive_scan,
+            },
+
+            directory,
+            filename_glob,
+        }
+    }
+
+    /// The directory the host should watch.
+    ///
+    /// Example: `/opt/my-shell/patches`.
+    #[inline]
+    pub fn directory(&self) -> &CStr {
+        &self.directory
+    }
+
+    /// The glob matching the names of the files the host should watch.
+    ///
+    /// Example: `*.patch`.
+    #[inline]
+    pub fn filename_glob(&self) -> &CStr {
+        &self.filename_glob
+    }
+
+    /// Whether the subdirectories of [`directory`](InvalidationSource::directory) should also be
+    /// watched.
+    #[inline]
+    pub fn recursive_scan(&self) -> bool {
+        self.raw.recursive_scan
+    }
+
+    /// Returns this source as a raw, C-FFI compatible invalidation source.
+    #[inline]
+    pub fn as_raw(&self) -> &clap_plugin_invalidation_source {
+        &self.raw
+    }
+}
+
+/// A wrapper around a given [`PluginInvalidationFactory`] implementation.
+///
+/// This wrapper is required in order to expose a C FFI-compatible factory to the host, and is what
+/// needs to be exposed by an [`Entry`](crate::entry::Entry).
+#[repr(C)]
+pub struct PluginInvalidationFactoryWrapper<F> {
+    raw: clap_plugin_invalidation_factory,
+    factory: F,
+}
+
+impl<F: PluginInvalidationFactory> PluginInvalidationFactoryWrapper<F> {
+    /// Wraps a given [`PluginInvalidationFactory`] instance.
+    pub const fn new(factory: F) -> Self {
+        Self {
+            raw: clap_plugin_invalidation_factory {
+                count: Some(Self::count),
+                get: Some(Self::get),
+                refresh: Some(Self::refresh),
+            },
+            factory,
+        }
+    }
+
+    /// Returns a shared reference to the wrapped [`PluginInvalidationFactory`].
+    #[inline]
+    pub fn factory(&self) -> &F {
+        &self.factory
+    }
+
+    /// Returns a raw CLAP plugin invalidation factory pointer, ready to be used by the host.
+    #[inline]
+    pub fn as_raw_ptr(&self) -> *const clap_plugin_invalidation_factory {
+        &self.raw
+    }
+
+    /// # Safety
+    /// The plugin invalidation factory pointer must be valid (but it can be null)
+    unsafe fn handle<T>(
+        raw: *const clap_plugin_invalidation_factory,
+        handler: impl FnOnce(&F) -> T,
+    ) -> Option<T> {
+        let result = match (raw as *const Self).as_ref() {
+            None => Err(PluginInvalidationFactoryError::NullFactoryInstance),
+            Some(factory) => handle_panic(AssertUnwindSafe(|| handler(factory.factory())))
+                .map_err(|_| PluginInvalidationFactoryError::Panic),
+        };
+
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("[CLAP_PLUGIN_INVALIDATION_FACTORY_ERROR] {e}");
+
+                None
+            }
+        }
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn count(factory: *const clap_plugin_invalidation_factory) -> u32 {
+        Self::handle(factory, |factory| factory.source_count()).unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get(
+        factory: *const clap_plugin_invalidation_factory,
+        index: u32,
+    ) -> *const clap_plugin_invalidation_source {
+        Self::handle(factory, |factory| match factory.source(index) {
+            None => core::ptr::null(),
+            Some(source) => source.as_raw() as *const _,
+        })
+        .unwrap_or(core::ptr::null())
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn refresh(factory: *const clap_plugin_invalidation_factory) -> bool {
+        Self::handle(factory, |factory| factory.refresh()).unwrap_or(false)
+    }
+}
+
+// SAFETY: PluginInvalidationFactoryWrapper is #[repr(C)] with clap_plugin_invalidation_factory as
+// its first field, and matches CLAP_PLUGIN_INVALIDATION_FACTORY_ID.
+unsafe impl<F> Factory for PluginInvalidationFactoryWrapper<F> {
+    const IDENTIFIER: &'static CStr = CLAP_PLUGIN_INVALIDATION_FACTORY_ID;
+}
+
+/// A Plugin Invalidation Factory implementation.
+///
+/// See the [module documentation](self) to learn more about the role of a Plugin Invalidation
+/// Factory, and for an example implementation.
+pub trait PluginInvalidationFactory: Send + Sync {
+    /// Returns the number of invalidation sources exposed by this factory.
+    fn source_count(&self) -> u32;
+
+    /// Returns the [`InvalidationSource`] that is assigned the given index.
+    ///
+    /// Hosts will usually call this method repeatedly with every index from 0 to the total returned
+    /// by [`source_count`](PluginInvalidationFactory::source_count).
+    ///
+    /// If the given index is out of bounds, this returns [`None`].
+    fn source(&self, index: u32) -> Option<&InvalidationSource>;
+
+    /// Updates the list of plugins exposed by the bundle, after the host detected a change in one
+    /// of the invalidation sources.
+    ///
+    /// This returns `true` if the bundle's [`PluginFactory`](crate::factory::plugin::PluginFactory)
+    /// was updated in place, in which case the host only has to scan it again. If this returns
+    /// `false`, the host has to unload and reload the whole bundle instead.
+    fn refresh(&self) -> bool;
+}
+
+#[derive(Debug)]
+enum PluginInvalidationFactoryError {
+    NullFactoryInstance,
+    Panic,
+}
+
+impl Display for PluginInvalidationFactoryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginInvalidationFactoryError::NullFactoryInstance => f.write_str(
+                "Plugin invalidation factory method was called with null clap_plugin_invalidation_factory pointer",
+            ),
+            PluginInvalidationFactoryError::Panic => {
+                f.write_str("Plugin invalidation factory panicked")
+            }
+        }
+    }
+}
+
+impl Error for PluginInvalidationFactoryError {}
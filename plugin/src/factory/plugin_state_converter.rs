@@ -0,0 +1,286 @@
+//! The plugin state converter factory type.
+//!
+//! The Plugin State Converter Factory allows plugins to convert the saved state of another plugin
+//! into their own, so that hosts can transparently migrate existing projects. This is useful when
+//! a plugin's ID changed between major versions, or when migrating from another plugin ABI (e.g.
+//! a VST3 version of the same plugin).
+//!
+//! This factory exposes a list of converters, described by their
+//! [`PluginStateConverterDescriptor`]. Each converter converts both the state of its source plugin,
+//! and the values of its parameters (e.g. for automation), through the [`PluginStateConverter`]
+//! trait.
+//!
+//! See the [`factory` module documentation](crate::factory) to learn more about factories.
+//!
+//! # Example
+//!
+//! The following example shows how to implement and expose a factory with a single converter,
+//! which migrates the state of a plugin whose ID changed.
+//!
+//! ```
+//! use clack_plugin::entry::prelude::*;
+//! use clack_plugin::factory::plugin_state_converter::*;
+//! use clack_plugin::prelude::*;
+//! use clack_plugin::stream::{InputStream, OutputStream};
+//! use clack_plugin::utils::ClapId;
+//! use std::ffi::CStr;
+//! use std::io::{Read, Write};
+//!
+//! pub struct MyConverterFactory {
+//!     descriptor: PluginStateConverterDescriptor,
+//! }
+//!
+//! impl PluginStateConverterFactory for MyConverterFactory {
+//!     fn converter_count(&self) -> u32 {
+//!         1
+//!     }
+//!
+//!     fn converter_descriptor(&self, index: u32) -> Option<&PluginStateConverterDescriptor> {
+//!         match index {
+//!             0 => Some(&self.descriptor),
+//!             _ => None,
+//!         }
+//!     }
+//!
+//!     fn create_converter(&self, converter_id: &CStr) -> Option<PluginStateConverterInstance<'_>> {
+//!         if converter_id == self.descriptor.id() {
+//!             Some(PluginStateConverterInstance::new(&self.descriptor, MyConverter))
+//!         } else {
+//!             None
+//!         }
+//!     }
+//! }
+//!
+//! pub struct MyConverter;
+//!
+//! impl PluginStateConverter for MyConverter {
+//!     fn convert_state(
+//!         &mut self,
+//!         src: &mut InputStream,
+//!         dst: &mut OutputStream,
+//!     ) -> Result<(), PluginError> {
+//!         // The state format didn't change, only the plugin ID did.
+//!         let mut state = vec![];
+//!         src.read_to_end(&mut state)?;
+//!         dst.write_all(&state)?;
+//!         Ok(())
+//!     }
+//!
+//!     fn convert_normalized_value(&mut self, id: ClapId, value: f64) -> Option<(ClapId, f64)> {
+//!         Some((id, value))
+//!     }
+//!
+//!     fn convert_plain_value(&mut self, id: ClapId, value: f64) -> Option<(ClapId, f64)> {
+//!         Some((id, value))
+//!     }
+//! }
+//!
+//! pub struct MyEntry {
+//!     converter_factory: PluginStateConverterFactoryWrapper<MyConverterFactory>,
+//! }
+//!
+//! impl Entry for MyEntry {
+//!     fn new(_bundle_path: &CStr) -> Result<Self, EntryLoadError> {
+//!         let old_id = PluginId::clap(CStr::from_bytes_with_nul(b"org.old.gain\0").unwrap());
+//!         let new_id = PluginId::clap(CStr::from_bytes_with_nul(b"org.new.gain\0").unwrap());
+//!
+//!         Ok(Self {
+//!             converter_factory: PluginStateConverterFactoryWrapper::new(MyConverterFactory {
+//!                 descriptor: PluginStateConverterDescriptor::new(
+//!                     "org.new.gain.converter",
+//!                     "Gain converter",
+//!                     old_id,
+//!                     new_id,
+//!                 ),
+//!             }),
+//!         })
+//!     }
+//!
+//!     fn declare_factories<'a>(&'a self, builder: &mut EntryFactories<'a>) {
+//!         builder.register_factory(&self.converter_factory);
+//!     }
+//! }
+//! ```
+
+use crate::extensions::wrapper::handle_panic;
+use crate::factory::Factory;
+use clack_common::factory::plugin_state_converter::sys::{
+    clap_plugin_state_converter, clap_plugin_state_converter_descriptor,
+    clap_plugin_state_converter_factory, CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID,
+};
+use std::error::Error;
+use std::ffi::{c_char, CStr};
+use std::fmt::{Display, Formatter};
+use std::panic::AssertUnwindSafe;
+
+pub use clack_common::factory::plugin_state_converter::*;
+
+mod converter;
+mod descriptor;
+
+pub use converter::{PluginStateConverter, PluginStateConverterInstance};
+pub use descriptor::PluginStateConverterDescriptor;
+
+/// A wrapper around a given [`PluginStateConverterFactory`] implementation.
+///
+/// This wrapper is required in order to expose a C FFI-compatible factory to the host, and is what
+/// needs to be exposed by an [`Entry`](crate::entry::Entry).
+#[repr(C)]
+pub struct PluginStateConverterFactoryWrapper<F> {
+    raw: clap_plugin_state_converter_factory,
+    factory: F,
+}
+
+impl<F: PluginStateConverterFactory> PluginStateConverterFactoryWrapper<F> {
+    /// Wraps a given [`PluginStateConverterFactory`] instance.
+    pub const fn new(factory: F) -> Self {
+        Self {
+            raw: clap_plugin_state_converter_factory {
+                count: Some(Self::count),
+                get_descriptor: Some(Self::get_descriptor),
+                create: Some(Self::create),
+            },
+            factory,
+        }
+    }
+
+    /// Returns a shared reference to the wrapped [`PluginStateConverterFactory`].
+    #[inline]
+    pub fn factory(&self) -> &F {
+        &self.factory
+    }
+
+    /// Returns a raw CLAP plugin state converter factory pointer, ready to be used by the host.
+    #[inline]
+    pub fn as_raw_ptr(&self) -> *const clap_plugin_state_converter_factory {
+        &self.raw
+    }
+
+    /// # Safety
+    /// The plugin state converter factory pointer must be valid (but it can be null)
+    unsafe fn handle<T>(
+        raw: *const clap_plugin_state_converter_factory,
+        handler: impl FnOnce(&F) -> Result<T, PluginStateConverterFactoryError>,
+    ) -> Option<T> {
+        let result = match (raw as *const Self).as_ref() {
+            None => Err(PluginStateConverterFactoryError::NullFactoryInstance),
+            Some(factory) => match handle_panic(AssertUnwindSafe(|| handler(factory.factory()))) {
+                Err(_) => Err(PluginStateConverterFactoryError::Panic),
+                Ok(result) => result,
+            },
+        };
+
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("[CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ERROR] {e}");
+
+                None
+            }
+        }
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn count(factory: *const clap_plugin_state_converter_factory) -> u32 {
+        Self::handle(factory, |factory| Ok(factory.converter_count())).unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_descriptor(
+        factory: *const clap_plugin_state_converter_factory,
+        index: u32,
+    ) -> *const clap_plugin_state_converter_descriptor {
+        Self::handle(factory, |factory| {
+            match factory.converter_descriptor(index) {
+                None => Ok(core::ptr::null()),
+                Some(d) => Ok(d.as_raw() as *const _),
+            }
+        })
+        .unwrap_or(core::ptr::null())
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn create(
+        factory: *const clap_plugin_state_converter_factory,
+        converter_id: *const c_char,
+    ) -> *mut clap_plugin_state_converter {
+        Self::handle(factory, |factory| {
+            if converter_id.is_null() {
+                return Err(PluginStateConverterFactoryError::NulPtr("converter_id"));
+            }
+
+            let converter_id = CStr::from_ptr(converter_id);
+
+            match factory.create_converter(converter_id) {
+                None => Ok(core::ptr::null_mut()),
+                Some(instance) => Ok(instance.into_owned_ptr()),
+            }
+        })
+        .unwrap_or(core::ptr::null_mut())
+    }
+}
+
+// SAFETY: PluginStateConverterFactoryWrapper is #[repr(C)] with
+// clap_plugin_state_converter_factory as its first field, and matches
+// CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID.
+unsafe impl<F> Factory for PluginStateConverterFactoryWrapper<F> {
+    const IDENTIFIER: &'static CStr = CLAP_PLUGIN_STATE_CONVERTER_FACTORY_ID;
+}
+
+/// A Plugin State Converter Factory implementation.
+///
+/// See the [module documentation](self) to learn more about the role of a Plugin State Converter
+/// Factory, and for an example implementation.
+pub trait PluginStateConverterFactory: Send + Sync {
+    /// Returns the number of converters exposed by this factory.
+    fn converter_count(&self) -> u32;
+
+    /// Returns the [`PluginStateConverterDescriptor`] of the converter that is assigned the given
+    /// index.
+    ///
+    /// Hosts will usually call this method repeatedly with every index from 0 to the total returned
+    /// by [`converter_count`](PluginStateConverterFactory::converter_count), in order to discover
+    /// all the converters exposed by this factory.
+    ///
+    /// If the given index is out of bounds, or in general does not match any given converter, this
+    /// returns [`None`].
+    fn converter_descriptor(&self, index: u32) -> Option<&PluginStateConverterDescriptor>;
+
+    /// Creates a new converter instance for the converter matching the given `converter_id`.
+    ///
+    /// If the given `converter_id` matches against one of the converters this factory manages,
+    /// implementors of this trait then use the [`PluginStateConverterInstance::new`] method to
+    /// instantiate the corresponding converter implementation.
+    ///
+    /// If the given `converter_id` does not match any known converters to this factory, this
+    /// method returns [`None`].
+    fn create_converter(&self, converter_id: &CStr) -> Option<PluginStateConverterInstance<'_>>;
+}
+
+#[derive(Debug)]
+enum PluginStateConverterFactoryError {
+    NullFactoryInstance,
+    NulPtr(&'static str),
+    Panic,
+}
+
+impl Display for PluginStateConverterFactoryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginStateConverterFactoryError::NullFactoryInstance => f.write_str(
+                "Plugin state converter factory method was called with null clap_plugin_state_converter_factory pointer",
+            ),
+            PluginStateConverterFactoryError::NulPtr(ptr_name) => {
+                write!(
+                    f,
+                    "Plugin state converter factory method was called with null {ptr_name} pointer"
+                )
+            }
+            PluginStateConverterFactoryError::Panic => {
+                f.write_str("Plugin state converter factory panicked")
+            }
+        }
+    }
+}
+
+impl Error for PluginStateConverterFactoryError {}
@@ -0,0 +1,306 @@
+use crate::extensions::wrapper::handle_panic;
+use crate::factory::plugin_state_converter::PluginStateConverterDescriptor;
+use crate::plugin::PluginError;
+use clack_common::factory::plugin_state_converter::sys::clap_plugin_state_converter;
+use clack_common::stream::{InputStream, OutputStream};
+use clack_common::utils::ClapId;
+use clap_sys::id::clap_id;
+use clap_sys::stream::{clap_istream, clap_ostream};
+use std::error::Error;
+use std::ffi::c_char;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::panic::AssertUnwindSafe;
+use std::ptr::NonNull;
+
+/// A plugin state converter implementation.
+///
+/// State converters are created by the
+/// [`PluginStateConverterFactory`](super::PluginStateConverterFactory), using the
+/// [`PluginStateConverterInstance::new`] method. They convert the saved state and the automation
+/// of the plugin described by their descriptor's
+/// [`src_plugin_id`](PluginStateConverterDescriptor::src_plugin_id) into the ones of the plugin
+/// described by its [`dst_plugin_id`](PluginStateConverterDescriptor::dst_plugin_id).
+pub trait PluginStateConverter {
+    /// Reads the saved state of the source plugin from the `src` stream, and writes the matching
+    /// state of the destination plugin into the `dst` stream.
+    ///
+    /// # Errors
+    ///
+    /// This may return an error if the state could not be converted. The error's message is then
+    /// displayed to the user by the host.
+    fn convert_state(
+        &mut self,
+        src: &mut InputStream,
+        dst: &mut OutputStream,
+    ) -> Result<(), PluginError>;
+
+    /// Converts the normalized value of a source plugin parameter, returning the ID and
+    /// normalized value of the matching destination plugin parameter.
+    ///
+    /// This returns [`None`] if the value could not be converted, e.g. if there is no matching
+    /// parameter in the destination plugin.
+    fn convert_normalized_value(
+        &mut self,
+        src_param_id: ClapId,
+        src_normalized_value: f64,
+    ) -> Option<(ClapId, f64)>;
+
+    /// Converts the plain value of a source plugin parameter, returning the ID and plain value of
+    /// the matching destination plugin parameter.
+    ///
+    /// This returns [`None`] if the value could not be converted, e.g. if there is no matching
+    /// parameter in the destination plugin.
+    fn convert_plain_value(
+        &mut self,
+        src_param_id: ClapId,
+        src_plain_value: f64,
+    ) -> Option<(ClapId, f64)>;
+}
+
+/// A wrapper around a [`PluginStateConverter`] instance.
+///
+/// This type is created with its [`new`](PluginStateConverterInstance::new) method when the host
+/// wants to create a given converter, and is what needs to be returned by the
+/// [`PluginStateConverterFactory::create_converter`](super::PluginStateConverterFactory::create_converter)
+/// method.
+pub struct PluginStateConverterInstance<'a> {
+    raw: NonNull<clap_plugin_state_converter>,
+    _lifetime: PhantomData<&'a PluginStateConverterDescriptor>,
+}
+
+impl<'a> PluginStateConverterInstance<'a> {
+    /// Creates a new converter instance from the given [`PluginStateConverter`] implementation.
+    ///
+    /// See the [`PluginStateConverterFactory`](super::PluginStateConverterFactory)'s trait
+    /// documentation for a usage example.
+    pub fn new<C: PluginStateConverter + 'a>(
+        descriptor: &'a PluginStateConverterDescriptor,
+        converter: C,
+    ) -> Self {
+        let raw = Box::new(clap_plugin_state_converter {
+            desc: descriptor.as_raw(),
+            converter_data: Box::into_raw(Box::new(converter)).cast(),
+            destroy: Some(destroy::<C>),
+            convert_state: Some(convert_state::<C>),
+            convert_normalized_value: Some(convert_normalized_value::<C>),
+            convert_plain_value: Some(convert_plain_value::<C>),
+        });
+
+        Self {
+            // SAFETY: pointers from Box::into_raw are never null.
+            raw: unsafe { NonNull::new_unchecked(Box::into_raw(raw)) },
+            _lifetime: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_owned_ptr(self) -> *mut clap_plugin_state_converter {
+        ManuallyDrop::new(self).raw.as_ptr()
+    }
+}
+
+impl Drop for PluginStateConverterInstance<'_> {
+    fn drop(&mut self) {
+        // SAFETY: the raw converter was created by PluginStateConverterInstance::new, and is still
+        // owned by this instance.
+        unsafe {
+            if let Some(destroy) = self.raw.as_ref().destroy {
+                destroy(self.raw.as_ptr())
+            }
+        }
+    }
+}
+
+/// # Safety
+/// The converter pointer must be valid (but it can be null), and must have been created by
+/// [`PluginStateConverterInstance::new`] with the same converter type.
+unsafe fn handle<C: PluginStateConverter, T>(
+    converter: *mut clap_plugin_state_converter,
+    handler: impl FnOnce(&mut C) -> Result<T, ConverterError>,
+) -> Result<T, ConverterError> {
+    let converter = converter
+        .as_ref()
+        .and_then(|converter| (converter.converter_data as *mut C).as_mut())
+        .ok_or(ConverterError::NullConverterInstance)?;
+
+    match handle_panic(AssertUnwindSafe(|| handler(converter))) {
+        Err(_) => Err(ConverterError::Panic),
+        Ok(result) => result,
+    }
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn destroy<C: PluginStateConverter>(converter: *mut clap_plugin_state_converter) {
+    if converter.is_null() {
+        return;
+    }
+
+    let converter = Box::from_raw(converter);
+    let data = converter.converter_data as *mut C;
+
+    if !data.is_null() {
+        let data = Box::from_raw(data);
+        if handle_panic(AssertUnwindSafe(|| drop(data))).is_err() {
+            eprintln!(
+                "[CLAP_PLUGIN_STATE_CONVERTER_ERROR] {}",
+                ConverterError::Panic
+            );
+        }
+    }
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn convert_state<C: PluginStateConverter>(
+    converter: *mut clap_plugin_state_converter,
+    src: *const clap_istream,
+    dst: *const clap_ostream,
+    error_buffer: *mut c_char,
+    error_buffer_size: usize,
+) -> bool {
+    let result = handle(converter, |converter: &mut C| {
+        if src.is_null() {
+            return Err(ConverterError::NulPtr("clap_istream"));
+        }
+
+        if dst.is_null() {
+            return Err(ConverterError::NulPtr("clap_ostream"));
+        }
+
+        let src = InputStream::from_raw_mut(&mut *(src as *mut _));
+        let dst = OutputStream::from_raw_mut(&mut *(dst as *mut _));
+
+        converter
+            .convert_state(src, dst)
+            .map_err(ConverterError::Plugin)
+    });
+
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            write_error_message(&e, error_buffer, error_buffer_size);
+            eprintln!("[CLAP_PLUGIN_STATE_CONVERTER_ERROR] {e}");
+
+            false
+        }
+    }
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn convert_normalized_value<C: PluginStateConverter>(
+    converter: *mut clap_plugin_state_converter,
+    src_param_id: clap_id,
+    src_normalized_value: f64,
+    dst_param_id: *mut clap_id,
+    dst_normalized_value: *mut f64,
+) -> bool {
+    convert_value(
+        converter,
+        src_param_id,
+        dst_param_id,
+        dst_normalized_value,
+        |converter: &mut C, id| converter.convert_normalized_value(id, src_normalized_value),
+    )
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn convert_plain_value<C: PluginStateConverter>(
+    converter: *mut clap_plugin_state_converter,
+    src_param_id: clap_id,
+    src_plain_value: f64,
+    dst_param_id: *mut clap_id,
+    dst_plain_value: *mut f64,
+) -> bool {
+    convert_value(
+        converter,
+        src_param_id,
+        dst_param_id,
+        dst_plain_value,
+        |converter: &mut C, id| converter.convert_plain_value(id, src_plain_value),
+    )
+}
+
+/// # Safety
+/// Same as [`handle`]. The destination pointers must be valid for writes (but they can be null).
+unsafe fn convert_value<C: PluginStateConverter>(
+    converter: *mut clap_plugin_state_converter,
+    src_param_id: clap_id,
+    dst_param_id: *mut clap_id,
+    dst_value: *mut f64,
+    convert: impl FnOnce(&mut C, ClapId) -> Option<(ClapId, f64)>,
+) -> bool {
+    let result = handle(converter, |converter: &mut C| {
+        if dst_param_id.is_null() || dst_value.is_null() {
+            return Err(ConverterError::NulPtr("destination value"));
+        }
+
+        let src_param_id = ClapId::from_raw(src_param_id).ok_or(ConverterError::InvalidParamId)?;
+
+        Ok(convert(converter, src_param_id))
+    });
+
+    match result {
+        Ok(Some((id, value))) => {
+            *dst_param_id = id.get();
+            *dst_value = value;
+            true
+        }
+        Ok(None) => false,
+        Err(e) => {
+            eprintln!("[CLAP_PLUGIN_STATE_CONVERTER_ERROR] {e}");
+            false
+        }
+    }
+}
+
+/// Writes the given error's message into the given C string buffer, truncating it if needed.
+///
+/// # Safety
+/// The given buffer must be valid for writes of `buffer_size` bytes (but it can be null).
+unsafe fn write_error_message(error: &ConverterError, buffer: *mut c_char, buffer_size: usize) {
+    if buffer.is_null() || buffer_size == 0 {
+        return;
+    }
+
+    let message = match error {
+        ConverterError::Plugin(e) => e.to_string(),
+        e => e.to_string(),
+    };
+
+    let len = message.len().min(buffer_size - 1);
+    core::ptr::copy_nonoverlapping(message.as_ptr().cast(), buffer, len);
+    *buffer.add(len) = 0;
+}
+
+#[derive(Debug)]
+enum ConverterError {
+    NullConverterInstance,
+    NulPtr(&'static str),
+    InvalidParamId,
+    Plugin(PluginError),
+    Panic,
+}
+
+impl Display for ConverterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConverterError::NullConverterInstance => f.write_str(
+                "State converter method was called with null clap_plugin_state_converter pointer",
+            ),
+            ConverterError::NulPtr(ptr_name) => {
+                write!(
+                    f,
+                    "State converter method was called with null {ptr_name} pointer"
+                )
+            }
+            ConverterError::InvalidParamId => {
+                f.write_str("State converter was given an invalid parameter ID")
+            }
+            ConverterError::Plugin(e) => write!(f, "State converter error: {e}"),
+            ConverterError::Panic => f.write_str("State converter panicked"),
+        }
+    }
+}
+
+impl Error for ConverterError {}
@@ -0,0 +1,227 @@
+use crate::factory::plugin_state_converter::PluginId;
+use clack_common::factory::plugin_state_converter::sys::clap_plugin_state_converter_descriptor;
+use clap_sys::factory::draft::preset_discovery::clap_plugin_id;
+use clap_sys::version::CLAP_VERSION;
+use std::ffi::{CStr, CString};
+use std::pin::Pin;
+
+/// Represents a description of a plugin state converter.
+///
+/// This descriptor is exposed to the host by the
+/// [`PluginStateConverterFactory`](super::PluginStateConverterFactory), allowing the host to find
+/// the converters matching the plugins it needs to convert, without having to create them.
+///
+/// The [`id`](PluginStateConverterDescriptor::id), [`name`](PluginStateConverterDescriptor::name),
+/// and the source and destination plugin IDs are required, while the other fields are optional.
+pub struct PluginStateConverterDescriptor {
+    id: Pin<Box<CStr>>,
+    name: Pin<Box<CStr>>,
+    vendor: Option<Pin<Box<CStr>>>,
+    version: Option<Pin<Box<CStr>>>,
+    description: Option<Pin<Box<CStr>>>,
+    src_plugin_id: (Pin<Box<CStr>>, Pin<Box<CStr>>),
+    dst_plugin_id: (Pin<Box<CStr>>, Pin<Box<CStr>>),
+
+    raw_descriptor: clap_plugin_state_converter_descriptor,
+}
+
+// SAFETY: PluginStateConverterDescriptor is fully self-contained, the pointers refer to data owned
+// by it.
+unsafe impl Send for PluginStateConverterDescriptor {}
+
+// SAFETY: PluginStateConverterDescriptor does not have any interior mutability.
+unsafe impl Sync for PluginStateConverterDescriptor {}
+
+impl PluginStateConverterDescriptor {
+    /// Creates a new converter descriptor, with the given ID and name, converting the state of
+    /// the plugin identified by `src_plugin_id` into the state of the plugin identified by
+    /// `dst_plugin_id`.
+    ///
+    /// Note that CLAP plugin IDs do not carry a plugin version: converters that only apply to
+    /// some versions of the source plugin must check the version stored in the state itself.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if either the ID or the name are empty, or contain NULL-byte
+    /// characters.
+    pub fn new(id: &str, name: &str, src_plugin_id: PluginId, dst_plugin_id: PluginId) -> Self {
+        if id.is_empty() {
+            panic!("State converter ID must not be blank!");
+        }
+
+        if name.is_empty() {
+            panic!("State converter Name must not be blank!");
+        }
+
+        let id = Pin::new(
+            CString::new(id)
+                .expect("Invalid State converter ID")
+                .into_boxed_c_str(),
+        );
+
+        let name = Pin::new(
+            CString::new(name)
+                .expect("Invalid State converter Name")
+                .into_boxed_c_str(),
+        );
+
+        let src_plugin_id = (
+            Pin::new(Box::<CStr>::from(src_plugin_id.abi)),
+            Pin::new(Box::<CStr>::from(src_plugin_id.id)),
+        );
+
+        let dst_plugin_id = (
+            Pin::new(Box::<CStr>::from(dst_plugin_id.abi)),
+            Pin::new(Box::<CStr>::from(dst_plugin_id.id)),
+        );
+
+        Self {
+            raw_descriptor: clap_plugin_state_converter_descriptor {
+                clap_version: CLAP_VERSION,
+                src_plugin_id: clap_plugin_id {
+                    abi: src_plugin_id.0.as_ptr(),
+                    id: src_plugin_id.1.as_ptr(),
+                },
+                dst_plugin_id: clap_plugin_id {
+                    abi: dst_plugin_id.0.as_ptr(),
+                    id: dst_plugin_id.1.as_ptr(),
+                },
+                id: id.as_ptr(),
+                name: name.as_ptr(),
+                vendor: core::ptr::null(),
+                version: core::ptr::null(),
+                description: core::ptr::null(),
+            },
+
+            id,
+            name,
+            vendor: None,
+            version: None,
+            description: None,
+            src_plugin_id,
+            dst_plugin_id,
+        }
+    }
+
+    /// The unique identifier of this converter.
+    ///
+    /// Example: `com.u-he.diva-converter`.
+    #[inline]
+    pub fn id(&self) -> &CStr {
+        &self.id
+    }
+
+    /// The user-facing display name of this converter.
+    ///
+    /// Example: `Diva 1.x to Diva 2.x converter`.
+    #[inline]
+    pub fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    /// The vendor of this converter.
+    ///
+    /// Example: `u-he`.
+    #[inline]
+    pub fn vendor(&self) -> Option<&CStr> {
+        self.vendor.as_deref()
+    }
+
+    /// The version of this converter.
+    ///
+    /// Example: `1.0.0`.
+    #[inline]
+    pub fn version(&self) -> Option<&CStr> {
+        self.version.as_deref()
+    }
+
+    /// A short description of this converter.
+    #[inline]
+    pub fn description(&self) -> Option<&CStr> {
+        self.description.as_deref()
+    }
+
+    /// The identifier of the plugin this converter converts the state from.
+    #[inline]
+    pub fn src_plugin_id(&self) -> PluginId<'_> {
+        PluginId {
+            abi: &self.src_plugin_id.0,
+            id: &self.src_plugin_id.1,
+        }
+    }
+
+    /// The identifier of the plugin this converter converts the state to.
+    #[inline]
+    pub fn dst_plugin_id(&self) -> PluginId<'_> {
+        PluginId {
+            abi: &self.dst_plugin_id.0,
+            id: &self.dst_plugin_id.1,
+        }
+    }
+
+    /// Sets the converter's vendor.
+    ///
+    /// Passing an empty string as the `vendor` parameter will mark it as unset, making
+    /// [`vendor`](PluginStateConverterDescriptor::vendor) then return `None`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the given vendor contains NULL-byte characters, which are
+    /// invalid.
+    pub fn with_vendor(mut self, vendor: &str) -> Self {
+        self.vendor = optional_cstr(vendor, "Invalid State converter Vendor");
+        self.raw_descriptor.vendor = optional_cstr_ptr(&self.vendor);
+        self
+    }
+
+    /// Sets the converter's version.
+    ///
+    /// Passing an empty string as the `version` parameter will mark it as unset, making
+    /// [`version`](PluginStateConverterDescriptor::version) then return `None`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the given version contains NULL-byte characters, which are
+    /// invalid.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = optional_cstr(version, "Invalid State converter Version");
+        self.raw_descriptor.version = optional_cstr_ptr(&self.version);
+        self
+    }
+
+    /// Sets the converter's description.
+    ///
+    /// Passing an empty string as the `description` parameter will mark it as unset, making
+    /// [`description`](PluginStateConverterDescriptor::description) then return `None`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the given description contains NULL-byte characters, which
+    /// are invalid.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = optional_cstr(description, "Invalid State converter Description");
+        self.raw_descriptor.description = optional_cstr_ptr(&self.description);
+        self
+    }
+
+    /// Returns this descriptor as a raw, C-FFI compatible converter descriptor.
+    #[inline]
+    pub fn as_raw(&self) -> &clap_plugin_state_converter_descriptor {
+        &self.raw_descriptor
+    }
+}
+
+fn optional_cstr(value: &str, error_message: &str) -> Option<Pin<Box<CStr>>> {
+    if value.is_empty() {
+        return None;
+    }
+
+    Some(Pin::new(
+        CString::new(value).expect(error_message).into_boxed_c_str(),
+    ))
+}
+
+#[inline]
+fn optional_cstr_ptr(value: &Option<Pin<Box<CStr>>>) -> *const std::ffi::c_char {
+    value.as_deref().map_or(core::ptr::null(), CStr::as_ptr)
+}
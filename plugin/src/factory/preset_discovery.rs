@@ -0,0 +1,337 @@
+//! The preset discovery factory type.
+//!
+//! The Preset Discovery Factory allows plugins to expose their presets (e.g. factory banks, or
+//! user presets stored on disk) to the host, so that hosts can index them and display them in their
+//! own preset browser, without having to instantiate the plugin itself.
+//!
+//! This factory exposes a list of [preset providers](PresetDiscoveryProvider), which are created
+//! by the host alongside its [`Indexer`]. Once [initialized](ProviderInstance::new), each provider
+//! declares to the indexer the [file types](FileType), [locations](LocationInfo) and
+//! [soundpacks](Soundpack) it supports. The host then crawls the declared locations, and asks
+//! the provider to describe the presets they contain through a [`MetadataReceiver`].
+//!
+//! Note that the preset discovery factory is still a draft in the CLAP specification, and is
+//! therefore subject to change.
+//!
+//! See the [`factory` module documentation](crate::factory) to learn more about factories.
+//!
+//! # Example
+//!
+//! The following example shows how to expose a preset discovery factory from a custom entry.
+//! Bundles usually expose it alongside their [`PluginFactory`](crate::factory::plugin::PluginFactory),
+//! which can be registered the same way. See the [`PresetDiscoveryFactory`] trait documentation for an example of a
+//! factory implementation.
+//!
+//! ```
+//! use clack_plugin::entry::prelude::*;
+//! use clack_plugin::factory::preset_discovery::*;
+//! use std::ffi::CStr;
+//!
+//! pub struct MyEntry {
+//!     preset_discovery_factory: PresetDiscoveryFactoryWrapper<MyPresetDiscoveryFactory>,
+//! }
+//!
+//! impl Entry for MyEntry {
+//!     fn new(_bundle_path: &CStr) -> Result<Self, EntryLoadError> {
+//!         Ok(Self {
+//!             preset_discovery_factory: PresetDiscoveryFactoryWrapper::new(
+//!                 MyPresetDiscoveryFactory::new(),
+//!             ),
+//!         })
+//!     }
+//!
+//!     fn declare_factories<'a>(&'a self, builder: &mut EntryFactories<'a>) {
+//!         builder.register_factory(&self.preset_discovery_factory);
+//!     }
+//! }
+//! # pub struct MyPresetDiscoveryFactory;
+//! # impl MyPresetDiscoveryFactory {
+//! #     pub fn new() -> Self { Self }
+//! # }
+//! # impl PresetDiscoveryFactory for MyPresetDiscoveryFactory {
+//! #     fn provider_count(&self) -> u32 { 0 }
+//! #     fn provider_descriptor(&self, _index: u32) -> Option<&ProviderDescriptor> { None }
+//! #     fn create_provider<'a>(&'a self, _indexer: IndexerInfo<'a>, _provider_id: &CStr) -> Option<ProviderInstance<'a>> { None }
+//! # }
+//! ```
+
+use crate::extensions::wrapper::handle_panic;
+use crate::factory::Factory;
+use clap_sys::factory::draft::preset_discovery::{
+    clap_preset_discovery_factory, clap_preset_discovery_indexer, clap_preset_discovery_provider,
+    clap_preset_discovery_provider_descriptor, CLAP_PRESET_DISCOVERY_FACTORY_ID,
+};
+use std::error::Error;
+use std::ffi::{c_char, CStr};
+use std::fmt::{Display, Formatter};
+use std::panic::AssertUnwindSafe;
+use std::ptr::NonNull;
+
+pub use clack_common::factory::preset_discovery::*;
+
+mod descriptor;
+mod indexer;
+mod metadata_receiver;
+mod provider;
+
+pub use descriptor::ProviderDescriptor;
+pub use indexer::{Indexer, IndexerError, IndexerInfo};
+pub use metadata_receiver::{MetadataReceiver, MetadataReceiverError};
+pub use provider::{PresetDiscoveryProvider, ProviderInstance};
+
+/// A wrapper around a given [`PresetDiscoveryFactory`] implementation.
+///
+/// This wrapper is required in order to expose a C FFI-compatible factory to the host, and is what
+/// needs to be exposed by an [`Entry`](crate::entry::Entry).
+#[repr(C)]
+pub struct PresetDiscoveryFactoryWrapper<F> {
+    raw: clap_preset_discovery_factory,
+    factory: F,
+}
+
+impl<F: PresetDiscoveryFactory> PresetDiscoveryFactoryWrapper<F> {
+    /// Wraps a given [`PresetDiscoveryFactory`] instance.
+    pub const fn new(factory: F) -> Self {
+        Self {
+            raw: clap_preset_discovery_factory {
+                count: Some(Self::count),
+                get_descriptor: Some(Self::get_descriptor),
+                create: Some(Self::create),
+            },
+            factory,
+        }
+    }
+
+    /// Returns a shared reference to the wrapped [`PresetDiscoveryFactory`].
+    #[inline]
+    pub fn factory(&self) -> &F {
+        &self.factory
+    }
+
+    /// Returns a raw CLAP preset discovery factory pointer, ready to be used by the host.
+    #[inline]
+    pub fn as_raw_ptr(&self) -> *const clap_preset_discovery_factory {
+        &self.raw
+    }
+
+    /// # Safety
+    /// The preset discovery factory pointer must be valid
+    unsafe fn handle<T>(
+        raw: *const clap_preset_discovery_factory,
+        handler: impl FnOnce(&F) -> Result<T, PresetDiscoveryFactoryError>,
+    ) -> Option<T> {
+        let factory = Self::from_raw(raw);
+        let result = factory.and_then(|factory| {
+            match handle_panic(AssertUnwindSafe(|| handler(factory.factory()))) {
+                Err(_) => Err(PresetDiscoveryFactoryError::Panic),
+                Ok(Err(e)) => Err(e),
+                Ok(Ok(val)) => Ok(val),
+            }
+        });
+
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("[CLAP_PRESET_DISCOVERY_FACTORY_ERROR] {e}");
+
+                None
+            }
+        }
+    }
+
+    /// # Safety
+    /// The preset discovery factory pointer must be valid (but it can be null)
+    unsafe fn from_raw<'a>(
+        raw: *const clap_preset_discovery_factory,
+    ) -> Result<&'a Self, PresetDiscoveryFactoryError> {
+        (raw as *const Self)
+            .as_ref()
+            .ok_or(PresetDiscoveryFactoryError::NullFactoryInstance)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn count(factory: *const clap_preset_discovery_factory) -> u32 {
+        Self::handle(factory, |factory| Ok(factory.provider_count())).unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_descriptor(
+        factory: *const clap_preset_discovery_factory,
+        index: u32,
+    ) -> *const clap_preset_discovery_provider_descriptor {
+        Self::handle(factory, |factory| {
+            match factory.provider_descriptor(index) {
+                None => Ok(core::ptr::null()),
+                Some(d) => Ok(d.as_raw() as *const _),
+            }
+        })
+        .unwrap_or(core::ptr::null())
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn create(
+        factory: *const clap_preset_discovery_factory,
+        indexer: *const clap_preset_discovery_indexer,
+        provider_id: *const c_char,
+    ) -> *const clap_preset_discovery_provider {
+        Self::handle(factory, |factory| {
+            if provider_id.is_null() {
+                return Err(PresetDiscoveryFactoryError::NulPtr("provider_id"));
+            }
+
+            let provider_id = CStr::from_ptr(provider_id);
+            let indexer = NonNull::new(indexer as *mut _).ok_or(
+                PresetDiscoveryFactoryError::NulPtr("clap_preset_discovery_indexer"),
+            )?;
+
+            let indexer = IndexerInfo::from_raw(indexer);
+
+            match factory.create_provider(indexer, provider_id) {
+                None => Ok(core::ptr::null()),
+                Some(instance) => Ok(instance.into_owned_ptr()),
+            }
+        })
+        .unwrap_or(core::ptr::null())
+    }
+}
+
+// SAFETY: PresetDiscoveryFactoryWrapper is #[repr(C)] with clap_preset_discovery_factory as its
+// first field, and matches CLAP_PRESET_DISCOVERY_FACTORY_ID.
+unsafe impl<F> Factory for PresetDiscoveryFactoryWrapper<F> {
+    const IDENTIFIER: &'static CStr = CLAP_PRESET_DISCOVERY_FACTORY_ID;
+}
+
+/// A Preset Discovery Factory implementation.
+///
+/// See the [module documentation](self) to learn more about the role of a Preset Discovery
+/// Factory.
+///
+/// # Example
+///
+/// The following example shows how to implement a factory exposing a single preset provider,
+/// which declares a single preset location bundled within the plugin itself.
+///
+/// ```
+/// use clack_plugin::factory::preset_discovery::*;
+/// use clack_plugin::prelude::*;
+/// use std::ffi::CStr;
+///
+/// pub struct MyPresetDiscoveryFactory {
+///     provider_descriptor: ProviderDescriptor,
+/// }
+///
+/// impl PresetDiscoveryFactory for MyPresetDiscoveryFactory {
+///     fn provider_count(&self) -> u32 {
+///         1 // We only have a single provider
+///     }
+///
+///     fn provider_descriptor(&self, index: u32) -> Option<&ProviderDescriptor> {
+///         match index {
+///             0 => Some(&self.provider_descriptor),
+///             _ => None,
+///         }
+///     }
+///
+///     fn create_provider<'a>(
+///         &'a self,
+///         indexer: IndexerInfo<'a>,
+///         provider_id: &CStr,
+///     ) -> Option<ProviderInstance<'a>> {
+///         if provider_id == self.provider_descriptor.id() {
+///             Some(ProviderInstance::new(indexer, &self.provider_descriptor, |indexer| {
+///                 indexer.declare_location(LocationInfo {
+///                     flags: Flags::IS_FACTORY_CONTENT,
+///                     name: CStr::from_bytes_with_nul(b"Factory presets\0").unwrap(),
+///                     location: Location::Plugin,
+///                 })?;
+///
+///                 Ok(MyPresetProvider)
+///             }))
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// pub struct MyPresetProvider;
+///
+/// impl PresetDiscoveryProvider for MyPresetProvider {
+///     fn get_metadata(
+///         &mut self,
+///         location: Location,
+///         receiver: &mut MetadataReceiver,
+///     ) -> Result<(), PluginError> {
+///         if location != Location::Plugin {
+///             return Err(PluginError::Message("Unknown location"));
+///         }
+///
+///         let plugin_id = CStr::from_bytes_with_nul(b"my.plugin\0").unwrap();
+///         let name = CStr::from_bytes_with_nul(b"Init\0").unwrap();
+///         let load_key = CStr::from_bytes_with_nul(b"0\0").unwrap();
+///
+///         receiver
+///             .begin_preset(Some(name), Some(load_key))?
+///             .add_plugin_id(PluginId::clap(plugin_id))
+///             .set_flags(Flags::IS_FACTORY_CONTENT);
+///
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait PresetDiscoveryFactory: Send + Sync {
+    /// Returns the number of preset providers exposed by this factory.
+    fn provider_count(&self) -> u32;
+
+    /// Returns the [`ProviderDescriptor`] of the provider that is assigned the given index.
+    ///
+    /// Hosts will usually call this method repeatedly with every index from 0 to the total returned
+    /// by [`provider_count`](PresetDiscoveryFactory::provider_count), in order to discover all the
+    /// providers exposed by this factory.
+    ///
+    /// If the given index is out of bounds, or in general does not match any given provider, this
+    /// returns [`None`].
+    fn provider_descriptor(&self, index: u32) -> Option<&ProviderDescriptor>;
+
+    /// Creates a new preset provider instance for the provider matching the given `provider_id`.
+    ///
+    /// If the given `provider_id` matches against one of the providers this factory manages,
+    /// implementors of this trait then use the [`ProviderInstance::new`] method to instantiate
+    /// the corresponding provider implementation.
+    ///
+    /// The host's indexer cannot be used until the provider is initialized, which is why only
+    /// information about it is available here.
+    ///
+    /// If the given `provider_id` does not match any known providers to this factory, this method
+    /// returns [`None`].
+    fn create_provider<'a>(
+        &'a self,
+        indexer: IndexerInfo<'a>,
+        provider_id: &CStr,
+    ) -> Option<ProviderInstance<'a>>;
+}
+
+#[derive(Debug)]
+enum PresetDiscoveryFactoryError {
+    NullFactoryInstance,
+    NulPtr(&'static str),
+    Panic,
+}
+
+impl Display for PresetDiscoveryFactoryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PresetDiscoveryFactoryError::NullFactoryInstance => f.write_str(
+                "Preset discovery factory method was called with null clap_preset_discovery_factory pointer",
+            ),
+            PresetDiscoveryFactoryError::NulPtr(ptr_name) => {
+                write!(
+                    f,
+                    "Preset discovery factory method was called with null {ptr_name} pointer"
+                )
+            }
+            PresetDiscoveryFactoryError::Panic => f.write_str("Preset discovery factory panicked"),
+        }
+    }
+}
+
+impl Error for PresetDiscoveryFactoryError {}
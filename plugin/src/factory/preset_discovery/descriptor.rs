@@ -0,0 +1,125 @@
+use clap_sys::factory::draft::preset_discovery::clap_preset_discovery_provider_descriptor;
+use clap_sys::version::CLAP_VERSION;
+use std::ffi::{CStr, CString};
+use std::pin::Pin;
+
+/// Represents a description of a preset provider.
+///
+/// This descriptor is exposed to the host by the [`PresetDiscoveryFactory`](super::PresetDiscoveryFactory),
+/// allowing the host to list the available providers before creating any of them.
+///
+/// Only the [`id`](ProviderDescriptor::id) and [`name`](ProviderDescriptor::name) fields are
+/// required, and the [`vendor`](ProviderDescriptor::vendor) field is optional.
+pub struct ProviderDescriptor {
+    id: Pin<Box<CStr>>,
+    name: Pin<Box<CStr>>,
+    vendor: Option<Pin<Box<CStr>>>,
+
+    raw_descriptor: clap_preset_discovery_provider_descriptor,
+}
+
+// SAFETY: ProviderDescriptor is fully self-contained, the pointers refer to data owned by it.
+unsafe impl Send for ProviderDescriptor {}
+
+// SAFETY: ProviderDescriptor does not have any interior mutability.
+unsafe impl Sync for ProviderDescriptor {}
+
+impl ProviderDescriptor {
+    /// Creates a new provider descriptor, with the given ID and name.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if either the ID or the name are empty, or contain NULL-byte
+    /// characters.
+    pub fn new(id: &str, name: &str) -> Self {
+        if id.is_empty() {
+            panic!("Provider ID must not be blank!");
+        }
+
+        if name.is_empty() {
+            panic!("Provider Name must not be blank!");
+        }
+
+        let id = Pin::new(
+            CString::new(id)
+                .expect("Invalid Provider ID")
+                .into_boxed_c_str(),
+        );
+
+        let name = Pin::new(
+            CString::new(name)
+                .expect("Invalid Provider Name")
+                .into_boxed_c_str(),
+        );
+
+        Self {
+            raw_descriptor: clap_preset_discovery_provider_descriptor {
+                clap_version: CLAP_VERSION,
+                id: id.as_ptr(),
+                name: name.as_ptr(),
+                vendor: core::ptr::null(),
+            },
+
+            id,
+            name,
+            vendor: None,
+        }
+    }
+
+    /// The unique identifier of this provider.
+    ///
+    /// Example: `com.u-he.diva.presets`.
+    #[inline]
+    pub fn id(&self) -> &CStr {
+        &self.id
+    }
+
+    /// The user-facing display name of this provider.
+    ///
+    /// Example: `Diva Presets`.
+    #[inline]
+    pub fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    /// The vendor of this provider.
+    ///
+    /// Example: `u-he`.
+    #[inline]
+    pub fn vendor(&self) -> Option<&CStr> {
+        self.vendor.as_deref()
+    }
+
+    /// Sets the provider's vendor.
+    ///
+    /// Passing an empty string as the `vendor` parameter will mark it as unset, making
+    /// [`vendor`](ProviderDescriptor::vendor) then return `None`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the given vendor contains NULL-byte characters, which are
+    /// invalid.
+    pub fn with_vendor(mut self, vendor: &str) -> Self {
+        if vendor.is_empty() {
+            self.raw_descriptor.vendor = core::ptr::null();
+            self.vendor = None;
+        } else {
+            let vendor = Pin::new(
+                CString::new(vendor)
+                    .expect("Invalid Provider Vendor")
+                    .into_boxed_c_str(),
+            );
+
+            self.raw_descriptor.vendor = vendor.as_ptr();
+            self.vendor = Some(vendor);
+        }
+
+        self
+    }
+
+    /// Returns this descriptor as a raw, C-FFI compatible provider descriptor.
+    #[inline]
+    pub fn as_raw(&self) -> &clap_preset_discovery_provider_descriptor {
+        &self.raw_descriptor
+    }
+}
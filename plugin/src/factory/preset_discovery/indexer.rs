@@ -0,0 +1,179 @@
+use crate::factory::preset_discovery::{FileType, LocationInfo, Soundpack};
+use crate::utils::ClapVersion;
+use clack_common::utils::cstr_from_nullable_ptr;
+use clap_sys::factory::draft::preset_discovery::clap_preset_discovery_indexer;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+/// Information about the host's preset indexer.
+///
+/// This is given to the [`PresetDiscoveryFactory`](super::PresetDiscoveryFactory) when a provider
+/// is created. The indexer itself can only be used once the provider is being initialized, through
+/// the [`Indexer`] handle.
+#[derive(Copy, Clone)]
+pub struct IndexerInfo<'a> {
+    raw: NonNull<clap_preset_discovery_indexer>,
+    _lifetime: PhantomData<&'a clap_preset_discovery_indexer>,
+}
+
+impl<'a> IndexerInfo<'a> {
+    /// # Safety
+    /// The indexer pointer must be valid for the lifetime `'a`.
+    #[inline]
+    pub(crate) unsafe fn from_raw(raw: NonNull<clap_preset_discovery_indexer>) -> Self {
+        Self {
+            raw,
+            _lifetime: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn raw(&self) -> &'a clap_preset_discovery_indexer {
+        // SAFETY: this type guarantees the indexer pointer is valid for 'a.
+        unsafe { self.raw.as_ref() }
+    }
+
+    /// The CLAP version the host's indexer implements.
+    #[inline]
+    pub fn clap_version(&self) -> ClapVersion {
+        ClapVersion::from_raw(self.raw().clap_version)
+    }
+
+    /// The name of the indexer, or [`None`] if the host did not provide it.
+    #[inline]
+    pub fn name(&self) -> Option<&'a CStr> {
+        // SAFETY: this type guarantees the indexer pointer (and its strings) are valid for 'a.
+        unsafe { cstr_from_nullable_ptr(self.raw().name) }
+    }
+
+    /// The vendor of the indexer, or [`None`] if the host did not provide it.
+    #[inline]
+    pub fn vendor(&self) -> Option<&'a CStr> {
+        // SAFETY: this type guarantees the indexer pointer (and its strings) are valid for 'a.
+        unsafe { cstr_from_nullable_ptr(self.raw().vendor) }
+    }
+
+    /// The URL of the indexer's homepage, or [`None`] if the host did not provide it.
+    #[inline]
+    pub fn url(&self) -> Option<&'a CStr> {
+        // SAFETY: this type guarantees the indexer pointer (and its strings) are valid for 'a.
+        unsafe { cstr_from_nullable_ptr(self.raw().url) }
+    }
+
+    /// The version of the indexer, or [`None`] if the host did not provide it.
+    #[inline]
+    pub fn version(&self) -> Option<&'a CStr> {
+        // SAFETY: this type guarantees the indexer pointer (and its strings) are valid for 'a.
+        unsafe { cstr_from_nullable_ptr(self.raw().version) }
+    }
+}
+
+/// A handle to the host's preset indexer.
+///
+/// This is given to a preset provider as it is being initialized, allowing it to declare the
+/// file types, locations and soundpacks it supports to the host.
+pub struct Indexer<'a> {
+    info: IndexerInfo<'a>,
+}
+
+impl<'a> Indexer<'a> {
+    #[inline]
+    pub(crate) fn new(info: IndexerInfo<'a>) -> Self {
+        Self { info }
+    }
+
+    /// Returns information about the host's indexer.
+    #[inline]
+    pub fn info(&self) -> IndexerInfo<'a> {
+        self.info
+    }
+
+    /// Declares a preset file type to the host.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`IndexerError::DeclareFileTypeError`] if the host rejected the file type.
+    pub fn declare_filetype(&mut self, file_type: FileType) -> Result<(), IndexerError> {
+        let raw = self.info.raw();
+        let declare_filetype = raw
+            .declare_filetype
+            .ok_or(IndexerError::DeclareFileTypeError)?;
+
+        let file_type = file_type.to_raw();
+
+        // SAFETY: This type ensures the function pointer is valid. The raw file type only borrows
+        // strings that are valid for the duration of the call.
+        match unsafe { declare_filetype(raw, &file_type) } {
+            true => Ok(()),
+            false => Err(IndexerError::DeclareFileTypeError),
+        }
+    }
+
+    /// Declares a preset location to the host.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`IndexerError::DeclareLocationError`] if the host rejected the location.
+    pub fn declare_location(&mut self, location: LocationInfo) -> Result<(), IndexerError> {
+        let raw = self.info.raw();
+        let declare_location = raw
+            .declare_location
+            .ok_or(IndexerError::DeclareLocationError)?;
+
+        let location = location.to_raw();
+
+        // SAFETY: This type ensures the function pointer is valid. The raw location only borrows
+        // strings that are valid for the duration of the call.
+        match unsafe { declare_location(raw, &location) } {
+            true => Ok(()),
+            false => Err(IndexerError::DeclareLocationError),
+        }
+    }
+
+    /// Declares a soundpack to the host.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`IndexerError::DeclareSoundpackError`] if the host rejected the soundpack.
+    pub fn declare_soundpack(&mut self, soundpack: Soundpack) -> Result<(), IndexerError> {
+        let raw = self.info.raw();
+        let declare_soundpack = raw
+            .declare_soundpack
+            .ok_or(IndexerError::DeclareSoundpackError)?;
+
+        let soundpack = soundpack.to_raw();
+
+        // SAFETY: This type ensures the function pointer is valid. The raw soundpack only borrows
+        // strings that are valid for the duration of the call.
+        match unsafe { declare_soundpack(raw, &soundpack) } {
+            true => Ok(()),
+            false => Err(IndexerError::DeclareSoundpackError),
+        }
+    }
+}
+
+/// Errors that can occur when declaring data to the host's [`Indexer`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IndexerError {
+    /// The host rejected the declared file type.
+    DeclareFileTypeError,
+    /// The host rejected the declared location.
+    DeclareLocationError,
+    /// The host rejected the declared soundpack.
+    DeclareSoundpackError,
+}
+
+impl Display for IndexerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexerError::DeclareFileTypeError => f.write_str("Failed to declare preset file type"),
+            IndexerError::DeclareLocationError => f.write_str("Failed to declare preset location"),
+            IndexerError::DeclareSoundpackError => f.write_str("Failed to declare soundpack"),
+        }
+    }
+}
+
+impl Error for IndexerError {}
@@ -0,0 +1,207 @@
+use crate::factory::preset_discovery::{Flags, PluginId, Timestamp};
+use clap_sys::factory::draft::preset_discovery::clap_preset_discovery_metadata_receiver;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+/// A handle to the host's metadata receiver.
+///
+/// This is given to a [`PresetDiscoveryProvider`](super::PresetDiscoveryProvider) when the host
+/// requests the metadata of a given location. The provider must call
+/// [`begin_preset`](MetadataReceiver::begin_preset) for each preset it finds in the location,
+/// followed by any number of calls to the other methods of this type to describe that preset.
+pub struct MetadataReceiver<'a> {
+    raw: NonNull<clap_preset_discovery_metadata_receiver>,
+    _lifetime: PhantomData<&'a clap_preset_discovery_metadata_receiver>,
+}
+
+impl<'a> MetadataReceiver<'a> {
+    /// # Safety
+    /// The receiver pointer must be valid for the lifetime `'a`.
+    #[inline]
+    pub(crate) unsafe fn from_raw(raw: NonNull<clap_preset_discovery_metadata_receiver>) -> Self {
+        Self {
+            raw,
+            _lifetime: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn raw(&self) -> &'a clap_preset_discovery_metadata_receiver {
+        // SAFETY: this type guarantees the receiver pointer is valid for 'a.
+        unsafe { self.raw.as_ref() }
+    }
+
+    /// Reports an error to the host, e.g. if a preset file could not be read.
+    ///
+    /// The `os_error` is the operating system error code, if applicable, or `0` otherwise.
+    pub fn on_error(&mut self, os_error: i32, error_message: &CStr) {
+        let raw = self.raw();
+        if let Some(on_error) = raw.on_error {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { on_error(raw, os_error, error_message.as_ptr()) }
+        }
+    }
+
+    /// Starts describing a new preset.
+    ///
+    /// If the location contains a single preset, `name` may be [`None`], in which case the host
+    /// will derive it from the location itself (e.g. from the file name). Likewise, the `load_key`
+    /// is only required if the location contains multiple presets, and is then given back to the
+    /// plugin when loading the preset.
+    ///
+    /// All subsequent calls to the other methods of this receiver apply to this preset, until
+    /// this method is called again.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`MetadataReceiverError`] if the host does not want any more metadata. The
+    /// provider must then stop calling this receiver.
+    pub fn begin_preset(
+        &mut self,
+        name: Option<&CStr>,
+        load_key: Option<&CStr>,
+    ) -> Result<&mut Self, MetadataReceiverError> {
+        let raw = self.raw();
+        let begin_preset = raw.begin_preset.ok_or(MetadataReceiverError)?;
+
+        // SAFETY: This type ensures the function pointer is valid.
+        let success = unsafe {
+            begin_preset(
+                raw,
+                name.map_or(core::ptr::null(), CStr::as_ptr),
+                load_key.map_or(core::ptr::null(), CStr::as_ptr),
+            )
+        };
+
+        match success {
+            true => Ok(self),
+            false => Err(MetadataReceiverError),
+        }
+    }
+
+    /// Adds the identifier of a plugin this preset can be loaded into.
+    pub fn add_plugin_id(&mut self, plugin_id: PluginId) -> &mut Self {
+        let raw = self.raw();
+        if let Some(add_plugin_id) = raw.add_plugin_id {
+            let plugin_id = plugin_id.to_raw();
+
+            // SAFETY: This type ensures the function pointer is valid. The raw plugin ID only
+            // borrows strings that are valid for the duration of the call.
+            unsafe { add_plugin_id(raw, &plugin_id) }
+        }
+
+        self
+    }
+
+    /// Sets the identifier of the soundpack this preset belongs to.
+    ///
+    /// The soundpack must have been declared to the
+    /// [`Indexer`](super::Indexer) beforehand.
+    pub fn set_soundpack_id(&mut self, soundpack_id: &CStr) -> &mut Self {
+        let raw = self.raw();
+        if let Some(set_soundpack_id) = raw.set_soundpack_id {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { set_soundpack_id(raw, soundpack_id.as_ptr()) }
+        }
+
+        self
+    }
+
+    /// Sets the flags of this preset.
+    ///
+    /// If this is not called, the preset inherits the flags of its location.
+    pub fn set_flags(&mut self, flags: Flags) -> &mut Self {
+        let raw = self.raw();
+        if let Some(set_flags) = raw.set_flags {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { set_flags(raw, flags.bits()) }
+        }
+
+        self
+    }
+
+    /// Adds a creator name to this preset.
+    pub fn add_creator(&mut self, creator: &CStr) -> &mut Self {
+        let raw = self.raw();
+        if let Some(add_creator) = raw.add_creator {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { add_creator(raw, creator.as_ptr()) }
+        }
+
+        self
+    }
+
+    /// Sets the description of this preset.
+    pub fn set_description(&mut self, description: &CStr) -> &mut Self {
+        let raw = self.raw();
+        if let Some(set_description) = raw.set_description {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { set_description(raw, description.as_ptr()) }
+        }
+
+        self
+    }
+
+    /// Sets the creation and last modification times of this preset.
+    ///
+    /// Either timestamp may be [`None`] if it is unknown.
+    pub fn set_timestamps(
+        &mut self,
+        creation_time: Option<Timestamp>,
+        modification_time: Option<Timestamp>,
+    ) -> &mut Self {
+        let raw = self.raw();
+        if let Some(set_timestamps) = raw.set_timestamps {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe {
+                set_timestamps(
+                    raw,
+                    Timestamp::optional_to_raw(creation_time),
+                    Timestamp::optional_to_raw(modification_time),
+                )
+            }
+        }
+
+        self
+    }
+
+    /// Adds a feature to this preset.
+    ///
+    /// Features are the same as the ones used in plugin descriptors, e.g. `"lead"` or `"pad"`.
+    pub fn add_feature(&mut self, feature: &CStr) -> &mut Self {
+        let raw = self.raw();
+        if let Some(add_feature) = raw.add_feature {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { add_feature(raw, feature.as_ptr()) }
+        }
+
+        self
+    }
+
+    /// Adds extra, free-form information to this preset, as a key-value pair.
+    pub fn add_extra_info(&mut self, key: &CStr, value: &CStr) -> &mut Self {
+        let raw = self.raw();
+        if let Some(add_extra_info) = raw.add_extra_info {
+            // SAFETY: This type ensures the function pointer is valid.
+            unsafe { add_extra_info(raw, key.as_ptr(), value.as_ptr()) }
+        }
+
+        self
+    }
+}
+
+/// An error returned by the host's [`MetadataReceiver`] when it does not want to receive any more
+/// presets.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MetadataReceiverError;
+
+impl Display for MetadataReceiverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Host's metadata receiver declined the preset")
+    }
+}
+
+impl Error for MetadataReceiverError {}
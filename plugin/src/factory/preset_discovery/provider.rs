@@ -0,0 +1,289 @@
+use crate::extensions::wrapper::handle_panic;
+use crate::factory::preset_discovery::{
+    Indexer, IndexerInfo, Location, MetadataReceiver, ProviderDescriptor,
+};
+use crate::plugin::PluginError;
+use clap_sys::factory::draft::preset_discovery::{
+    clap_preset_discovery_location_kind, clap_preset_discovery_metadata_receiver,
+    clap_preset_discovery_provider,
+};
+use std::error::Error;
+use std::ffi::{c_char, c_void};
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::panic::AssertUnwindSafe;
+use std::ptr::NonNull;
+
+/// A preset provider implementation.
+///
+/// Preset providers are created by the [`PresetDiscoveryFactory`](super::PresetDiscoveryFactory),
+/// using the [`ProviderInstance::new`] method. Once initialized, the host can request the
+/// metadata of all the presets contained in the locations the provider declared.
+pub trait PresetDiscoveryProvider {
+    /// Reads the metadata of all the presets present at the given location, and describes them
+    /// to the host through the given [`MetadataReceiver`].
+    ///
+    /// If the location is a file containing a single preset, the preset's name may be omitted
+    /// when calling [`begin_preset`](MetadataReceiver::begin_preset): the host will then derive
+    /// it from the file name.
+    ///
+    /// # Errors
+    ///
+    /// This may return an error if the metadata could not be read (e.g. if the location doesn't
+    /// exist, or is invalid). Specific errors can also be reported to the host beforehand using
+    /// [`MetadataReceiver::on_error`].
+    fn get_metadata(
+        &mut self,
+        location: Location,
+        receiver: &mut MetadataReceiver,
+    ) -> Result<(), PluginError>;
+}
+
+/// A wrapper around a [`PresetDiscoveryProvider`] instance.
+///
+/// This type is created with its [`new`](ProviderInstance::new) method when the host wants to
+/// create a given provider, and is what needs to be returned by the
+/// [`PresetDiscoveryFactory::create_provider`](super::PresetDiscoveryFactory::create_provider)
+/// method.
+pub struct ProviderInstance<'a> {
+    raw: NonNull<clap_preset_discovery_provider>,
+    _lifetime: PhantomData<&'a ProviderDescriptor>,
+}
+
+impl<'a> ProviderInstance<'a> {
+    /// Creates a new provider instance of a given implementation `P`.
+    ///
+    /// The given `initializer` is not called immediately: it is only called once the host
+    /// initializes the provider, at which point the provider can use the given [`Indexer`] to
+    /// declare its file types, locations and soundpacks.
+    ///
+    /// See the [`PresetDiscoveryFactory`](super::PresetDiscoveryFactory)'s trait documentation for
+    /// a usage example.
+    pub fn new<P: PresetDiscoveryProvider + 'a>(
+        indexer: IndexerInfo<'a>,
+        descriptor: &'a ProviderDescriptor,
+        initializer: impl FnOnce(&mut Indexer<'a>) -> Result<P, PluginError> + 'a,
+    ) -> Self {
+        ProviderData::new_instance(indexer, descriptor, initializer)
+    }
+
+    #[inline]
+    pub(crate) fn into_owned_ptr(self) -> *const clap_preset_discovery_provider {
+        ManuallyDrop::new(self).raw.as_ptr()
+    }
+}
+
+impl Drop for ProviderInstance<'_> {
+    fn drop(&mut self) {
+        // SAFETY: the raw provider was created by ProviderData::new_instance, and is still owned
+        // by this instance.
+        unsafe {
+            if let Some(destroy) = self.raw.as_ref().destroy {
+                destroy(self.raw.as_ptr())
+            }
+        }
+    }
+}
+
+enum ProviderState<P, F> {
+    Uninitialized(F),
+    Initialized(P),
+    InitializationFailed,
+}
+
+struct ProviderData<'a, P, F> {
+    indexer: Indexer<'a>,
+    state: ProviderState<P, F>,
+}
+
+impl<'a, P, F> ProviderData<'a, P, F>
+where
+    P: PresetDiscoveryProvider + 'a,
+    F: FnOnce(&mut Indexer<'a>) -> Result<P, PluginError> + 'a,
+{
+    fn new_instance(
+        indexer: IndexerInfo<'a>,
+        descriptor: &'a ProviderDescriptor,
+        initializer: F,
+    ) -> ProviderInstance<'a> {
+        let data = Box::new(Self {
+            indexer: Indexer::new(indexer),
+            state: ProviderState::Uninitialized(initializer),
+        });
+
+        let raw = Box::new(clap_preset_discovery_provider {
+            desc: descriptor.as_raw(),
+            provider_data: Box::into_raw(data).cast(),
+            init: Some(Self::init),
+            destroy: Some(Self::destroy),
+            get_metadata: Some(Self::get_metadata),
+            get_extension: Some(Self::get_extension),
+        });
+
+        ProviderInstance {
+            // SAFETY: pointers from Box::into_raw are never null.
+            raw: unsafe { NonNull::new_unchecked(Box::into_raw(raw)) },
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// # Safety
+    /// The provider pointer must be valid (but it can be null), and must have been created by
+    /// [`ProviderData::new_instance`] with the same type parameters.
+    unsafe fn handle<T>(
+        provider: *const clap_preset_discovery_provider,
+        handler: impl FnOnce(&mut Self) -> Result<T, ProviderError>,
+    ) -> Option<T> {
+        let data = Self::from_raw(provider);
+        let result = data.and_then(
+            |data| match handle_panic(AssertUnwindSafe(|| handler(data))) {
+                Err(_) => Err(ProviderError::Panic),
+                Ok(Err(e)) => Err(e),
+                Ok(Ok(val)) => Ok(val),
+            },
+        );
+
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("[CLAP_PRESET_DISCOVERY_PROVIDER_ERROR] {e}");
+
+                None
+            }
+        }
+    }
+
+    /// # Safety
+    /// Same as [`Self::handle`].
+    unsafe fn from_raw<'p>(
+        provider: *const clap_preset_discovery_provider,
+    ) -> Result<&'p mut Self, ProviderError> {
+        provider
+            .as_ref()
+            .and_then(|provider| (provider.provider_data as *mut Self).as_mut())
+            .ok_or(ProviderError::NullProviderInstance)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn init(provider: *const clap_preset_discovery_provider) -> bool {
+        Self::handle(provider, |data| {
+            let initializer =
+                match core::mem::replace(&mut data.state, ProviderState::InitializationFailed) {
+                    ProviderState::Uninitialized(initializer) => initializer,
+                    ProviderState::Initialized(provider) => {
+                        data.state = ProviderState::Initialized(provider);
+                        return Err(ProviderError::AlreadyInitialized);
+                    }
+                    ProviderState::InitializationFailed => {
+                        return Err(ProviderError::InitializationAlreadyFailed)
+                    }
+                };
+
+            let provider = initializer(&mut data.indexer).map_err(ProviderError::Plugin)?;
+            data.state = ProviderState::Initialized(provider);
+
+            Ok(())
+        })
+        .is_some()
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn destroy(provider: *const clap_preset_discovery_provider) {
+        if provider.is_null() {
+            return;
+        }
+
+        let provider = Box::from_raw(provider as *mut clap_preset_discovery_provider);
+        let data = provider.provider_data as *mut Self;
+
+        if !data.is_null() {
+            let data = Box::from_raw(data);
+            if handle_panic(AssertUnwindSafe(|| drop(data))).is_err() {
+                eprintln!(
+                    "[CLAP_PRESET_DISCOVERY_PROVIDER_ERROR] {}",
+                    ProviderError::Panic
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_metadata(
+        provider: *const clap_preset_discovery_provider,
+        location_kind: clap_preset_discovery_location_kind,
+        location: *const c_char,
+        metadata_receiver: *const clap_preset_discovery_metadata_receiver,
+    ) -> bool {
+        Self::handle(provider, |data| {
+            let ProviderState::Initialized(provider) = &mut data.state else {
+                return Err(ProviderError::UninitializedProvider);
+            };
+
+            let location = Location::from_raw(location_kind, location)
+                .ok_or(ProviderError::InvalidLocation)?;
+            let metadata_receiver = NonNull::new(metadata_receiver as *mut _).ok_or(
+                ProviderError::NulPtr("clap_preset_discovery_metadata_receiver"),
+            )?;
+
+            let mut receiver = MetadataReceiver::from_raw(metadata_receiver);
+            provider
+                .get_metadata(location, &mut receiver)
+                .map_err(ProviderError::Plugin)
+        })
+        .is_some()
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_extension(
+        _provider: *const clap_preset_discovery_provider,
+        _extension_id: *const c_char,
+    ) -> *const c_void {
+        // There are no standard preset provider extensions yet.
+        core::ptr::null()
+    }
+}
+
+#[derive(Debug)]
+enum ProviderError {
+    NullProviderInstance,
+    NulPtr(&'static str),
+    UninitializedProvider,
+    AlreadyInitialized,
+    InitializationAlreadyFailed,
+    InvalidLocation,
+    Plugin(PluginError),
+    Panic,
+}
+
+impl Display for ProviderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProviderError::NullProviderInstance => f.write_str(
+                "Preset provider method was called with null clap_preset_discovery_provider pointer",
+            ),
+            ProviderError::NulPtr(ptr_name) => {
+                write!(
+                    f,
+                    "Preset provider method was called with null {ptr_name} pointer"
+                )
+            }
+            ProviderError::UninitializedProvider => {
+                f.write_str("Preset provider was used before being initialized")
+            }
+            ProviderError::AlreadyInitialized => {
+                f.write_str("Preset provider was initialized twice")
+            }
+            ProviderError::InitializationAlreadyFailed => {
+                f.write_str("Preset provider initialization was retried after it failed")
+            }
+            ProviderError::InvalidLocation => {
+                f.write_str("Preset provider was given an invalid location")
+            }
+            ProviderError::Plugin(e) => write!(f, "Preset provider error: {e}"),
+            ProviderError::Panic => f.write_str("Preset provider panicked"),
+        }
+    }
+}
+
+impl Error for ProviderError {}